@@ -1,50 +1,86 @@
 use std::fmt;
 
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 use smplc_ast::Span;
 
+use crate::files::Files;
+
 struct Error<'source, K: fmt::Display> {
     pub filename: &'source str,
     pub code: &'source str,
 
+    pub level: Level,
     pub span: Span,
     pub kind: K,
 }
 
-pub fn output_error<'source>(
-    filename: &'source str,
-    code: &'source str,
-    span: Span,
-    kind: impl fmt::Display,
-) {
+#[derive(Clone, Copy)]
+enum Level {
+    Error,
+    Warning,
+}
+
+/// reports the error in the file the span points to
+pub fn output_error(files: &Files, span: Span, kind: impl fmt::Display) {
+    output(files, Level::Error, span, kind);
+}
+
+/// reports the warning like an error, but in yellow
+pub fn output_warning(files: &Files, span: Span, kind: impl fmt::Display) {
+    output(files, Level::Warning, span, kind);
+}
+
+fn output(files: &Files, level: Level, span: Span, kind: impl fmt::Display) {
+    let file = files.get(span.file());
+
     eprintln!(
         "{}",
         Error {
-            filename,
-            code,
+            filename: &file.name,
+            code: file.code,
+            level,
             span,
             kind,
         }
     );
 }
 
+impl Level {
+    fn paint(self, text: &str) -> ColoredString {
+        match self {
+            Self::Error => text.red(),
+            Self::Warning => text.yellow(),
+        }
+    }
+}
+
 impl<K: fmt::Display> fmt::Display for Error<'_, K> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}:{}: ", self.filename, self.span)?;
 
-        writeln!(f, "{} {}", "Error:".red(), self.kind)?;
+        let label = match self.level {
+            Level::Error => "Error:",
+            Level::Warning => "Warning:",
+        };
+
+        writeln!(f, "{} {}", self.level.paint(label), self.kind)?;
 
         let column_length = self.span.start().line().ilog10() as usize + 1;
+        let line = self.get_line();
 
         write!(f, " {} | ", self.span.start().line())?;
-        writeln!(f, "{}", self.get_line())?;
+        writeln!(f, "{line}")?;
+
+        // the span of a statement can take several lines, only the first one is underlined
+        let column = self.span.start().column() - 1;
+        let len = self.span.len().min(line.len().saturating_sub(column));
 
         write!(f, " {} | ", " ".repeat(column_length))?;
         write!(
             f,
             "{}{}",
-            " ".repeat(self.span.start().column() - 1),
-            "^".repeat(self.span.len()).red()
+            " ".repeat(column),
+            self.level.paint(&"^".repeat(len))
         )
     }
 }
@@ -0,0 +1,151 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use smplc_ast::{Declaration, FileId, Span};
+use smplc_lexer::Lexer;
+use smplc_parse::{parse, TokenStream};
+
+use crate::errors::output_error;
+
+pub struct SourceFile {
+    /// the path from the command, imported paths are joined with the importer's directory
+    pub name: String,
+    pub code: &'static str,
+
+    canonical: PathBuf,
+}
+
+/// all files of the program, `FileId` is the index of a file
+#[derive(Default)]
+pub struct Files {
+    files: Vec<SourceFile>,
+}
+
+impl Files {
+    pub fn get(&self, file: FileId) -> &SourceFile {
+        &self.files[file.0]
+    }
+
+    /// loads the file with everything it imports,
+    /// the declarations of an imported file come before the importer's ones
+    pub fn load(&mut self, path: &str) -> Result<Vec<Declaration<'static>>, ()> {
+        let mut declarations = Vec::new();
+
+        self.load_file(Path::new(path), None, &mut Vec::new(), &mut declarations)?;
+
+        Ok(declarations)
+    }
+
+    fn load_file(
+        &mut self,
+        path: &Path,
+        import: Option<Span>,
+        importers: &mut Vec<FileId>,
+        declarations: &mut Vec<Declaration<'static>>,
+    ) -> Result<(), ()> {
+        let name = path.display().to_string();
+
+        let read = fs::canonicalize(path)
+            .and_then(|canonical| fs::read_to_string(&canonical).map(|code| (canonical, code)));
+
+        let (canonical, code) = match read {
+            Ok(file) => file,
+            Err(err) => {
+                self.output(import, ImportError::Read { name, err });
+
+                return Err(());
+            }
+        };
+
+        let is_current = |&file: &FileId| self.get(file).canonical == canonical;
+
+        if let Some(start) = importers.iter().position(is_current) {
+            let mut cycle = importers[start..]
+                .iter()
+                .map(|&file| self.get(file).name.clone())
+                .collect::<Vec<_>>();
+
+            cycle.push(name);
+
+            self.output(import, ImportError::Cycle(cycle));
+
+            return Err(());
+        }
+
+        // every file is merged once, even if it's imported several times
+        if self.files.iter().any(|file| file.canonical == canonical) {
+            return Ok(());
+        }
+
+        let file = FileId(self.files.len());
+
+        // the declarations borrow the code until the end of the compilation
+        let code: &'static str = Box::leak(code.into_boxed_str());
+
+        self.files.push(SourceFile {
+            name,
+            code,
+            canonical,
+        });
+
+        let parsed = parse(TokenStream::new(Lexer::in_file(code, file)));
+
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(errors) => {
+                for error in errors {
+                    output_error(self, error.span, error.kind);
+                }
+
+                return Err(());
+            }
+        };
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+
+        importers.push(file);
+
+        let mut own = Vec::new();
+
+        for declaration in parsed {
+            match declaration {
+                Declaration::Import(import) => {
+                    let path = dir.join(import.path.0);
+
+                    self.load_file(&path, Some(import.path.1), importers, declarations)?;
+                }
+
+                declaration => own.push(declaration),
+            }
+        }
+
+        importers.pop();
+
+        declarations.extend(own);
+
+        Ok(())
+    }
+
+    fn output(&self, import: Option<Span>, error: ImportError) {
+        match import {
+            Some(span) => output_error(self, span, error),
+            None => eprintln!("{error}"),
+        }
+    }
+}
+
+enum ImportError {
+    Read { name: String, err: io::Error },
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Read { name, err } => write!(f, "can't read \"{name}\": {err}"),
+            ImportError::Cycle(files) => write!(f, "import cycle: {}", files.join(" -> ")),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use comet_ir::Value;
+use smplc_ast::{ConstantDeclaration, Declaration, Spanned, Type};
+use smplc_semcheck::{sem_check, LenEval};
+use smplc_translate::constant_values;
+use smplc_typecheck::typecheck;
+
+/// Evaluates the array lengths that aren't literals: a length is the last constant
+/// of a program of all the constants and `const fn`s, so the lengths of their own types
+/// must be literals
+pub fn len_eval<'source>(declarations: &[Declaration<'source>]) -> Box<LenEval<'source>> {
+    let constants = declarations
+        .iter()
+        .filter(|declaration| match declaration {
+            Declaration::Constant(_) => true,
+            Declaration::Function(function) => function.is_const,
+            _ => false,
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    Box::new(move |len| {
+        let mut program = constants.clone();
+
+        program.push(Declaration::Constant(ConstantDeclaration {
+            // it can't be the name of a user constant
+            id: Spanned("array length", len.1),
+            ty: Spanned(Type::Named("int"), len.1),
+            value: len.clone(),
+            attributes: Vec::new(),
+        }));
+
+        // the errors of the constants are reported by the check of the whole program
+        let (hir, errors, _) = sem_check(program);
+
+        if !errors.is_empty() {
+            return None;
+        }
+
+        let thir = typecheck(hir).ok()?;
+
+        match constant_values(&thir).ok()?.last()? {
+            &(_, Value::Int(len)) => Some(len.into()),
+            _ => None,
+        }
+    })
+}
@@ -1,17 +1,23 @@
 mod errors;
+mod files;
+mod lengths;
+
+#[cfg(test)]
+mod tests;
 
 use std::{fs, process::Command};
 
 use clap::Parser;
 
 use comet_backend_x86::compile;
-use smplc_lexer::Lexer;
-use smplc_parse::{parse, ParseError, TokenStream};
-use smplc_semcheck::sem_check;
+use smplc_semcheck::sem_check_with_lengths;
+use smplc_semcheck::warning::Lint;
 use smplc_translate::translate;
 use smplc_typecheck::typecheck;
 
-use errors::output_error;
+use errors::{output_error, output_warning};
+use files::Files;
+use lengths::len_eval;
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -22,6 +28,23 @@ struct Args {
 
     #[arg(long, default_value_t = false)]
     show_ir: bool,
+
+    /// reports the lint even if `-A` allows it, `warnings` are all the lints
+    #[arg(short = 'W', value_name = "LINT", value_parser = lints)]
+    warn: Vec<Lints>,
+
+    /// doesn't report the lint, `warnings` are all the lints
+    #[arg(short = 'A', value_name = "LINT", value_parser = lints)]
+    allow: Vec<Lints>,
+}
+
+#[derive(Clone, Debug)]
+struct Lints(Vec<Lint>);
+
+fn lints(name: &str) -> Result<Lints, String> {
+    Lint::named(name)
+        .map(Lints)
+        .ok_or_else(|| format!("unknown lint \"{name}\""))
 }
 
 fn main() {
@@ -29,62 +52,70 @@ fn main() {
         filename,
         output,
         show_ir,
+        warn,
+        allow,
     } = Args::parse();
 
-    let program = match fs::read_to_string(filename.as_str()) {
-        Ok(program) => program,
-        Err(e) => {
-            eprintln!("{e}");
-            return;
-        }
-    };
+    let warned = warn
+        .into_iter()
+        .flat_map(|lints| lints.0)
+        .collect::<Vec<_>>();
 
-    let Ok(asm_code) = generate_asm(&program, &filename, show_ir) else {
+    let allowed = allow
+        .into_iter()
+        .flat_map(|lints| lints.0)
+        .filter(|lint| !warned.contains(lint))
+        .collect::<Vec<_>>();
+
+    let Ok(asm_code) = generate_asm(&filename, show_ir, &allowed) else {
         return;
     };
 
     assembly(asm_code, output);
 }
 
-pub fn generate_asm(code: &str, filename: &str, show_ir: bool) -> Result<String, ()> {
-    let tokens = Lexer::new(code);
-    let token_stream = match TokenStream::new(tokens) {
-        Ok(token_stream) => token_stream,
-        Err(err) => {
-            let err = ParseError::from(err);
-            output_error(filename, code, err.span, err.kind);
-            return Err(());
-        }
-    };
+fn generate_asm(filename: &str, show_ir: bool, allowed: &[Lint]) -> Result<String, ()> {
+    let mut files = Files::default();
 
-    let stmts = match parse(token_stream) {
-        Ok(stmts) => stmts,
-        Err(err) => {
-            output_error(filename, code, err.span, err.kind);
-            return Err(());
-        }
-    };
+    let declarations = files.load(filename)?;
 
-    let hir = match sem_check(stmts) {
-        Ok(stmts) => stmts,
-        Err(err) => {
-            output_error(filename, code, err.span, err.kind);
-            return Err(());
+    // the typecheck ignores the undefined names, so it runs
+    // on a program with semantic errors too, the warnings
+    // are reported only for a correct program
+    let eval_len = len_eval(&declarations);
+    let (hir, sem_errors, warnings) = sem_check_with_lengths(declarations, eval_len);
+
+    for error in &sem_errors {
+        output_error(&files, error.span, &error.kind);
+    }
+
+    if sem_errors.is_empty() {
+        for warning in warnings {
+            if !allowed.contains(&warning.kind.lint()) {
+                output_warning(&files, warning.span, warning.kind);
+            }
         }
-    };
+    }
 
     let thir = match typecheck(hir) {
-        Ok(thir) => thir,
+        Ok(thir) if sem_errors.is_empty() => thir,
+        Ok(_) => return Err(()),
         Err(errors) => {
             for error in errors {
-                output_error(filename, code, error.span, error.kind);
+                output_error(&files, error.span, error.kind);
             }
 
             return Err(());
         }
     };
 
-    let ir_code = translate(thir);
+    let ir_code = match translate(thir) {
+        Ok(ir_code) => ir_code,
+        Err(err) => {
+            output_error(&files, err.span, err.kind);
+            return Err(());
+        }
+    };
 
     if show_ir {
         print!("{ir_code}");
@@ -93,7 +124,7 @@ pub fn generate_asm(code: &str, filename: &str, show_ir: bool) -> Result<String,
     compile(ir_code).map_err(|_| ())
 }
 
-pub fn assembly(assembly: String, output_filename: String) {
+fn assembly(assembly: String, output_filename: String) {
     if let Err(err) = fs::write("./temp.asm", assembly) {
         eprintln!("Error: {err}");
         return;
@@ -108,7 +139,7 @@ pub fn assembly(assembly: String, output_filename: String) {
     }
 
     if let Err(err) = Command::new("gcc")
-        .args(["-no-pie", "temp.o", &format!("-o{output_filename}")])
+        .args(["-no-pie", "temp.o", &format!("-o{output_filename}"), "-lm"])
         .output()
     {
         eprintln!("Error: {err}");
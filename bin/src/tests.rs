@@ -0,0 +1,98 @@
+use smplc_lexer::Lexer;
+use smplc_parse::{parse, TokenStream};
+use smplc_semcheck::{error::SemErrorKind, sem_check_with_lengths};
+use smplc_translate::translate;
+use smplc_typecheck::typecheck;
+
+use crate::lengths::len_eval;
+
+/// the IR of the program or its semantic errors, the array lengths are evaluated
+fn compile(code: &str) -> Result<String, Vec<SemErrorKind<'_>>> {
+    let token_stream = TokenStream::new(Lexer::new(code));
+    let declarations = parse(token_stream).unwrap();
+
+    let eval_len = len_eval(&declarations);
+    let (hir, errors, _) = sem_check_with_lengths(declarations, eval_len);
+
+    if !errors.is_empty() {
+        return Err(errors.into_iter().map(|error| error.kind).collect());
+    }
+
+    let Ok(thir) = typecheck(hir) else {
+        panic!("type errors");
+    };
+
+    let Ok(lir) = translate(thir) else {
+        panic!("const eval error");
+    };
+
+    Ok(lir.to_string())
+}
+
+#[test]
+pub fn const_array_lens() {
+    let code = compile(
+        "
+const N: int = 3;
+
+const fn twice(n: int) -> int {
+    let r = 0;
+    for _ in 0..n { r += 2; }
+    return r;
+}
+
+struct S { xs: [real; N] }
+
+fn sum(a: [int; twice(N)]) -> int {
+    let s = 0;
+    for i in 0..len(a) { s += a[i]; }
+    return s;
+}
+
+fn main() {
+    let a = [1; twice(N)];
+    let b: [int; N + 1] = [0; 4];
+    let s = S { xs: [1.0; N] };
+    printi(sum(a) + len(b));
+    printr(s.xs[2]);
+}
+",
+    )
+    .unwrap();
+
+    assert!(code.contains("call sum agg6 $"));
+    assert!(code.contains("= agg6 {}"));
+    assert!(code.contains("= agg4 {}"));
+    assert!(code.contains("= agg3 {}"));
+    assert!(code.contains("= int 4\n"));
+}
+
+#[test]
+pub fn invalid_const_array_lens() {
+    let errors = compile(
+        "
+static M: int = 2;
+const R: real = 1.5;
+const fn zero() -> int { return 1 / 0; }
+fn main() {
+    let n = 3;
+    let _a: [int; n];
+    let _b = [0; M];
+    let _c = [0; R];
+    let _d = [0; zero()];
+    let _e = [0; 1 - 2];
+    printi(M);
+}
+",
+    )
+    .unwrap_err();
+
+    assert_eq!(errors.len(), 5);
+    assert!(errors
+        .iter()
+        .all(|error| *error == SemErrorKind::InvalidArrayLen));
+
+    let errors = compile("const N: int = 1 << 20; fn main() { let _a = [0; N]; }").unwrap_err();
+
+    assert_eq!(errors, [SemErrorKind::ArrayTooLong(1 << 20)]);
+}
@@ -7,6 +7,9 @@ pub struct Builder {
     code: String,
 
     constants: Vec<SimdConstant>,
+    globals: Vec<SimdConstant>,
+    strings: Vec<String>,
+    jump_tables: Vec<Vec<String>>,
 }
 
 impl Write for Builder {
@@ -38,7 +41,24 @@ section .data
     fmtvec4:  db \"(%f, %f, %f, %f)\", 10, 0
     fmtc:     db \"%f + %fi\", 10, 0
     fmtcim:   db \"%fi\", 10, 0
+    strtrue:  db \"true\", 0
+    strfalse: db \"false\", 0
     zero:     dq 0.0
+    f32one:   dd 1.0
+    f32half:  dd 0.5
+    slerpeps: dd 1.0e-6
+    complexconj: dd 0, 0x80000000, 0, 0
+    quatconj:    dd 0x80000000, 0x80000000, 0x80000000, 0
+    quatsignw:   dd 0, 0, 0, 0x80000000
+    fmtreadi:  db \"%d\", 0
+    fmtreadr1: db \"%f\", 0
+    fmtreadr2: db \"%f %f\", 0
+    fmtreadr3: db \"%f %f %f\", 0
+    fmtreadr4: db \"%f %f %f %f\", 0
+    errread:   db \"error: malformed input\", 10
+    errreadlen equ $ - errread
+    errindex:  db \"error: index out of bounds\", 10
+    errindexlen equ $ - errindex
 "
         )?;
 
@@ -48,6 +68,29 @@ section .data
             }
         }
 
+        if !self.globals.is_empty() {
+            // globals are overwritten with `movaps`
+            writeln!(self.code, "align 16")?;
+
+            for (index, &global) in self.globals.iter().enumerate() {
+                writeln!(self.code, "G{index}: dd {global}")?;
+            }
+        }
+
+        for (index, string) in self.strings.iter().enumerate() {
+            write!(self.code, "LS{index}: db ")?;
+
+            for byte in string.bytes() {
+                write!(self.code, "{byte}, ")?;
+            }
+
+            writeln!(self.code, "0")?;
+        }
+
+        for (index, labels) in self.jump_tables.iter().enumerate() {
+            writeln!(self.code, "LJ{index}: dq {}", labels.join(", "))?;
+        }
+
         Ok(self.code)
     }
 
@@ -65,6 +108,46 @@ section .data
 
         Address::Const(index)
     }
+
+    /// returns the label of the null-terminated string
+    pub fn string(&mut self, string: String) -> String {
+        let index = match self.strings.iter().position(|s| *s == string) {
+            Some(index) => index,
+            None => {
+                self.strings.push(string);
+
+                self.strings.len() - 1
+            }
+        };
+
+        format!("LS{index}")
+    }
+
+    /// returns the label of the table of the labels addresses
+    pub fn jump_table(&mut self, labels: Vec<String>) -> String {
+        self.jump_tables.push(labels);
+
+        format!("LJ{}", self.jump_tables.len() - 1)
+    }
+
+    /// unlike `constant` always adds new constants,
+    /// so the columns are placed one after another
+    pub fn columns(&mut self, columns: Vec<SimdConstant>) -> Address {
+        let index = self.constants.len();
+
+        self.constants.extend(columns);
+
+        Address::Const(index)
+    }
+
+    /// adds a global initialized with `slots`, they are placed one after another
+    pub fn global(&mut self, slots: Vec<SimdConstant>) -> Address {
+        let index = self.globals.len();
+
+        self.globals.extend(slots);
+
+        Address::Global(index)
+    }
 }
 
 #[derive(Clone, Copy, PartialEq)]
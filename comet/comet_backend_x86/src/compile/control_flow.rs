@@ -2,9 +2,12 @@ use std::fmt::Write;
 
 use comet_ir::{BinOp, ControlFlow, Dims, EqOp, RelOp, Type};
 
-use crate::{builder::Builder, env::Env};
+use crate::{
+    builder::Builder,
+    env::{Address, Env, Operand},
+};
 
-use super::{atom, Compile};
+use super::{atom, copy, Compile};
 
 impl Compile for ControlFlow {
     fn compile(self, env: &mut Env, builder: &mut Builder) -> std::fmt::Result {
@@ -78,6 +81,30 @@ impl Compile for ControlFlow {
                 writeln!(builder, "jmp {}", env.labels[&label])?;
             }
 
+            ControlFlow::Switch {
+                value,
+                min,
+                targets,
+                default,
+            } => {
+                let value = atom(env, builder, value);
+
+                let len = targets.len();
+                let table = builder.jump_table(
+                    targets
+                        .into_iter()
+                        .map(|target| env.labels[&target].clone())
+                        .collect(),
+                );
+
+                // the values below `min` wrap around and fail the unsigned comparison too
+                writeln!(builder, "mov eax, {value}")?;
+                writeln!(builder, "sub eax, {min}")?;
+                writeln!(builder, "cmp eax, {len}")?;
+                writeln!(builder, "jae {}", env.labels[&default])?;
+                writeln!(builder, "jmp [{table} + rax*8]")?;
+            }
+
             ControlFlow::Return { value } => {
                 if let Some((ty, operand)) = value {
                     let operand = atom(env, builder, operand);
@@ -89,6 +116,11 @@ impl Compile for ControlFlow {
                         Type::Int => {
                             writeln!(builder, "mov eax, {operand}")?;
                         }
+                        Type::F32x2x2 | Type::F32x3x3 | Type::F32x4x4 | Type::Aggregate(_) => {
+                            let ret_address = Operand::Address(Address::Stack(env.ret_address));
+
+                            copy(builder, ty, operand, ret_address)?;
+                        }
                     }
                 }
 
@@ -0,0 +1,208 @@
+//! Matrices are stored by columns, a column takes a whole slot,
+//! so the element at `row` and `col` is `matrix.offset(col).lane(row)`
+
+use std::fmt::{self, Write};
+
+use comet_ir::{Dims, MatOp};
+
+use crate::{builder::Builder, env::Operand};
+
+pub fn binary(
+    builder: &mut Builder,
+    dims: Dims,
+    op: MatOp,
+    lhs: Operand,
+    rhs: Operand,
+    res: Operand,
+) -> fmt::Result {
+    let n = dims as usize;
+
+    match op {
+        MatOp::Add | MatOp::Sub => {
+            let instr = match op {
+                MatOp::Add => "addps",
+                MatOp::Sub => "subps",
+                _ => unreachable!(),
+            };
+
+            for col in 0..n {
+                writeln!(builder, "movups xmm0, {}", lhs.offset(col))?;
+                writeln!(builder, "movups xmm1, {}", rhs.offset(col))?;
+                writeln!(builder, "{instr} xmm0, xmm1")?;
+                writeln!(builder, "movaps {}, xmm0", res.offset(col))?;
+            }
+        }
+
+        MatOp::ScalarMul | MatOp::ScalarDiv => {
+            let instr = match op {
+                MatOp::ScalarMul => "mulps",
+                MatOp::ScalarDiv => "divps",
+                _ => unreachable!(),
+            };
+
+            writeln!(builder, "movss xmm1, {rhs}")?;
+            writeln!(builder, "shufps xmm1, xmm1, 0b00_00_00_00")?;
+
+            for col in 0..n {
+                writeln!(builder, "movups xmm0, {}", lhs.offset(col))?;
+                writeln!(builder, "{instr} xmm0, xmm1")?;
+                writeln!(builder, "movaps {}, xmm0", res.offset(col))?;
+            }
+        }
+
+        MatOp::MulVec => mul_vec(builder, n, lhs, rhs, res)?,
+
+        MatOp::MulMat => {
+            for col in 0..n {
+                mul_vec(builder, n, lhs, rhs.offset(col), res.offset(col))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `res = mat * vec` is the sum of the columns scaled by the components of `vec`
+fn mul_vec(
+    builder: &mut Builder,
+    n: usize,
+    mat: Operand,
+    vec: Operand,
+    res: Operand,
+) -> fmt::Result {
+    writeln!(builder, "movups xmm1, {vec}")?;
+    writeln!(builder, "xorps xmm0, xmm0")?;
+
+    for col in 0..n {
+        writeln!(builder, "movaps xmm2, xmm1")?;
+        writeln!(builder, "shufps xmm2, xmm2, {}", col * 0b01_01_01_01)?;
+        writeln!(builder, "movups xmm3, {}", mat.offset(col))?;
+        writeln!(builder, "mulps xmm2, xmm3")?;
+        writeln!(builder, "addps xmm0, xmm2")?;
+    }
+
+    writeln!(builder, "movaps {res}, xmm0")
+}
+
+pub fn transpose(builder: &mut Builder, dims: Dims, mat: Operand, res: Operand) -> fmt::Result {
+    let n = dims as usize;
+
+    // the missing columns are zeros, so any matrix is transposed as 4x4
+    for col in 0..4 {
+        if col < n {
+            writeln!(builder, "movups xmm{col}, {}", mat.offset(col))?;
+        } else {
+            writeln!(builder, "xorps xmm{col}, xmm{col}")?;
+        }
+    }
+
+    writeln!(builder, "movaps xmm4, xmm0")?;
+    writeln!(builder, "unpcklps xmm4, xmm1")?; // xmm4 = [a0, b0, a1, b1]
+    writeln!(builder, "movaps xmm5, xmm2")?;
+    writeln!(builder, "unpcklps xmm5, xmm3")?; // xmm5 = [c0, d0, c1, d1]
+    writeln!(builder, "movaps xmm6, xmm0")?;
+    writeln!(builder, "unpckhps xmm6, xmm1")?; // xmm6 = [a2, b2, a3, b3]
+    writeln!(builder, "movaps xmm7, xmm2")?;
+    writeln!(builder, "unpckhps xmm7, xmm3")?; // xmm7 = [c2, d2, c3, d3]
+    writeln!(builder, "movaps xmm0, xmm4")?;
+    writeln!(builder, "movlhps xmm0, xmm5")?; // xmm0 = [a0, b0, c0, d0]
+    writeln!(builder, "movaps xmm1, xmm5")?;
+    writeln!(builder, "movhlps xmm1, xmm4")?; // xmm1 = [a1, b1, c1, d1]
+    writeln!(builder, "movaps xmm2, xmm6")?;
+    writeln!(builder, "movlhps xmm2, xmm7")?; // xmm2 = [a2, b2, c2, d2]
+    writeln!(builder, "movaps xmm3, xmm7")?;
+    writeln!(builder, "movhlps xmm3, xmm6")?; // xmm3 = [a3, b3, c3, d3]
+
+    for col in 0..n {
+        writeln!(builder, "movaps {}, xmm{col}", res.offset(col))?;
+    }
+
+    Ok(())
+}
+
+pub fn determinant(builder: &mut Builder, dims: Dims, mat: Operand, res: Operand) -> fmt::Result {
+    let all = (0..dims as usize).collect::<Vec<_>>();
+
+    minor(builder, mat, &all, &all, 0)?;
+
+    writeln!(builder, "movss {res}, xmm0")
+}
+
+/// `inverse = adjugate / determinant`
+pub fn inverse(builder: &mut Builder, dims: Dims, mat: Operand, res: Operand) -> fmt::Result {
+    let n = dims as usize;
+    let all = (0..n).collect::<Vec<_>>();
+
+    minor(builder, mat, &all, &all, 0)?;
+
+    let one = builder.constant([1.0]);
+
+    writeln!(builder, "movss xmm8, {one}")?;
+    writeln!(builder, "divss xmm8, xmm0")?; // xmm8 = 1 / det
+    writeln!(builder, "xorps xmm9, xmm9")?;
+    writeln!(builder, "subss xmm9, xmm8")?; // xmm9 = -1 / det
+
+    for row in 0..n {
+        for col in 0..n {
+            let rows = all
+                .iter()
+                .copied()
+                .filter(|&r| r != col)
+                .collect::<Vec<_>>();
+            let cols = all
+                .iter()
+                .copied()
+                .filter(|&c| c != row)
+                .collect::<Vec<_>>();
+
+            minor(builder, mat, &rows, &cols, 0)?;
+
+            let factor = if (row + col) % 2 == 0 { "xmm8" } else { "xmm9" };
+
+            writeln!(builder, "mulss xmm0, {factor}")?;
+            writeln!(builder, "movss {}, xmm0", res.offset(col).lane(row))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Computes the determinant of the submatrix by the cofactor expansion
+/// along its first row. The result is written to `xmm{2 * depth}`,
+/// a nested call uses only registers with greater numbers.
+fn minor(
+    builder: &mut Builder,
+    mat: Operand,
+    rows: &[usize],
+    cols: &[usize],
+    depth: usize,
+) -> fmt::Result {
+    let acc = 2 * depth;
+    let tmp = acc + 1;
+    let sub = acc + 2;
+
+    if let &[col] = cols {
+        return writeln!(builder, "movss xmm{acc}, {}", mat.offset(col).lane(rows[0]));
+    }
+
+    for (n, &col) in cols.iter().enumerate() {
+        let sub_cols = cols
+            .iter()
+            .copied()
+            .filter(|&c| c != col)
+            .collect::<Vec<_>>();
+
+        minor(builder, mat, &rows[1..], &sub_cols, depth + 1)?;
+
+        writeln!(builder, "movss xmm{tmp}, {}", mat.offset(col).lane(rows[0]))?;
+        writeln!(builder, "mulss xmm{tmp}, xmm{sub}")?;
+
+        match n {
+            0 => writeln!(builder, "movss xmm{acc}, xmm{tmp}")?,
+            n if n % 2 == 0 => writeln!(builder, "addss xmm{acc}, xmm{tmp}")?,
+            _ => writeln!(builder, "subss xmm{acc}, xmm{tmp}")?,
+        }
+    }
+
+    Ok(())
+}
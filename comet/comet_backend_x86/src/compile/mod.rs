@@ -1,13 +1,16 @@
 mod control_flow;
+mod matrix;
+mod print;
+mod quat;
 mod sequental;
 
-use std::fmt;
+use std::fmt::{self, Write};
 
 use comet_ir as lir;
 
 use crate::{
-    builder::Builder,
-    env::{Env, Operand},
+    builder::{Builder, SimdConstant},
+    env::{Address, Env, Operand},
 };
 
 pub trait Compile {
@@ -34,5 +37,72 @@ pub fn value(builder: &mut Builder, value: lir::Value) -> Operand {
         lir::Value::F32x4(value) => {
             Operand::Address(builder.constant([value[0], value[1], value[2], value[3]]))
         }
+
+        lir::Value::F32x2x2(value) => Operand::Address(
+            builder.columns(value.column_iter().map(|c| [c[0], c[1]].into()).collect()),
+        ),
+
+        lir::Value::F32x3x3(value) => Operand::Address(
+            builder.columns(
+                value
+                    .column_iter()
+                    .map(|c| [c[0], c[1], c[2]].into())
+                    .collect(),
+            ),
+        ),
+
+        lir::Value::F32x4x4(value) => Operand::Address(
+            builder.columns(
+                value
+                    .column_iter()
+                    .map(|c| [c[0], c[1], c[2], c[3]].into())
+                    .collect(),
+            ),
+        ),
     }
 }
+
+/// lays out the initial value of a global, an int is kept as its bits
+pub fn global(builder: &mut Builder, value: lir::Value) -> Address {
+    let slots: Vec<SimdConstant> = match value {
+        lir::Value::Real(value) => vec![[value].into()],
+        lir::Value::Int(value) => vec![[f32::from_bits(value as u32)].into()],
+        lir::Value::F32x2(value) => vec![[value[0], value[1]].into()],
+        lir::Value::F32x3(value) => vec![[value[0], value[1], value[2]].into()],
+        lir::Value::F32x4(value) => vec![[value[0], value[1], value[2], value[3]].into()],
+        lir::Value::F32x2x2(value) => value.column_iter().map(|c| [c[0], c[1]].into()).collect(),
+
+        lir::Value::F32x3x3(value) => value
+            .column_iter()
+            .map(|c| [c[0], c[1], c[2]].into())
+            .collect(),
+
+        lir::Value::F32x4x4(value) => value
+            .column_iter()
+            .map(|c| [c[0], c[1], c[2], c[3]].into())
+            .collect(),
+    };
+
+    builder.global(slots)
+}
+
+pub fn copy(builder: &mut Builder, ty: lir::Type, src: Operand, dst: Operand) -> fmt::Result {
+    match ty {
+        lir::Type::Int => {
+            writeln!(builder, "mov eax, {src}")?;
+            writeln!(builder, "mov {dst}, eax")?;
+        }
+        lir::Type::Real | lir::Type::F32x2 | lir::Type::F32x3 | lir::Type::F32x4 => {
+            writeln!(builder, "movups xmm0, {src}")?;
+            writeln!(builder, "movaps {dst}, xmm0")?;
+        }
+        lir::Type::F32x2x2 | lir::Type::F32x3x3 | lir::Type::F32x4x4 | lir::Type::Aggregate(_) => {
+            for slot in 0..ty.slots() {
+                writeln!(builder, "movups xmm0, {}", src.offset(slot))?;
+                writeln!(builder, "movaps {}, xmm0", dst.offset(slot))?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+//! `Print` is compiled to a single `printf` call,
+//! the format string is built here and placed in the data section
+
+use std::fmt::{self, Write};
+
+use comet_ir::{Atom, Format};
+
+use crate::{
+    builder::Builder,
+    compile::atom,
+    env::{Env, Lane, Operand},
+    STACK_ALIGN,
+};
+
+/// the integer registers left for the arguments, `rdi` takes the format string
+const INT_REGS: [(&str, &str); 5] = [
+    ("rsi", "esi"),
+    ("rdx", "edx"),
+    ("rcx", "ecx"),
+    ("r8", "r8d"),
+    ("r9", "r9d"),
+];
+
+const FLOAT_REGS: usize = 8;
+
+/// an argument of `printf`
+enum Arg {
+    Int(Operand),
+    /// passed as a pointer to "true" or "false"
+    Bool(Operand),
+    /// f32 converted to f64
+    Float(Lane),
+}
+
+pub fn print(
+    env: &mut Env,
+    builder: &mut Builder,
+    pieces: Vec<String>,
+    args: Vec<(Atom, Format)>,
+) -> fmt::Result {
+    let mut format = String::new();
+    let mut printf_args = Vec::new();
+
+    let mut pieces = pieces.into_iter();
+
+    for (arg, arg_format) in args {
+        format += &pieces.next().unwrap().replace('%', "%%");
+
+        let value = atom(env, builder, arg);
+
+        spec(&mut format, &mut printf_args, value, arg_format);
+    }
+
+    format += &pieces.next().unwrap().replace('%', "%%");
+
+    let format = builder.string(format);
+
+    // SysV: the arguments that don't fit in the registers go to the stack in order
+    let mut int_regs = INT_REGS.iter();
+    let mut float_regs = 0;
+    let mut stack_args: usize = 0;
+
+    let mut code = Vec::new();
+
+    for arg in printf_args {
+        match arg {
+            Arg::Float(lane) if float_regs < FLOAT_REGS => {
+                code.push(format!("cvtss2sd xmm{float_regs}, {lane}"));
+
+                float_regs += 1;
+            }
+
+            Arg::Float(lane) => {
+                code.push(format!("cvtss2sd xmm8, {lane}"));
+                code.push(format!("movsd [rsp+{}], xmm8", 8 * stack_args));
+
+                stack_args += 1;
+            }
+
+            Arg::Int(value) | Arg::Bool(value) => {
+                let (reg, reg32) = int_regs.next().copied().unwrap_or(("r11", "r11d"));
+
+                if let Arg::Bool(_) = arg {
+                    code.push(format!("mov r10d, {value}"));
+                    code.push(format!("lea {reg}, [strfalse]"));
+                    code.push("lea rax, [strtrue]".into());
+                    code.push("test r10d, r10d".into());
+                    code.push(format!("cmovnz {reg}, rax"));
+                } else {
+                    code.push(format!("mov {reg32}, {value}"));
+                }
+
+                if reg == "r11" {
+                    code.push(format!("mov [rsp+{}], r11", 8 * stack_args));
+
+                    stack_args += 1;
+                }
+            }
+        }
+    }
+
+    let stack_slots = (8 * stack_args).div_ceil(STACK_ALIGN as usize);
+    let shift = (env.vars_count() + stack_slots) * STACK_ALIGN as usize;
+
+    writeln!(builder, "sub rsp, {shift}")?;
+
+    for instr in code {
+        writeln!(builder, "{instr}")?;
+    }
+
+    writeln!(builder, "lea rdi, [{format}]")?;
+    writeln!(builder, "mov eax, {float_regs}")?;
+    writeln!(builder, "call printf")?;
+    writeln!(builder, "add rsp, {shift}")
+}
+
+/// writes the printf conversion of the value and collects its arguments
+fn spec(format: &mut String, args: &mut Vec<Arg>, value: Operand, arg_format: Format) {
+    match arg_format {
+        Format::Int => {
+            *format += "%d";
+            args.push(Arg::Int(value));
+        }
+
+        Format::Bool => {
+            *format += "%s";
+            args.push(Arg::Bool(value));
+        }
+
+        Format::Real => {
+            *format += "%f";
+            args.push(Arg::Float(value.lane(0)));
+        }
+
+        Format::Complex => {
+            *format += "%f + %fi";
+            args.extend([Arg::Float(value.lane(0)), Arg::Float(value.lane(1))]);
+        }
+
+        // the real part is the last lane
+        Format::Quat => {
+            *format += "%f + %fi + %fj + %fk";
+            args.extend([3, 0, 1, 2].map(|lane| Arg::Float(value.lane(lane))));
+        }
+
+        Format::F32s(dims) => {
+            let n = dims as usize;
+
+            *format += &floats(n);
+            args.extend((0..n).map(|lane| Arg::Float(value.lane(lane))));
+        }
+
+        // a matrix is printed by rows
+        Format::Mat(dims) => {
+            let n = dims as usize;
+
+            *format += "[";
+
+            for row in 0..n {
+                if row != 0 {
+                    *format += ", ";
+                }
+
+                *format += &floats(n);
+                args.extend((0..n).map(|col| Arg::Float(value.offset(col).lane(row))));
+            }
+
+            *format += "]";
+        }
+    }
+}
+
+/// `(%f, %f, ...)` with `n` conversions
+fn floats(n: usize) -> String {
+    format!("({})", vec!["%f"; n].join(", "))
+}
@@ -0,0 +1,65 @@
+//! Quaternions are stored as `[x, y, z, w]`, where `w` is the real part
+
+use std::fmt::{self, Write};
+
+use crate::{builder::Builder, env::Operand};
+
+pub fn mul(builder: &mut Builder, lhs: Operand, rhs: Operand, res: Operand) -> fmt::Result {
+    writeln!(builder, "movups xmm0, {lhs}")?;
+    writeln!(builder, "movups xmm1, {rhs}")?;
+
+    product(builder)?;
+
+    writeln!(builder, "movaps {res}, xmm0")
+}
+
+/// `lhs * conj(rhs) / |rhs|^2`
+pub fn div(builder: &mut Builder, lhs: Operand, rhs: Operand, res: Operand) -> fmt::Result {
+    writeln!(builder, "movups xmm0, {lhs}")?;
+    writeln!(builder, "movups xmm1, {rhs}")?;
+    writeln!(builder, "movaps xmm2, xmm1")?;
+    writeln!(builder, "mulps  xmm2, xmm2")?;
+    writeln!(builder, "haddps xmm2, xmm2")?;
+    writeln!(builder, "haddps xmm2, xmm2")?; // xmm2 = [|b|^2, |b|^2, |b|^2, |b|^2]
+    writeln!(builder, "movups xmm3, [quatconj]")?;
+    writeln!(builder, "xorps  xmm1, xmm3")?; // xmm1 = [-bx, -by, -bz, bw]
+    writeln!(builder, "divps  xmm1, xmm2")?;
+
+    product(builder)?;
+
+    writeln!(builder, "movaps {res}, xmm0")
+}
+
+/// the Hamilton product of `a = xmm0` and `b = xmm1` to `xmm0`:
+/// `a.wwww * b.xyzw + (a.xyzx * b.wwwx + a.yzxy * b.zxyy) * [1, 1, 1, -1] - a.zxyz * b.yzxz`,
+/// the sign masks are in the data section, because the constants `-0.0` and `0.0` are merged
+fn product(builder: &mut Builder) -> fmt::Result {
+    writeln!(builder, "movaps xmm2, xmm0")?;
+    writeln!(builder, "shufps xmm2, xmm2, 0b11_11_11_11")?; // xmm2 = a.wwww
+    writeln!(builder, "mulps  xmm2, xmm1")?;
+
+    writeln!(builder, "movaps xmm3, xmm0")?;
+    writeln!(builder, "shufps xmm3, xmm3, 0b00_10_01_00")?; // xmm3 = a.xyzx
+    writeln!(builder, "movaps xmm4, xmm1")?;
+    writeln!(builder, "shufps xmm4, xmm4, 0b00_11_11_11")?; // xmm4 = b.wwwx
+    writeln!(builder, "mulps  xmm3, xmm4")?;
+
+    writeln!(builder, "movaps xmm4, xmm0")?;
+    writeln!(builder, "shufps xmm4, xmm4, 0b01_00_10_01")?; // xmm4 = a.yzxy
+    writeln!(builder, "movaps xmm5, xmm1")?;
+    writeln!(builder, "shufps xmm5, xmm5, 0b01_01_00_10")?; // xmm5 = b.zxyy
+    writeln!(builder, "mulps  xmm4, xmm5")?;
+    writeln!(builder, "addps  xmm3, xmm4")?;
+    writeln!(builder, "movups xmm4, [quatsignw]")?;
+    writeln!(builder, "xorps  xmm3, xmm4")?;
+    writeln!(builder, "addps  xmm2, xmm3")?;
+
+    writeln!(builder, "movaps xmm4, xmm0")?;
+    writeln!(builder, "shufps xmm4, xmm4, 0b10_01_00_10")?; // xmm4 = a.zxyz
+    writeln!(builder, "movaps xmm5, xmm1")?;
+    writeln!(builder, "shufps xmm5, xmm5, 0b10_00_10_01")?; // xmm5 = b.yzxz
+    writeln!(builder, "mulps  xmm4, xmm5")?;
+    writeln!(builder, "subps  xmm2, xmm4")?;
+
+    writeln!(builder, "movaps xmm0, xmm2")
+}
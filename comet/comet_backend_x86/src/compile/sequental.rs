@@ -1,11 +1,13 @@
 use std::fmt::{self, Write};
 
-use comet_ir::{ArithmOp, BinOp, Dims, EqOp, F32sOp, Id, RelOp, Sequental, Type, UnOp};
+use comet_ir::{
+    ArithmOp, Atom, BinOp, Conversion, Dims, EqOp, F32sOp, Id, IntOp, RelOp, Sequental, Type, UnOp,
+};
 
 use crate::{
     builder::Builder,
-    compile::atom,
-    env::{Address, Env},
+    compile::{atom, copy, matrix, print, quat},
+    env::{Address, Env, Operand},
     STACK_ALIGN,
 };
 
@@ -17,24 +19,15 @@ impl Compile for Sequental<'_> {
 
         match self {
             Sequental::Assign { dst, ty, value } => {
-                let result_ptr = env.get_or_add(dst);
+                let result_ptr = env.get_or_add(dst, ty);
 
                 let value = atom(env, builder, value);
 
-                match ty {
-                    Type::Int => {
-                        writeln!(builder, "mov eax, {value}")?;
-                        writeln!(builder, "mov {result_ptr}, eax")?;
-                    }
-                    Type::Real | Type::F32x2 | Type::F32x3 | Type::F32x4 => {
-                        writeln!(builder, "movups xmm0, {value}")?;
-                        writeln!(builder, "movaps {result_ptr}, xmm0")?;
-                    }
-                }
+                copy(builder, ty, value, result_ptr)?;
             }
 
             Sequental::Binary { dst, op, lhs, rhs } => {
-                let res = env.get_or_add(dst);
+                let res = env.get_or_add(dst, op.ty());
 
                 let lhs = atom(env, builder, lhs);
                 let rhs = atom(env, builder, rhs);
@@ -55,11 +48,46 @@ impl Compile for Sequental<'_> {
 
                     BinOp::Int(ArithmOp::Div) => {
                         writeln!(builder, "mov eax, {lhs}")?;
+                        writeln!(builder, "cdq")?;
                         writeln!(builder, "mov ebx, {rhs}")?;
                         writeln!(builder, "idiv ebx")?;
                         writeln!(builder, "mov {res}, eax")?;
                     }
 
+                    BinOp::IntOp(IntOp::Rem) => {
+                        writeln!(builder, "mov eax, {lhs}")?;
+                        writeln!(builder, "cdq")?;
+                        writeln!(builder, "mov ebx, {rhs}")?;
+                        writeln!(builder, "idiv ebx")?;
+                        writeln!(builder, "mov {res}, edx")?;
+                    }
+
+                    BinOp::IntOp(op @ (IntOp::And | IntOp::Or | IntOp::Xor)) => {
+                        let instr = match op {
+                            IntOp::And => "and",
+                            IntOp::Or => "or",
+                            IntOp::Xor => "xor",
+                            _ => unreachable!(),
+                        };
+
+                        writeln!(builder, "mov eax, {lhs}")?;
+                        writeln!(builder, "{instr} eax, {rhs}")?;
+                        writeln!(builder, "mov {res}, eax")?;
+                    }
+
+                    BinOp::IntOp(op @ (IntOp::Shl | IntOp::Shr)) => {
+                        let instr = match op {
+                            IntOp::Shl => "shl",
+                            IntOp::Shr => "sar",
+                            _ => unreachable!(),
+                        };
+
+                        writeln!(builder, "mov eax, {lhs}")?;
+                        writeln!(builder, "mov ecx, {rhs}")?;
+                        writeln!(builder, "{instr} eax, cl")?;
+                        writeln!(builder, "mov {res}, eax")?;
+                    }
+
                     BinOp::IntRel(op) => {
                         let cc = match op {
                             RelOp::Eq => "e",
@@ -150,6 +178,39 @@ impl Compile for Sequental<'_> {
                         writeln!(builder, "set{cc} {res}")?;
                     }
 
+                    BinOp::Lane(_) => match rhs {
+                        Operand::Number(lane) => {
+                            writeln!(builder, "movss xmm0, {}", lhs.lane(lane as usize))?;
+                            writeln!(builder, "movss {res}, xmm0")?;
+                        }
+
+                        index => {
+                            let Operand::Address(Address::Stack(base)) = res else {
+                                unreachable!()
+                            };
+
+                            // the vector is copied to the result to index it in place
+                            writeln!(builder, "movups xmm0, {lhs}")?;
+                            writeln!(builder, "movaps {res}, xmm0")?;
+                            writeln!(builder, "movsxd rcx, dword {index}")?;
+                            writeln!(builder, "shl rcx, 2")?;
+                            writeln!(builder, "movss xmm0, {}", Address::Dynamic(base))?;
+                            writeln!(builder, "movss {res}, xmm0")?;
+                        }
+                    },
+
+                    BinOp::Blend(_, swizzle) => {
+                        writeln!(builder, "movups xmm0, {lhs}")?;
+                        writeln!(builder, "movaps {res}, xmm0")?;
+
+                        for (lane, &comp) in swizzle.as_slice().iter().enumerate() {
+                            writeln!(builder, "movss xmm0, {}", rhs.lane(lane))?;
+                            writeln!(builder, "movss {}, xmm0", res.lane(comp as usize))?;
+                        }
+                    }
+
+                    BinOp::Mat(dims, op) => matrix::binary(builder, dims, op, lhs, rhs, res)?,
+
                     BinOp::ComplexMul => {
                         writeln!(builder, "movups xmm0, {lhs}")?;
                         writeln!(builder, "movups xmm1, {rhs}")?;
@@ -179,11 +240,14 @@ impl Compile for Sequental<'_> {
                         writeln!(builder, "divps  xmm0, xmm2")?; // xmm0 = [(ac + bd)/(cc + dd), (bc - ad)/(cc + dd)]
                         writeln!(builder, "movaps {res}, xmm0")?;
                     }
+
+                    BinOp::QuatMul => quat::mul(builder, lhs, rhs, res)?,
+                    BinOp::QuatDiv => quat::div(builder, lhs, rhs, res)?,
                 }
             }
 
             Sequental::Unary { dst, op, operand } => {
-                let result_ptr = env.get_or_add(dst);
+                let result_ptr = env.get_or_add(dst, op.ty());
                 let operand = atom(env, builder, operand);
 
                 match op {
@@ -199,13 +263,68 @@ impl Compile for Sequental<'_> {
                         writeln!(builder, "mov {result_ptr}, eax")?;
                     }
 
+                    UnOp::Convert(Conversion::IntToReal) => {
+                        writeln!(builder, "mov eax, {operand}")?;
+                        writeln!(builder, "cvtsi2ss xmm0, eax")?;
+                        writeln!(builder, "movups {result_ptr}, xmm0")?;
+                    }
+
+                    UnOp::Convert(Conversion::RealToInt) => {
+                        writeln!(builder, "movss xmm0, {operand}")?;
+                        writeln!(builder, "cvttss2si eax, xmm0")?;
+                        writeln!(builder, "mov {result_ptr}, eax")?;
+                    }
+
+                    UnOp::Convert(Conversion::IntToComplex) => {
+                        writeln!(builder, "mov eax, {operand}")?;
+                        writeln!(builder, "xorps xmm0, xmm0")?;
+                        writeln!(builder, "cvtsi2ss xmm0, eax")?;
+                        writeln!(builder, "movups {result_ptr}, xmm0")?;
+                    }
+
+                    UnOp::Convert(Conversion::RealToComplex) => {
+                        // the load zeroes the other lanes
+                        writeln!(builder, "movss xmm0, {operand}")?;
+                        writeln!(builder, "movups {result_ptr}, xmm0")?;
+                    }
+
+                    UnOp::Convert(Conversion::Resize(from, to)) => {
+                        match Ord::min(from as usize, to as usize) {
+                            2 => writeln!(builder, "movsd xmm0, {operand}")?,
+                            3 => {
+                                writeln!(builder, "movsd xmm0, {operand}")?;
+                                writeln!(builder, "movss xmm1, {}", operand.lane(2))?;
+                                writeln!(builder, "movlhps xmm0, xmm1")?;
+                            }
+                            _ => writeln!(builder, "movups xmm0, {operand}")?,
+                        }
+
+                        writeln!(builder, "movups {result_ptr}, xmm0")?;
+                    }
+
+                    UnOp::BitNot => {
+                        writeln!(builder, "mov eax, {operand}")?;
+                        writeln!(builder, "not eax")?;
+                        writeln!(builder, "mov {result_ptr}, eax")?;
+                    }
+
                     UnOp::Neg(Type::F32x2 | Type::F32x3 | Type::F32x4) => {
                         writeln!(builder, "xorps xmm0, xmm0")?;
                         writeln!(builder, "subps xmm0, {operand}")?;
                     }
 
+                    UnOp::Neg(
+                        Type::F32x2x2 | Type::F32x3x3 | Type::F32x4x4 | Type::Aggregate(_),
+                    ) => unreachable!(),
+
+                    UnOp::Transpose(dims) => matrix::transpose(builder, dims, operand, result_ptr)?,
+                    UnOp::Determinant(dims) => {
+                        matrix::determinant(builder, dims, operand, result_ptr)?
+                    }
+                    UnOp::Inverse(dims) => matrix::inverse(builder, dims, operand, result_ptr)?,
+
                     UnOp::Swizzle(swizzle) => {
-                        let comb = swizzle.as_slice().into_iter();
+                        let comb = swizzle.as_slice().iter();
                         let comb_code: usize = comb
                             .enumerate()
                             .map(|(n, &comp)| 4usize.pow(n as u32) * comp as usize)
@@ -219,35 +338,38 @@ impl Compile for Sequental<'_> {
             }
 
             Sequental::Call { dst, fun, args } => {
-                let shift = env.stack_size() + args.len() * STACK_ALIGN as usize;
+                // aggregates are returned through the slots right after the arguments
+                let ret_slots = match fun.ret_ty() {
+                    Some(
+                        ty @ (Type::F32x2x2 | Type::F32x3x3 | Type::F32x4x4 | Type::Aggregate(_)),
+                    ) => ty.slots(),
+                    _ => 0,
+                };
+
+                let args_slots = args.iter().map(|(_, ty)| ty.slots()).sum::<usize>();
+                let frame_slots = env.vars_count() + args_slots + ret_slots;
+                let shift = frame_slots * STACK_ALIGN as usize;
 
-                for (n, (arg, ty)) in args.into_iter().rev().enumerate() {
+                let mut slot = 0;
+
+                for (arg, ty) in args {
                     let value = atom(env, builder, arg);
-                    let address = env.stack_size() + (n + 1) * STACK_ALIGN as usize;
+                    let address = Operand::Address(Address::Stack((frame_slots - slot) as isize));
 
-                    match ty {
-                        Type::Real => {
-                            writeln!(builder, "movups xmm0, {value}")?;
-                            writeln!(builder, "movups [rsp - {address}], xmm0")?;
-                        }
-                        Type::Int => {
-                            writeln!(builder, "mov eax, {value}")?;
-                            writeln!(builder, "mov [rsp - {address}], eax")?;
-                        }
-                        _ => {
-                            writeln!(builder, "movups xmm0, {value}")?;
-                            writeln!(builder, "movaps [rsp - {address}], xmm0")?;
-                        }
-                    }
+                    copy(builder, ty, value, address)?;
+
+                    slot += ty.slots();
                 }
 
+                let ret_address = Address::Stack((env.vars_count() + ret_slots) as isize);
+
                 writeln!(builder, "sub rsp, {shift}")?;
                 writeln!(builder, "call {fun}")?;
                 writeln!(builder, "add rsp, {shift}")?;
 
                 if let Some(dst) = dst {
-                    let result_ptr = env.get_or_add(dst);
                     let ret_ty = fun.ret_ty().unwrap();
+                    let result_ptr = env.get_or_add(dst, ret_ty);
 
                     match ret_ty {
                         Type::Real | Type::F32x2 | Type::F32x3 | Type::F32x4 => {
@@ -256,37 +378,168 @@ impl Compile for Sequental<'_> {
                         Type::Int => {
                             writeln!(builder, "mov {result_ptr}, eax")?;
                         }
+                        Type::F32x2x2 | Type::F32x3x3 | Type::F32x4x4 | Type::Aggregate(_) => {
+                            if result_ptr != Operand::Address(ret_address) {
+                                copy(builder, ret_ty, Operand::Address(ret_address), result_ptr)?;
+                            }
+                        }
                     }
                 }
             }
-        }
 
-        if let Some((dst, ty)) = dst_and_ty {
-            let dst_address = env.get(dst);
+            Sequental::Aggregate { dst, ty, elems } => {
+                let result_ptr = env.get_or_add(dst, ty);
+
+                let mut slot = 0;
+
+                for (elem, ty) in elems {
+                    let value = atom(env, builder, elem);
+
+                    copy(builder, ty, value, result_ptr.offset(slot))?;
+
+                    slot += ty.slots();
+                }
+            }
+
+            Sequental::Load {
+                dst,
+                ty,
+                aggregate,
+                offset,
+            } => {
+                let result_ptr = env.get_or_add(dst, ty);
+                let value = element(env, builder, aggregate, offset)?;
+
+                copy(builder, ty, value, result_ptr)?;
+            }
+
+            Sequental::Store {
+                dst,
+                ty,
+                aggregate,
+                offset,
+                value: (value, value_ty),
+            } => {
+                let result_ptr = env.get_or_add(dst, ty);
+                let aggregate = env.get(aggregate);
+
+                copy(builder, ty, aggregate, result_ptr)?;
+
+                let value = atom(env, builder, value);
+                let place = element(env, builder, dst, offset)?;
+
+                copy(builder, value_ty, value, place)?;
+            }
+
+            Sequental::SetLane {
+                dst,
+                dims,
+                vec,
+                lane,
+                value,
+            } => {
+                let result_ptr = env.get_or_add(dst, dims.ty());
+                let vec = atom(env, builder, vec);
+                let value = atom(env, builder, value);
 
-            if let Some(phi_dst_ptr) = add_phi(dst, env, None) {
-                let phi_dst_ptr = Address::Stack(phi_dst_ptr);
+                writeln!(builder, "movups xmm0, {vec}")?;
+                writeln!(builder, "movaps {result_ptr}, xmm0")?;
+                writeln!(builder, "movss xmm0, {value}")?;
 
-                match ty {
-                    Type::Real | Type::F32x2 | Type::F32x3 | Type::F32x4 => {
-                        writeln!(builder, "movups xmm0, {dst_address}")?;
-                        writeln!(builder, "movaps {phi_dst_ptr}, xmm0")?;
+                match atom(env, builder, lane) {
+                    Operand::Number(lane) => {
+                        writeln!(builder, "movss {}, xmm0", result_ptr.lane(lane as usize))?;
                     }
-                    Type::Int => {
-                        writeln!(builder, "mov eax, {dst_address}")?;
-                        writeln!(builder, "mov {phi_dst_ptr}, eax")?;
+
+                    lane => {
+                        let Operand::Address(Address::Stack(base)) = result_ptr else {
+                            unreachable!()
+                        };
+
+                        writeln!(builder, "movsxd rcx, dword {lane}")?;
+                        writeln!(builder, "shl rcx, 2")?;
+                        writeln!(builder, "movss {}, xmm0", Address::Dynamic(base))?;
                     }
                 }
             }
+
+            Sequental::CheckIndex { index, len } => {
+                let index = atom(env, builder, index);
+
+                // a negative index is a big unsigned one
+                writeln!(builder, "mov eax, {index}")?;
+                writeln!(builder, "cmp eax, {len}")?;
+                writeln!(builder, "jae index_error")?;
+            }
+
+            Sequental::LoadGlobal { dst, ty, global } => {
+                let result_ptr = env.get_or_add(dst, ty);
+                let global = env.global(global);
+
+                copy(builder, ty, global, result_ptr)?;
+            }
+
+            Sequental::StoreGlobal {
+                global,
+                value: (value, ty),
+            } => {
+                let value = atom(env, builder, value);
+                let global = env.global(global);
+
+                copy(builder, ty, value, global)?;
+            }
+
+            Sequental::Print { pieces, args } => {
+                print::print(env, builder, pieces, args)?;
+            }
+        }
+
+        if let Some((dst, ty)) = dst_and_ty {
+            let dst_address = env.get(dst);
+
+            if let Some(phi_dst_ptr) = add_phi(dst, ty, env, None) {
+                let phi_dst_ptr = Operand::Address(Address::Stack(phi_dst_ptr));
+
+                copy(builder, ty, dst_address, phi_dst_ptr)?;
+            }
         }
 
         Ok(())
     }
 }
 
-fn add_phi(dst: Id, env: &mut Env, mut address: Option<isize>) -> Option<isize> {
+/// returns the operand of the value at `offset` slots from the start of `aggregate`
+fn element(
+    env: &mut Env,
+    builder: &mut Builder,
+    aggregate: Id,
+    offset: Atom,
+) -> Result<Operand, fmt::Error> {
+    // the aggregate is either on the stack or a constant
+    let base = env.get(aggregate);
+
+    match atom(env, builder, offset) {
+        Operand::Number(offset) => Ok(base.offset(offset as usize)),
+
+        offset => {
+            let base = match base {
+                Operand::Address(Address::Stack(base)) => Address::Dynamic(base),
+                Operand::Address(Address::Const(n)) => Address::ConstDynamic(n),
+
+                _ => unreachable!(),
+            };
+
+            writeln!(builder, "movsxd rcx, dword {offset}")?;
+            writeln!(builder, "shl rcx, 4")?;
+
+            Ok(Operand::Address(base))
+        }
+    }
+}
+
+fn add_phi(dst: Id, ty: Type, env: &mut Env, mut address: Option<isize>) -> Option<isize> {
     for phi in env.phis {
-        if !phi.branches.iter().any(|&id| id == dst) {
+        if !phi.branches.contains(&dst) {
             continue;
         }
 
@@ -300,12 +553,12 @@ fn add_phi(dst: Id, env: &mut Env, mut address: Option<isize>) -> Option<isize>
                 env.set(phi.dst, address);
             }
             None => {
-                env.get_or_add(phi.dst);
+                env.get_or_add(phi.dst, ty);
                 address = Some(env.addr(phi.dst))
             }
         }
 
-        add_phi(phi.dst, env, address);
+        add_phi(phi.dst, ty, env, address);
     }
 
     address
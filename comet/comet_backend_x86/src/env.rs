@@ -7,7 +7,10 @@ use crate::STACK_ALIGN;
 pub struct Env<'a> {
     pub labels: &'a HashMap<Label, String>,
     pub phis: &'a Vec<Phi>,
+    /// the address where an aggregate return value is written
+    pub ret_address: isize,
     constants: &'a HashMap<ir::Id, Operand>,
+    globals: &'a HashMap<ir::Global, Address>,
 
     addresses: HashMap<ir::Id, isize>,
     vars_count: usize,
@@ -18,11 +21,14 @@ impl<'a> Env<'a> {
         labels: &'a HashMap<Label, String>,
         phis: &'a Vec<Phi>,
         constants: &'a HashMap<ir::Id, Operand>,
+        globals: &'a HashMap<ir::Global, Address>,
     ) -> Self {
         Self {
             labels,
             phis,
+            ret_address: Default::default(),
             constants,
+            globals,
             addresses: Default::default(),
             vars_count: Default::default(),
         }
@@ -36,6 +42,10 @@ impl<'a> Env<'a> {
         Operand::Address(Address::Stack(self.addresses[&id]))
     }
 
+    pub fn global(&self, global: ir::Global) -> Operand {
+        Operand::Address(self.globals[&global])
+    }
+
     pub fn has(&self, id: ir::Id) -> bool {
         self.addresses.contains_key(&id)
     }
@@ -48,17 +58,19 @@ impl<'a> Env<'a> {
         self.addresses.insert(id, address);
     }
 
-    pub fn get_or_add(&mut self, id: ir::Id) -> Operand {
+    /// the value takes `ty.slots()` slots,
+    /// the slot with the lowest address is the first
+    pub fn get_or_add(&mut self, id: ir::Id, ty: ir::Type) -> Operand {
         if !self.addresses.contains_key(&id) {
-            self.vars_count += 1;
+            self.vars_count += ty.slots();
             self.set(id, self.vars_count as isize);
         }
 
         self.get(id)
     }
 
-    pub fn stack_size(&self) -> usize {
-        self.vars_count * STACK_ALIGN as usize
+    pub fn vars_count(&self) -> usize {
+        self.vars_count
     }
 }
 
@@ -68,6 +80,41 @@ pub enum Operand {
     Number(i32),
 }
 
+impl Operand {
+    pub fn offset(self, slots: usize) -> Self {
+        match self {
+            Operand::Address(address) => Operand::Address(address.offset(slots)),
+            Operand::Number(_) => unreachable!(),
+        }
+    }
+
+    /// the `lane`th f32 of the value
+    pub fn lane(self, lane: usize) -> Lane {
+        match self {
+            Operand::Address(address) => Lane(address, lane),
+            Operand::Number(_) => unreachable!(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Lane(Address, usize);
+
+impl fmt::Display for Lane {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let &Lane(address, lane) = self;
+        let lane = 4 * lane as isize;
+
+        match address {
+            Address::Stack(address) => write!(f, "[rbp{:+}]", lane - STACK_ALIGN * address),
+            Address::Dynamic(address) => write!(f, "[rbp+rcx{:+}]", lane - STACK_ALIGN * address),
+            Address::Const(n) => write!(f, "[LC{n}+{lane}]"),
+            Address::ConstDynamic(n) => write!(f, "[LC{n}+rcx+{lane}]"),
+            Address::Global(n) => write!(f, "[G{n}+{lane}]"),
+        }
+    }
+}
+
 impl fmt::Display for Operand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -80,13 +127,30 @@ impl fmt::Display for Operand {
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Address {
     Stack(isize),
+    /// same as `Stack`, but additionally shifted by `rcx` bytes
+    Dynamic(isize),
     Const(usize),
+    /// same as `Const`, but additionally shifted by `rcx` bytes
+    ConstDynamic(usize),
+    Global(usize),
+}
+
+impl Address {
+    pub fn offset(self, slots: usize) -> Self {
+        match self {
+            Address::Stack(address) => Address::Stack(address - slots as isize),
+            Address::Dynamic(address) => Address::Dynamic(address - slots as isize),
+            Address::Const(n) => Address::Const(n + slots),
+            Address::ConstDynamic(n) => Address::ConstDynamic(n + slots),
+            Address::Global(n) => Address::Global(n + slots),
+        }
+    }
 }
 
 impl fmt::Display for Address {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            &Address::Stack(address) => {
+        match *self {
+            Address::Stack(address) => {
                 let ordering = address.cmp(&0);
                 let address = STACK_ALIGN * address.abs();
 
@@ -97,9 +161,25 @@ impl fmt::Display for Address {
                     _ => unreachable!(),
                 }
             }
-            &Address::Const(n) => {
+            Address::Dynamic(address) => {
+                let ordering = address.cmp(&0);
+                let address = STACK_ALIGN * address.abs();
+
+                match ordering {
+                    Ordering::Less => write!(f, "[rbp+rcx+{address}]"),
+                    Ordering::Greater => write!(f, "[rbp+rcx-{address}]"),
+                    Ordering::Equal => write!(f, "[rbp+rcx]"),
+                }
+            }
+            Address::Const(n) => {
                 write!(f, "[LC{n}]")
             }
+            Address::ConstDynamic(n) => {
+                write!(f, "[LC{n}+rcx]")
+            }
+            Address::Global(n) => {
+                write!(f, "[G{n}]")
+            }
         }
     }
 }
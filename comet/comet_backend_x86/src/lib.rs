@@ -6,7 +6,7 @@ use std::{
 use comet_ir::{self as ir, ControlFlowDisplay};
 
 use builder::Builder;
-use compile::{value, Compile};
+use compile::{global, value, Compile};
 use env::Env;
 
 mod builder;
@@ -24,27 +24,39 @@ pub fn compile(lir: ir::LIR) -> Result<String, fmt::Error> {
         .map(|(id, v)| (id, value(&mut builder, v)))
         .collect::<HashMap<_, _>>();
 
+    let globals = lir
+        .globals
+        .into_iter()
+        .map(|(id, v)| (id, global(&mut builder, v)))
+        .collect::<HashMap<_, _>>();
+
     writeln!(
         builder,
         "\
 section .text
 global main
-extern printf"
+extern printf, scanf, fflush, exit
+extern sinf, cosf, tanf, acosf, atan2f, expf, logf, powf, floorf, ceilf"
     )?;
 
     writeln!(builder, include_str!("std.nasm"))?;
 
     for (id, function) in lir.bodies {
-        let mut env = Env::new(&lir.labels, &function.code.phis, &constants);
+        let mut env = Env::new(&lir.labels, &function.code.phis, &constants, &globals);
 
         writeln!(builder, "{id}:")?;
         writeln!(builder, "push rbp")?;
         writeln!(builder, "mov rbp, rsp")?;
 
-        for (index, arg) in function.args.into_iter().enumerate() {
-            env.set(arg, -(index as isize + 1));
+        let mut args_slots = 0;
+
+        for (arg, ty) in function.args {
+            env.set(arg, -(args_slots as isize + 1));
+            args_slots += ty.slots();
         }
 
+        env.ret_address = -(args_slots as isize + 1);
+
         for block in function.code.blocks {
             if let Some(label) = block.label {
                 writeln!(builder, "{}:", lir.labels[&label])?;
@@ -57,7 +69,7 @@ extern printf"
             }
 
             if let Some(end) = block.end {
-                writeln!(builder, "; {}", ControlFlowDisplay(&env.labels, end))?;
+                writeln!(builder, "; {}", ControlFlowDisplay(env.labels, &end))?;
 
                 end.compile(&mut env, &mut builder)?;
             }
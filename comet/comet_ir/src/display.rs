@@ -1,23 +1,31 @@
 use std::{collections::HashMap, fmt};
 
 use crate::{
-    ArithmOp, Atom, BinOp, Component, ControlFlow, EqOp, F32sOp, FunId, Id, Label, Phi, RelOp,
-    Sequental, Type, UnOp, Value, LIR,
+    ArithmOp, Atom, BinOp, Component, ControlFlow, Conversion, EqOp, F32sOp, Format, FunId, Global,
+    Id, IntOp, Label, MatOp, Phi, RelOp, Sequental, Type, UnOp, Value, LIR,
 };
 
 impl fmt::Display for LIR<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (global, value) in &self.globals {
+            writeln!(f, "global {global} = {value}")?;
+        }
+
+        if !self.globals.is_empty() {
+            writeln!(f)?;
+        }
+
         for (id, function) in &self.bodies {
             write!(f, "fn {id}(")?;
 
-            let mut args = function.args.iter().copied();
+            let mut args = function.args.iter();
 
-            if let Some(arg) = args.next() {
-                write!(f, "{arg}")?;
+            if let Some((arg, ty)) = args.next() {
+                write!(f, "{ty} {arg}")?;
             }
 
-            for arg in args {
-                write!(f, ", {arg}")?;
+            for (arg, ty) in args {
+                write!(f, ", {ty} {arg}")?;
             }
 
             writeln!(f, ") {{")?;
@@ -35,7 +43,7 @@ impl fmt::Display for LIR<'_> {
                     writeln!(f, "\t{instr}")?;
                 }
 
-                if let &Some(instr) = &block.end {
+                if let Some(instr) = &block.end {
                     writeln!(f, "\t{}", ControlFlowDisplay(&self.labels, instr))?;
                 }
             }
@@ -81,11 +89,94 @@ impl fmt::Display for Sequental<'_> {
 
                 Ok(())
             }
+
+            Sequental::Aggregate { dst, ty, elems } => {
+                write!(f, "{dst} = {ty} {{")?;
+
+                let mut elems = elems.iter();
+
+                if let Some((elem, ty)) = elems.next() {
+                    write!(f, "{ty} {elem}")?;
+                }
+
+                for (elem, ty) in elems {
+                    write!(f, ", {ty} {elem}")?;
+                }
+
+                write!(f, "}}")
+            }
+
+            Sequental::Load {
+                dst,
+                ty,
+                aggregate,
+                offset,
+            } => {
+                write!(f, "{dst} = load {ty} {aggregate}, {offset}")
+            }
+
+            Sequental::Store {
+                dst,
+                ty,
+                aggregate,
+                offset,
+                value: (value, value_ty),
+            } => {
+                write!(
+                    f,
+                    "{dst} = store {ty} {aggregate}, {offset}, {value_ty} {value}"
+                )
+            }
+
+            Sequental::SetLane {
+                dst,
+                dims,
+                vec,
+                lane,
+                value,
+            } => {
+                write!(
+                    f,
+                    "{dst} = f32x{dims}.set_lane {vec}, {lane}, {value}",
+                    dims = *dims as usize
+                )
+            }
+
+            Sequental::CheckIndex { index, len } => {
+                write!(f, "check_index {index}, {len}")
+            }
+
+            Sequental::LoadGlobal { dst, ty, global } => {
+                write!(f, "{dst} = load_global {ty} {global}")
+            }
+
+            Sequental::StoreGlobal {
+                global,
+                value: (value, ty),
+            } => {
+                write!(f, "store_global {global}, {ty} {value}")
+            }
+
+            Sequental::Print { pieces, args } => {
+                let format = pieces
+                    .iter()
+                    .map(|piece| piece.replace('{', "{{").replace('}', "}}"))
+                    .collect::<Vec<_>>()
+                    .join("{}");
+
+                write!(f, "print {format:?}")?;
+
+                for (arg, format) in args {
+                    write!(f, ", {format} {arg}")?;
+                }
+
+                Ok(())
+            }
         }
     }
 }
 
-pub struct ControlFlowDisplay<'a>(pub &'a HashMap<Label, String>, pub ControlFlow);
+pub struct ControlFlowDisplay<'a>(pub &'a HashMap<Label, String>, pub &'a ControlFlow);
 
 impl fmt::Display for ControlFlowDisplay<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -96,11 +187,30 @@ impl fmt::Display for ControlFlowDisplay<'_> {
                 rhs,
                 label,
             } => {
-                write!(f, "if {op} {lhs}, {rhs} goto {}", self.0[&label])
+                write!(f, "if {op} {lhs}, {rhs} goto {}", self.0[label])
             }
 
             ControlFlow::Goto { label } => {
-                write!(f, "goto {}", self.0[&label])
+                write!(f, "goto {}", self.0[label])
+            }
+
+            ControlFlow::Switch {
+                value,
+                min,
+                targets,
+                default,
+            } => {
+                write!(f, "switch {value} from {min} [")?;
+
+                for (n, target) in targets.iter().enumerate() {
+                    if n > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}", self.0[target])?;
+                }
+
+                write!(f, "] else goto {}", self.0[default])
             }
 
             ControlFlow::Return { value } => {
@@ -142,6 +252,8 @@ impl fmt::Display for UnOp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             UnOp::Neg(ty) => write!(f, "{ty}.-"),
+            UnOp::BitNot => write!(f, "int.~"),
+            UnOp::Convert(conversion) => write!(f, "{conversion}"),
             UnOp::Swizzle(swizzle) => {
                 write!(f, "vec.:")?;
 
@@ -151,6 +263,11 @@ impl fmt::Display for UnOp {
 
                 Ok(())
             }
+            UnOp::Transpose(dims) => {
+                write!(f, "f32x{dims}x{dims}.transpose", dims = *dims as usize)
+            }
+            UnOp::Determinant(dims) => write!(f, "f32x{dims}x{dims}.det", dims = *dims as usize),
+            UnOp::Inverse(dims) => write!(f, "f32x{dims}x{dims}.inverse", dims = *dims as usize),
         }
     }
 }
@@ -163,6 +280,24 @@ impl fmt::Display for Type {
             Type::F32x2 => write!(f, "f32x2"),
             Type::F32x3 => write!(f, "f32x3"),
             Type::F32x4 => write!(f, "f32x4"),
+            Type::F32x2x2 => write!(f, "f32x2x2"),
+            Type::F32x3x3 => write!(f, "f32x3x3"),
+            Type::F32x4x4 => write!(f, "f32x4x4"),
+            Type::Aggregate(slots) => write!(f, "agg{slots}"),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Int => write!(f, "int"),
+            Format::Real => write!(f, "real"),
+            Format::Bool => write!(f, "bool"),
+            Format::Complex => write!(f, "complex"),
+            Format::Quat => write!(f, "quat"),
+            &Format::F32s(dims) => write!(f, "f32x{dims}", dims = dims as usize),
+            &Format::Mat(dims) => write!(f, "f32x{dims}x{dims}", dims = dims as usize),
         }
     }
 }
@@ -184,8 +319,31 @@ impl fmt::Display for Value {
             Value::F32x2(v) => write!(f, "({}, {})", v.x, v.y),
             Value::F32x3(v) => write!(f, "({}, {}, {})", v.x, v.y, v.z),
             Value::F32x4(v) => write!(f, "({}, {}, {}, {})", v.x, v.y, v.z, v.w),
+            Value::F32x2x2(m) => write_columns(f, m.column_iter().map(|c| c.as_slice().to_vec())),
+            Value::F32x3x3(m) => write_columns(f, m.column_iter().map(|c| c.as_slice().to_vec())),
+            Value::F32x4x4(m) => write_columns(f, m.column_iter().map(|c| c.as_slice().to_vec())),
+        }
+    }
+}
+
+/// writes a matrix as a list of its columns
+fn write_columns(
+    f: &mut fmt::Formatter<'_>,
+    columns: impl Iterator<Item = Vec<f32>>,
+) -> fmt::Result {
+    write!(f, "[")?;
+
+    for (n, column) in columns.enumerate() {
+        if n != 0 {
+            write!(f, ", ")?;
         }
+
+        let column = column.iter().map(f32::to_string).collect::<Vec<_>>();
+
+        write!(f, "({})", column.join(", "))?;
     }
+
+    write!(f, "]")
 }
 
 impl fmt::Display for Id {
@@ -194,6 +352,12 @@ impl fmt::Display for Id {
     }
 }
 
+impl fmt::Display for Global {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@{}", self.0)
+    }
+}
+
 impl fmt::Display for FunId<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.name)
@@ -204,13 +368,27 @@ impl fmt::Display for BinOp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             BinOp::Int(op) => write!(f, "int.{op}"),
+            BinOp::IntOp(op) => write!(f, "int.{op}"),
             BinOp::Real(op) => write!(f, "real.{op}"),
             &BinOp::F32s(dims, op) => write!(f, "f32x{dims}.{op}", dims = dims as usize),
             BinOp::ComplexMul => write!(f, "f32x2.complex_mul"),
             BinOp::ComplexDiv => write!(f, "f32x2.complex_div"),
+            BinOp::QuatMul => write!(f, "f32x4.quat_mul"),
+            BinOp::QuatDiv => write!(f, "f32x4.quat_div"),
             BinOp::IntRel(op) => write!(f, "int.{op}"),
             BinOp::RealRel(op) => write!(f, "real.{op}"),
             &BinOp::F32sRel(dims, op) => write!(f, "f32x{dims}.{op}", dims = dims as usize),
+            &BinOp::Lane(dims) => write!(f, "f32x{dims}.lane", dims = dims as usize),
+            &BinOp::Blend(dims, swizzle) => {
+                write!(f, "f32x{dims}.blend:", dims = dims as usize)?;
+
+                for &comp in swizzle.as_slice() {
+                    write!(f, "{comp}")?;
+                }
+
+                Ok(())
+            }
+            &BinOp::Mat(dims, op) => write!(f, "f32x{dims}x{dims}.{op}", dims = dims as usize),
         }
     }
 }
@@ -226,6 +404,33 @@ impl fmt::Display for ArithmOp {
     }
 }
 
+impl fmt::Display for Conversion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IntToReal => write!(f, "int.as real"),
+            Self::RealToInt => write!(f, "real.as int"),
+            Self::IntToComplex => write!(f, "int.as f32x2"),
+            Self::RealToComplex => write!(f, "real.as f32x2"),
+            &Self::Resize(from, to) => {
+                write!(f, "f32x{}.as f32x{}", from as usize, to as usize)
+            }
+        }
+    }
+}
+
+impl fmt::Display for IntOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rem => write!(f, "%"),
+            Self::And => write!(f, "&"),
+            Self::Or => write!(f, "|"),
+            Self::Xor => write!(f, "^"),
+            Self::Shl => write!(f, "<<"),
+            Self::Shr => write!(f, ">>"),
+        }
+    }
+}
+
 impl fmt::Display for RelOp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -250,6 +455,19 @@ impl fmt::Display for F32sOp {
     }
 }
 
+impl fmt::Display for MatOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Add => write!(f, "+"),
+            Self::Sub => write!(f, "-"),
+            Self::ScalarMul => write!(f, "scalar_mul"),
+            Self::ScalarDiv => write!(f, "scalar_div"),
+            Self::MulVec => write!(f, "mul_vec"),
+            Self::MulMat => write!(f, "*"),
+        }
+    }
+}
+
 impl fmt::Display for EqOp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -1,4 +1,4 @@
-use crate::{BinOp, FunId, UnOp, Value};
+use crate::{BinOp, Dims, FunId, UnOp, Value};
 
 pub enum Instruction<'f> {
     Sequental(Sequental<'f>),
@@ -46,15 +46,72 @@ pub enum Sequental<'f> {
         fun: FunId<'f>,
         args: Vec<(Atom, Type)>,
     },
+    Aggregate {
+        dst: Id,
+        ty: Type,
+        elems: Vec<(Atom, Type)>,
+    },
+    /// reads a value of type `ty` from `aggregate`,
+    /// `offset` is measured in slots
+    Load {
+        dst: Id,
+        ty: Type,
+        aggregate: Id,
+        offset: Atom,
+    },
+    /// copies `aggregate` to `dst` replacing the value at `offset`
+    Store {
+        dst: Id,
+        ty: Type,
+        aggregate: Id,
+        offset: Atom,
+        value: (Atom, Type),
+    },
+    /// copies the vector `vec` to `dst` replacing the lane by the index `lane`
+    SetLane {
+        dst: Id,
+        dims: Dims,
+        vec: Atom,
+        lane: Atom,
+        value: Atom,
+    },
+    /// stops the program if `index` isn't in `0..len`
+    CheckIndex {
+        index: Atom,
+        len: usize,
+    },
+    /// reads the current value of a global
+    LoadGlobal {
+        dst: Id,
+        ty: Type,
+        global: Global,
+    },
+    /// overwrites a global, the later loads see the new value
+    StoreGlobal {
+        global: Global,
+        value: (Atom, Type),
+    },
+    /// prints `args` between `pieces` of text,
+    /// there's one more piece than args
+    Print {
+        pieces: Vec<String>,
+        args: Vec<(Atom, Format)>,
+    },
 }
 
 impl Sequental<'_> {
     pub fn dst_and_ty(&self) -> Option<(Id, Type)> {
-        match self {
-            &Self::Assign { dst, ty, .. } => Some((dst, ty)),
-            &Self::Binary { dst, op, .. } => Some((dst, op.ty())),
-            &Self::Unary { dst, op, .. } => Some((dst, op.ty())),
-            &Self::Call { dst, fun, .. } => dst.zip(fun.ret_ty()),
+        match *self {
+            Self::Assign { dst, ty, .. } => Some((dst, ty)),
+            Self::Binary { dst, op, .. } => Some((dst, op.ty())),
+            Self::Unary { dst, op, .. } => Some((dst, op.ty())),
+            Self::Call { dst, ref fun, .. } => dst.zip(fun.ret_ty()),
+            Self::Aggregate { dst, ty, .. } => Some((dst, ty)),
+            Self::Load { dst, ty, .. } => Some((dst, ty)),
+            Self::Store { dst, ty, .. } => Some((dst, ty)),
+            Self::SetLane { dst, dims, .. } => Some((dst, dims.ty())),
+            Self::LoadGlobal { dst, ty, .. } => Some((dst, ty)),
+            Self::CheckIndex { .. } | Self::StoreGlobal { .. } | Self::Print { .. } => None,
         }
     }
 }
@@ -65,7 +122,7 @@ pub struct Phi {
     pub branches: Vec<Id>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum ControlFlow {
     If {
         lhs: Atom,
@@ -76,6 +133,14 @@ pub enum ControlFlow {
     Goto {
         label: Label,
     },
+    /// goes to `targets[value - min]`,
+    /// or to `default` if there is no such target
+    Switch {
+        value: Atom,
+        min: i32,
+        targets: Vec<Label>,
+        default: Label,
+    },
     Return {
         value: Option<(Type, Atom)>,
     },
@@ -89,6 +154,50 @@ pub enum Type {
     F32x2,
     F32x3,
     F32x4,
+    /// column-major matrices, a column takes a slot
+    F32x2x2,
+    F32x3x3,
+    F32x4x4,
+    /// a sequence of values, the size is measured in slots
+    Aggregate(usize),
+}
+
+impl Type {
+    pub fn slots(self) -> usize {
+        match self {
+            Self::F32x2x2 => 2,
+            Self::F32x3x3 => 3,
+            Self::F32x4x4 => 4,
+            Self::Aggregate(slots) => slots,
+            _ => 1,
+        }
+    }
+}
+
+/// the way `Print` shows a value
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Int,
+    Real,
+    Bool,
+    Complex,
+    /// `w + xi + yj + zk`
+    Quat,
+    F32s(Dims),
+    Mat(Dims),
+}
+
+impl Format {
+    pub fn ty(self) -> Type {
+        match self {
+            Self::Int | Self::Bool => Type::Int,
+            Self::Real => Type::Real,
+            Self::Complex => Type::F32x2,
+            Self::Quat => Type::F32x4,
+            Self::F32s(dims) => dims.ty(),
+            Self::Mat(dims) => dims.mat_ty(),
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -114,3 +223,13 @@ impl Id {
         Self(id)
     }
 }
+
+/// a value living in the data section, unlike `Id`s it's shared by all the functions
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Global(pub(crate) usize);
+
+impl Global {
+    pub fn new(id: usize) -> Self {
+        Self(id)
+    }
+}
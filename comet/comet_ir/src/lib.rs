@@ -5,6 +5,7 @@ mod op;
 mod value;
 
 use std::{
+    borrow::Cow,
     cmp,
     collections::{BTreeMap, HashMap},
 };
@@ -17,39 +18,45 @@ pub use value::*;
 pub struct LIR<'f> {
     pub bodies: BTreeMap<FunId<'f>, FunctionBody<'f>>,
     pub constants: HashMap<Id, Value>,
+    /// the initial values of the globals
+    pub globals: BTreeMap<Global, Value>,
     pub labels: HashMap<Label, String>,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct FunId<'f> {
-    pub name: &'f str,
+    /// the symbol in the assembly, it's mangled for overloaded functions
+    pub name: Cow<'f, str>,
     ret_ty: Option<Type>,
 }
 
 impl<'f> FunId<'f> {
-    pub fn new(name: &'f str, ret_ty: Option<Type>) -> Self {
-        Self { name, ret_ty }
+    pub fn new(name: impl Into<Cow<'f, str>>, ret_ty: Option<Type>) -> Self {
+        Self {
+            name: name.into(),
+            ret_ty,
+        }
     }
 
-    pub fn ret_ty(self) -> Option<Type> {
+    pub fn ret_ty(&self) -> Option<Type> {
         self.ret_ty
     }
 }
 
 impl PartialOrd for FunId<'_> {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        self.name.partial_cmp(&other.name)
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for FunId<'_> {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
-        self.name.cmp(other.name)
+        self.name.cmp(&other.name)
     }
 }
 
 pub struct FunctionBody<'f> {
-    pub args: Vec<Id>,
+    pub args: Vec<(Id, Type)>,
     pub code: Code<'f>,
 }
 
@@ -3,27 +3,43 @@ use crate::Type;
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum BinOp {
     Int(ArithmOp),
+    IntOp(IntOp),
     Real(ArithmOp),
     F32s(Dims, F32sOp),
     IntRel(RelOp),
     RealRel(RelOp),
     F32sRel(Dims, EqOp),
+    /// extracts a component of the vector by the index
+    Lane(Dims),
+    /// replaces the swizzle components of the vector by the lanes of rhs
+    Blend(Dims, Swizzle),
+    Mat(Dims, MatOp),
     ComplexMul,
     ComplexDiv,
+    /// the Hamilton product
+    QuatMul,
+    /// lhs x inverse(rhs)
+    QuatDiv,
 }
 
 impl BinOp {
     pub fn ty(self) -> Type {
         match self {
-            Self::Int(..) => Type::Int,
+            Self::Int(..) | Self::IntOp(..) => Type::Int,
             Self::Real(..) => Type::Real,
             Self::F32s(dims, ..) => dims.ty(),
 
             Self::ComplexMul | Self::ComplexDiv => Type::F32x2,
+            Self::QuatMul | Self::QuatDiv => Type::F32x4,
 
             Self::IntRel(..) => Type::Int,
             Self::RealRel(..) => Type::Int,
             Self::F32sRel(..) => Type::Int,
+
+            Self::Lane(..) => Type::Real,
+            Self::Blend(dims, ..) => dims.ty(),
+            Self::Mat(dims, MatOp::MulVec) => dims.ty(),
+            Self::Mat(dims, ..) => dims.mat_ty(),
         }
     }
 }
@@ -36,6 +52,19 @@ pub enum ArithmOp {
     Div,
 }
 
+/// the operations that are defined only for ints
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum IntOp {
+    Rem,
+    And,
+    Or,
+    Xor,
+    /// shift left
+    Shl,
+    /// arithmetic shift right
+    Shr,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum F32sOp {
     Add,
@@ -44,6 +73,18 @@ pub enum F32sOp {
     ScalarDiv,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MatOp {
+    Add,
+    Sub,
+    ScalarMul,
+    ScalarDiv,
+    /// Mat x Vec
+    MulVec,
+    /// Mat x Mat
+    MulMat,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum RelOp {
     Eq,
@@ -75,19 +116,59 @@ impl Dims {
             Dims::X4 => Type::F32x4,
         }
     }
+
+    pub fn mat_ty(self) -> Type {
+        match self {
+            Dims::X2 => Type::F32x2x2,
+            Dims::X3 => Type::F32x3x3,
+            Dims::X4 => Type::F32x4x4,
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum UnOp {
     Neg(Type),
+    /// bitwise not of an int
+    BitNot,
+    Convert(Conversion),
     Swizzle(Swizzle),
+    Transpose(Dims),
+    Determinant(Dims),
+    Inverse(Dims),
 }
 
 impl UnOp {
     pub fn ty(self) -> Type {
         match self {
             Self::Neg(ty) => ty,
+            Self::BitNot => Type::Int,
+            Self::Convert(conversion) => conversion.ty(),
             Self::Swizzle(swizzle) => swizzle.ty(),
+            Self::Transpose(dims) | Self::Inverse(dims) => dims.mat_ty(),
+            Self::Determinant(_) => Type::Real,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    IntToReal,
+    /// truncates the fractional part
+    RealToInt,
+    IntToComplex,
+    RealToComplex,
+    /// copies the common lanes, the rest are zeroed
+    Resize(Dims, Dims),
+}
+
+impl Conversion {
+    pub fn ty(self) -> Type {
+        match self {
+            Self::IntToReal => Type::Real,
+            Self::RealToInt => Type::Int,
+            Self::IntToComplex | Self::RealToComplex => Type::F32x2,
+            Self::Resize(_, to) => to.ty(),
         }
     }
 }
@@ -1,4 +1,4 @@
-use nalgebra::{Vector2, Vector3, Vector4};
+use nalgebra::{Matrix2, Matrix3, Matrix4, Vector2, Vector3, Vector4};
 
 use crate::Type;
 
@@ -9,6 +9,9 @@ pub enum Value {
     F32x2(Vector2<f32>),
     F32x3(Vector3<f32>),
     F32x4(Vector4<f32>),
+    F32x2x2(Matrix2<f32>),
+    F32x3x3(Matrix3<f32>),
+    F32x4x4(Matrix4<f32>),
 }
 
 impl Value {
@@ -19,6 +22,9 @@ impl Value {
             Value::F32x2(_) => Type::F32x2,
             Value::F32x3(_) => Type::F32x3,
             Value::F32x4(_) => Type::F32x4,
+            Value::F32x2x2(_) => Type::F32x2x2,
+            Value::F32x3x3(_) => Type::F32x3x3,
+            Value::F32x4x4(_) => Type::F32x4x4,
         }
     }
 
@@ -57,4 +63,28 @@ impl Value {
 
         v
     }
+
+    pub fn f32x2x2(&self) -> Matrix2<f32> {
+        let &Self::F32x2x2(v) = self else {
+            unreachable!()
+        };
+
+        v
+    }
+
+    pub fn f32x3x3(&self) -> Matrix3<f32> {
+        let &Self::F32x3x3(v) = self else {
+            unreachable!()
+        };
+
+        v
+    }
+
+    pub fn f32x4x4(&self) -> Matrix4<f32> {
+        let &Self::F32x4x4(v) = self else {
+            unreachable!()
+        };
+
+        v
+    }
 }
@@ -1,28 +1,71 @@
-use crate::{Block, Expr, Id, Spanned};
+use crate::{Block, Expr, Id, Span, Spanned, Type};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Declaration<'source> {
     Function(FunctionDeclaration<'source>),
     Constant(ConstantDeclaration<'source>),
+    Static(StaticDeclaration<'source>),
+    Struct(StructDeclaration<'source>),
+    Import(ImportDeclaration<'source>),
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct FunctionDeclaration<'source> {
     pub id: Id<'source>,
     pub args: Vec<FunctionArg<'source>>,
-    pub ret_ty: Option<Id<'source>>,
+    pub ret_ty: Option<Spanned<Type<'source>>>,
     pub body: Block<'source>,
+    /// the closing brace of the body
+    pub end: Span,
+    /// `const fn` can be called in constants, it's evaluated at compile time there
+    pub is_const: bool,
+    pub attributes: Vec<Attribute<'source>>,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct FunctionArg<'source> {
     pub id: Id<'source>,
-    pub ty: Id<'source>,
+    pub ty: Spanned<Type<'source>>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ConstantDeclaration<'source> {
     pub id: Id<'source>,
-    pub ty: Id<'source>,
+    pub ty: Spanned<Type<'source>>,
     pub value: Spanned<Expr<'source>>,
+    pub attributes: Vec<Attribute<'source>>,
+}
+
+/// mutable global, `value` is evaluated at compile time like a constant
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticDeclaration<'source> {
+    pub id: Id<'source>,
+    pub ty: Spanned<Type<'source>>,
+    pub value: Spanned<Expr<'source>>,
+    pub attributes: Vec<Attribute<'source>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructDeclaration<'source> {
+    pub id: Id<'source>,
+    pub fields: Vec<StructField<'source>>,
+}
+
+/// `#[allow(unused_variables, dead_code)]` before a function, a constant or a static
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute<'source> {
+    pub id: Id<'source>,
+    pub args: Vec<Id<'source>>,
+}
+
+/// `path` is relative to the importing file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportDeclaration<'source> {
+    pub path: Spanned<&'source str>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructField<'source> {
+    pub id: Id<'source>,
+    pub ty: Spanned<Type<'source>>,
 }
@@ -28,10 +28,16 @@ impl fmt::Display for BinOp {
             Self::Gt => write!(f, ">"),
             Self::Le => write!(f, "<="),
             Self::Lt => write!(f, "<"),
+            Self::BitOr => write!(f, "|||"),
+            Self::BitXor => write!(f, "^^^"),
+            Self::BitAnd => write!(f, "&&&"),
+            Self::Shl => write!(f, "<<"),
+            Self::Shr => write!(f, ">>"),
             Self::Add => write!(f, "+"),
             Self::Sub => write!(f, "-"),
             Self::Mul => write!(f, "*"),
             Self::Div => write!(f, "/"),
+            Self::Rem => write!(f, "%"),
         }
     }
 }
@@ -41,6 +47,7 @@ impl fmt::Display for UnOp {
         match self {
             Self::Not => write!(f, "!"),
             Self::Neg => write!(f, "-"),
+            Self::BitNot => write!(f, "~"),
         }
     }
 }
@@ -1,8 +1,8 @@
-use crate::{BinOp, Spanned, UnOp};
+use crate::{BinOp, BlockExpr, Spanned, Type, UnOp};
 
 pub use comet_ir::{Component, Swizzle};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Expr<'source> {
     Prefix {
         op: UnOp,
@@ -17,17 +17,57 @@ pub enum Expr<'source> {
         lhs: Box<Spanned<Self>>,
         swizzle: Swizzle,
     },
+    Index {
+        lhs: Box<Spanned<Self>>,
+        index: Box<Spanned<Self>>,
+    },
+    /// the field of a tuple is the index of the element, e.g. `t.0`
+    Field {
+        lhs: Box<Spanned<Self>>,
+        field: Id<'source>,
+    },
+    /// `expr as ty`
+    As {
+        expr: Box<Spanned<Self>>,
+        ty: Spanned<Type<'source>>,
+    },
+    /// `else if` is an `else` block with only a value
+    If {
+        cond: Box<Spanned<Self>>,
+        body: BlockExpr<'source>,
+        else_body: BlockExpr<'source>,
+    },
+    Block(BlockExpr<'source>),
+    Tuple(Vec<Spanned<Self>>),
+    Array(Vec<Spanned<Self>>),
+    ArrayRepeat {
+        value: Box<Spanned<Self>>,
+        len: Box<Spanned<Self>>,
+    },
+    Struct(StructLiteral<'source>),
     Call(Call<'source>),
     Atom(Atom<'source>),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Call<'source> {
     pub id: Id<'source>,
     pub args: Vec<Spanned<Expr<'source>>>,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructLiteral<'source> {
+    pub id: Id<'source>,
+    pub fields: Vec<FieldInit<'source>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldInit<'source> {
+    pub id: Id<'source>,
+    pub value: Spanned<Expr<'source>>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
 pub enum Atom<'source> {
     Id(Id<'source>),
     Literal(Literal<'source>),
@@ -47,4 +87,6 @@ pub enum LiteralType {
     Real,
     Int,
     Bool,
+    /// the value is quoted and not unescaped
+    String,
 }
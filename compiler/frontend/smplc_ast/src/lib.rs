@@ -5,15 +5,25 @@ mod operators;
 mod pos;
 mod span;
 mod statement;
+mod ty;
 
 pub use declaration::*;
 pub use expr::*;
 pub use operators::*;
-pub use pos::Pos;
+pub use pos::{FileId, Pos};
 pub use span::*;
 pub use statement::*;
+pub use ty::Type;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct Block<'source> {
-    pub statements: Vec<Statement<'source>>,
+    pub statements: Vec<Spanned<Statement<'source>>>,
+}
+
+/// `{ statements; value }`, a block with a value,
+/// a block without the value must end with a diverging statement
+#[derive(PartialEq, Debug, Clone)]
+pub struct BlockExpr<'source> {
+    pub statements: Vec<Spanned<Statement<'source>>>,
+    pub value: Option<Box<Spanned<Expr<'source>>>>,
 }
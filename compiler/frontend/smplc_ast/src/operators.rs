@@ -10,10 +10,17 @@ pub enum BinOp {
     Le,
     Lt,
 
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shl,
+    Shr,
+
     Add,
     Sub,
     Mul,
     Div,
+    Rem,
 }
 
 impl BinOp {
@@ -27,10 +34,16 @@ impl BinOp {
             Self::Gt => (9, 10),
             Self::Le => (9, 10),
             Self::Lt => (9, 10),
-            Self::Add => (11, 12),
-            Self::Sub => (11, 12),
-            Self::Mul => (13, 14),
-            Self::Div => (13, 14),
+            Self::BitOr => (11, 12),
+            Self::BitXor => (13, 14),
+            Self::BitAnd => (15, 16),
+            Self::Shl => (17, 18),
+            Self::Shr => (17, 18),
+            Self::Add => (19, 20),
+            Self::Sub => (19, 20),
+            Self::Mul => (21, 22),
+            Self::Div => (21, 22),
+            Self::Rem => (21, 22),
         }
     }
 
@@ -63,19 +76,28 @@ impl BinOp {
 
         matches!(self, And | Or)
     }
+
+    /// operators that are defined only for `int`
+    pub fn is_int(self) -> bool {
+        use BinOp::*;
+
+        matches!(self, Rem | BitOr | BitXor | BitAnd | Shl | Shr)
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum UnOp {
     Not,
     Neg,
+    BitNot,
 }
 
 impl UnOp {
     pub fn power(&self) -> (usize, usize) {
         match self {
-            Self::Not => (0, 15),
-            Self::Neg => (0, 15),
+            Self::Not => (0, 25),
+            Self::Neg => (0, 25),
+            Self::BitNot => (0, 25),
         }
     }
 }
@@ -1,33 +1,49 @@
+/// the index of a source file in the program, the main file is the first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FileId(pub usize);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Pos {
-    line: usize,
-    column: usize,
+    // u32 keeps the size of `Pos` with the file
+    line: u32,
+    column: u32,
     index: usize,
+    file: FileId,
 }
 
 impl Pos {
     #[inline(always)]
     pub fn new(line: usize, column: usize, index: usize) -> Self {
         Self {
-            line,
-            column,
+            line: line as u32,
+            column: column as u32,
             index,
+            file: FileId::default(),
+        }
+    }
+
+    /// the start of the file
+    #[inline(always)]
+    pub fn file_start(file: FileId) -> Self {
+        Self {
+            file,
+            ..Default::default()
         }
     }
 
     #[inline(always)]
     pub fn line(self) -> usize {
-        self.line
+        self.line as usize
     }
 
     #[inline(always)]
     pub fn column(self) -> usize {
-        self.column
+        self.column as usize
     }
 
     #[inline(always)]
     pub fn line_start(self) -> usize {
-        self.index + 1 - self.column
+        self.index + 1 - self.column()
     }
 
     #[inline(always)]
@@ -35,6 +51,11 @@ impl Pos {
         self.index
     }
 
+    #[inline(always)]
+    pub fn file(self) -> FileId {
+        self.file
+    }
+
     #[inline(always)]
     pub fn update(&mut self, char: char) {
         match char {
@@ -56,6 +77,7 @@ impl Default for Pos {
             line: 1,
             column: 1,
             index: 0,
+            file: FileId::default(),
         }
     }
 }
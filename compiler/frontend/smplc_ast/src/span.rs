@@ -2,7 +2,7 @@ use std::fmt;
 use std::hash::Hash;
 use std::ops::Index;
 
-use crate::Pos;
+use crate::{FileId, Pos};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Spanned<T>(pub T, pub Span);
@@ -74,10 +74,20 @@ impl Span {
         self.len
     }
 
+    #[inline(always)]
+    pub fn is_empty(self) -> bool {
+        self.len == 0
+    }
+
     #[inline(always)]
     pub fn end(self) -> usize {
         self.start().index() + self.len()
     }
+
+    #[inline(always)]
+    pub fn file(self) -> FileId {
+        self.start().file()
+    }
 }
 
 impl Index<Span> for str {
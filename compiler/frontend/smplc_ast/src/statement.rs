@@ -1,46 +1,137 @@
-use crate::{Block, Expr, Id, Spanned};
+use crate::{BinOp, Block, Expr, Id, Span, Spanned, Swizzle, Type};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Statement<'source> {
     Declare(DeclareStatement<'source>),
+    Destructure(DestructureStatement<'source>),
     If(IfStatement<'source>),
     While(WhileStatement<'source>),
+    DoWhile(DoWhileStatement<'source>),
+    Loop(LoopStatement<'source>),
+    For(Box<ForStatement<'source>>),
+    Match(MatchStatement<'source>),
     Expr(ExprStatement<'source>),
     Return(ReturnStatement<'source>),
-    Break,
-    Continue,
+    /// the label is set for `break 'outer;`
+    Break(Option<Id<'source>>),
+    Continue(Option<Id<'source>>),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DeclareStatement<'source> {
     pub id: Id<'source>,
-    pub ty: Option<Id<'source>>,
+    pub ty: Option<Spanned<Type<'source>>>,
     pub value: Option<Spanned<Expr<'source>>>,
 }
 
-#[derive(PartialEq, Debug)]
+/// `let (a, b) = value;`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DestructureStatement<'source> {
+    pub ids: Vec<Id<'source>>,
+    pub value: Spanned<Expr<'source>>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
 pub enum ExprStatement<'source> {
     Expr(Spanned<Expr<'source>>),
+    /// `op` is set for compound assignments like `a += b`
     Assign {
-        id: Id<'source>,
+        place: Place<'source>,
+        op: Option<BinOp>,
         rhs: Spanned<Expr<'source>>,
     },
 }
 
-#[derive(PartialEq, Debug)]
+/// The left side of an assignment: a variable
+/// with an optional chain of element and field accesses, e.g. `a[i].pos`,
+/// that can end with a swizzle, e.g. `p.pos:xy`
+#[derive(PartialEq, Debug, Clone)]
+pub struct Place<'source> {
+    pub id: Id<'source>,
+    pub projections: Vec<Projection<'source>>,
+    pub swizzle: Option<Spanned<Swizzle>>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Projection<'source> {
+    Index(Spanned<Expr<'source>>),
+    Field(Id<'source>),
+}
+
+#[derive(PartialEq, Debug, Clone)]
 pub struct IfStatement<'source> {
     pub cond: Spanned<Expr<'source>>,
     pub body: Block<'source>,
     pub else_body: Option<Block<'source>>,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct ReturnStatement<'source> {
     pub value: Option<Spanned<Expr<'source>>>,
+    /// the `return` keyword
+    pub span: Span,
 }
 
-#[derive(PartialEq, Debug)]
+/// The labels of the loops keep the quote, e.g. `'outer`
+#[derive(PartialEq, Debug, Clone)]
 pub struct WhileStatement<'source> {
+    pub label: Option<Id<'source>>,
     pub cond: Spanned<Expr<'source>>,
     pub body: Block<'source>,
 }
+
+/// `do { ... } while cond;`
+#[derive(PartialEq, Debug, Clone)]
+pub struct DoWhileStatement<'source> {
+    pub label: Option<Id<'source>>,
+    pub body: Block<'source>,
+    pub cond: Spanned<Expr<'source>>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct LoopStatement<'source> {
+    pub label: Option<Id<'source>>,
+    pub body: Block<'source>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct ForStatement<'source> {
+    pub label: Option<Id<'source>>,
+    pub var: Id<'source>,
+    pub range: Range<'source>,
+    pub body: Block<'source>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct MatchStatement<'source> {
+    pub value: Spanned<Expr<'source>>,
+    pub arms: Vec<MatchArm<'source>>,
+}
+
+/// `0 | 1 => ...`, an expression arm is kept as a block with one statement
+#[derive(PartialEq, Debug, Clone)]
+pub struct MatchArm<'source> {
+    pub patterns: Vec<Spanned<Pattern<'source>>>,
+    pub body: Block<'source>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Pattern<'source> {
+    /// `_`
+    Wildcard,
+    Value(Expr<'source>),
+    /// `3..=9` or `3..10`
+    Range {
+        start: Expr<'source>,
+        end: Expr<'source>,
+        inclusive: bool,
+    },
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Range<'source> {
+    pub start: Spanned<Expr<'source>>,
+    pub end: Spanned<Expr<'source>>,
+    pub inclusive: bool,
+    pub step: Option<Spanned<Expr<'source>>>,
+}
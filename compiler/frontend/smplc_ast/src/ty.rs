@@ -0,0 +1,11 @@
+use crate::{Expr, Spanned};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type<'source> {
+    Named(&'source str),
+    Array {
+        elem: Box<Spanned<Self>>,
+        len: Box<Spanned<Expr<'source>>>,
+    },
+    Tuple(Vec<Spanned<Self>>),
+}
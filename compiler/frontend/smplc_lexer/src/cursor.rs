@@ -1,4 +1,4 @@
-use smplc_ast::Pos;
+use smplc_ast::{FileId, Pos};
 
 pub struct Cursor<'source> {
     source: &'source str,
@@ -6,10 +6,10 @@ pub struct Cursor<'source> {
 }
 
 impl<'source> Cursor<'source> {
-    pub fn new(source: &'source str) -> Self {
+    pub fn new(source: &'source str, file: FileId) -> Self {
         Self {
             source,
-            pos: Pos::default(),
+            pos: Pos::file_start(file),
         }
     }
 
@@ -17,6 +17,11 @@ impl<'source> Cursor<'source> {
         self.source[self.index()..].chars().next().unwrap()
     }
 
+    /// the char after the current one
+    pub fn peek(&self) -> Option<char> {
+        self.source[self.index()..].chars().nth(1)
+    }
+
     pub fn next_ch(&mut self) -> char {
         let ch = self.current();
 
@@ -48,6 +53,11 @@ impl<'source> Cursor<'source> {
         self.slice(self.pos.index(), self.pos.index() + len)
     }
 
+    /// up to `len` chars before the current one
+    pub fn slice_before_current(&self, len: usize) -> &'source str {
+        self.slice(self.index().saturating_sub(len), self.index())
+    }
+
     pub fn get_pos(&self) -> Pos {
         self.pos
     }
@@ -1,15 +1,31 @@
 use std::fmt;
 
-use smplc_ast::Pos;
+use smplc_ast::Span;
 
 #[derive(Debug)]
 pub struct LexError {
-    pub char: char,
-    pub pos: Pos,
+    pub kind: LexErrorKind,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LexErrorKind {
+    UnexpectedChar(char),
+    /// the string isn't closed before the end of the line
+    UnterminatedString,
+}
+
+impl fmt::Display for LexErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedChar(char) => write!(f, "unexpected char '{}'", char.escape_default()),
+            Self::UnterminatedString => write!(f, "unterminated string"),
+        }
+    }
 }
 
 impl fmt::Display for LexError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "unexpected char '{}'", self.char)
+        self.kind.fmt(f)
     }
 }
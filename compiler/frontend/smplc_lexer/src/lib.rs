@@ -3,21 +3,23 @@ mod error;
 mod number;
 mod skip;
 mod specials;
+mod string;
 mod token;
 mod word;
 
 #[cfg(test)]
 mod tests;
 
-pub use error::LexError;
+pub use error::{LexError, LexErrorKind};
 pub use token::{Token, TokenTag};
 
 use cursor::Cursor;
 use number::lex_number;
 use skip::skip;
-use smplc_ast::{Pos, Span};
-use specials::{one_char_specials, two_char_specials};
-use word::lex_word;
+use smplc_ast::{FileId, Pos, Span};
+use specials::{one_char_specials, three_char_specials, two_char_specials};
+use string::lex_string;
+use word::{lex_label, lex_word};
 
 pub struct Lexer<'source> {
     cursor: Cursor<'source>,
@@ -26,8 +28,13 @@ pub struct Lexer<'source> {
 
 impl<'source> Lexer<'source> {
     pub fn new(source: &'source str) -> Self {
+        Self::in_file(source, FileId::default())
+    }
+
+    /// the spans of the tokens will point to `file`
+    pub fn in_file(source: &'source str, file: FileId) -> Self {
         Self {
-            cursor: Cursor::new(source),
+            cursor: Cursor::new(source, file),
             ended: false,
         }
     }
@@ -56,14 +63,23 @@ impl<'source> Lexer<'source> {
                 value: self.cursor.slice(start.index(), end),
             })
         } else {
-            Err(self.unexpected_char(start))
+            Err(self.error(start))
         }
     }
 
-    fn unexpected_char(&mut self, pos: Pos) -> LexError {
+    /// the lexing goes on after the broken token:
+    /// an unterminated string is skipped up to the end of the line, otherwise the char is skipped
+    fn error(&mut self, start: Pos) -> LexError {
+        if self.cursor.slice(start.index(), start.index() + 1) == "\"" {
+            return LexError {
+                kind: LexErrorKind::UnterminatedString,
+                span: Span::with_end(start, self.cursor.index()),
+            };
+        }
+
         LexError {
-            char: self.cursor.next_ch(),
-            pos,
+            kind: LexErrorKind::UnexpectedChar(self.cursor.next_ch()),
+            span: Span::with_len(start, 1),
         }
     }
 }
@@ -81,8 +97,15 @@ impl<'source> Iterator for Lexer<'source> {
 }
 
 pub fn lex(cursor: &mut Cursor) -> Option<TokenTag> {
+    // an unterminated string has already consumed its chars, nothing else is tried on them
+    if cursor.check('"') {
+        return lex_string(cursor);
+    }
+
     lex_number(cursor)
         .or_else(|| lex_word(cursor))
+        .or_else(|| lex_label(cursor))
+        .or_else(|| three_char_specials(cursor))
         .or_else(|| two_char_specials(cursor))
         .or_else(|| one_char_specials(cursor))
 }
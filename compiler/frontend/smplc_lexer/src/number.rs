@@ -29,8 +29,16 @@ pub fn prefixed(cursor: &mut Cursor, radix: u32) -> LiteralType {
 }
 
 pub fn decimal(cursor: &mut Cursor) -> LiteralType {
+    // in `t.0.1` the numbers are indices of tuple elements
+    let before = cursor.slice_before_current(2);
+    let is_element_index = before.ends_with('.') && !before.starts_with('.');
+
     literal(cursor, 10);
 
+    if is_element_index {
+        return LiteralType::Int;
+    }
+
     let has_fraction = fraction(cursor);
     let has_exponential = exponential_part(cursor);
     let is_complex = complex_postfix(cursor);
@@ -45,7 +53,8 @@ pub fn decimal(cursor: &mut Cursor) -> LiteralType {
 }
 
 pub fn fraction(cursor: &mut Cursor) -> bool {
-    if cursor.check('.') {
+    // `1..2` is a range, not a fraction
+    if cursor.check('.') && !cursor.check_slice("..") {
         cursor.next_ch();
 
         literal(cursor, 10);
@@ -1,6 +1,18 @@
 use crate::cursor::Cursor;
 use crate::TokenTag;
 
+pub fn three_char_specials(cursor: &mut Cursor) -> Option<TokenTag> {
+    match cursor.slice_from_current(3) {
+        "..=" => Some(TokenTag::DotDotEq),
+        "|||" => Some(TokenTag::BitOr),
+        "&&&" => Some(TokenTag::BitAnd),
+        "^^^" => Some(TokenTag::BitXor),
+
+        _ => None,
+    }
+    .inspect(|_| cursor.skip(3))
+}
+
 pub fn two_char_specials(cursor: &mut Cursor) -> Option<TokenTag> {
     match cursor.slice_from_current(2) {
         ">=" => Some(TokenTag::Ge),
@@ -8,6 +20,14 @@ pub fn two_char_specials(cursor: &mut Cursor) -> Option<TokenTag> {
         "!=" => Some(TokenTag::Ne),
         "==" => Some(TokenTag::Eq),
         "->" => Some(TokenTag::Arrow),
+        "=>" => Some(TokenTag::FatArrow),
+        "<<" => Some(TokenTag::Shl),
+        ">>" => Some(TokenTag::Shr),
+        "+=" => Some(TokenTag::PlusAssign),
+        "-=" => Some(TokenTag::MinusAssign),
+        "*=" => Some(TokenTag::StarAssign),
+        "/=" => Some(TokenTag::SlashAssign),
+        ".." => Some(TokenTag::DotDot),
 
         _ => None,
     }
@@ -23,6 +43,8 @@ pub fn one_char_specials(cursor: &mut Cursor) -> Option<TokenTag> {
         '}' => Some(TokenTag::RBrace),
         '(' => Some(TokenTag::LParen),
         ')' => Some(TokenTag::RParen),
+        '[' => Some(TokenTag::LBracket),
+        ']' => Some(TokenTag::RBracket),
         '=' => Some(TokenTag::Assign),
         '|' => Some(TokenTag::Or),
         '&' => Some(TokenTag::And),
@@ -32,7 +54,11 @@ pub fn one_char_specials(cursor: &mut Cursor) -> Option<TokenTag> {
         '-' => Some(TokenTag::Minus),
         '*' => Some(TokenTag::Star),
         '/' => Some(TokenTag::Slash),
+        '%' => Some(TokenTag::Percent),
+        '~' => Some(TokenTag::Tilde),
         '!' => Some(TokenTag::Not),
+        '.' => Some(TokenTag::Dot),
+        '#' => Some(TokenTag::Hash),
 
         _ => None,
     }
@@ -0,0 +1,26 @@
+use smplc_ast::LiteralType;
+
+use crate::{cursor::Cursor, TokenTag};
+
+/// the value of the token keeps the quotes and the escape sequences as is
+pub fn lex_string(cursor: &mut Cursor) -> Option<TokenTag> {
+    if !cursor.check('"') {
+        return None;
+    }
+
+    cursor.next_ch();
+
+    while !cursor.check('"') {
+        if cursor.is_eof() || cursor.check('\n') {
+            return None;
+        }
+
+        if cursor.next_ch() == '\\' && !cursor.is_eof() {
+            cursor.next_ch();
+        }
+    }
+
+    cursor.next_ch();
+
+    Some(TokenTag::Literal(LiteralType::String))
+}
@@ -1,6 +1,6 @@
 use smplc_ast::LiteralType;
 
-use crate::{Lexer, TokenTag};
+use crate::{LexErrorKind, Lexer, TokenTag};
 
 macro_rules! lexer_test {
     (
@@ -39,6 +39,39 @@ fn numbers() {
     ];
 }
 
+#[test]
+fn ranges() {
+    lexer_test![
+        "0..10 1..=n 0.5..1.5";
+
+        TokenTag::Literal(LiteralType::Int), "0";
+        TokenTag::DotDot;
+        TokenTag::Literal(LiteralType::Int), "10";
+        TokenTag::Literal(LiteralType::Int), "1";
+        TokenTag::DotDotEq;
+        TokenTag::Id, "n";
+        TokenTag::Literal(LiteralType::Real), "0.5";
+        TokenTag::DotDot;
+        TokenTag::Literal(LiteralType::Real), "1.5";
+    ];
+}
+
+#[test]
+fn tuple_elements() {
+    lexer_test![
+        "t.0.1 t.1";
+
+        TokenTag::Id, "t";
+        TokenTag::Dot;
+        TokenTag::Literal(LiteralType::Int), "0";
+        TokenTag::Dot;
+        TokenTag::Literal(LiteralType::Int), "1";
+        TokenTag::Id, "t";
+        TokenTag::Dot;
+        TokenTag::Literal(LiteralType::Int), "1";
+    ];
+}
+
 #[test]
 fn bool() {
     lexer_test![
@@ -49,10 +82,21 @@ fn bool() {
     ];
 }
 
+#[test]
+fn strings() {
+    lexer_test![
+        r#""x = {}" "" "say \"hi\"\n""#;
+
+        TokenTag::Literal(LiteralType::String), r#""x = {}""#;
+        TokenTag::Literal(LiteralType::String), r#""""#;
+        TokenTag::Literal(LiteralType::String), r#""say \"hi\"\n""#;
+    ];
+}
+
 #[test]
 fn keywords() {
     lexer_test![
-        "return while else let fn if continue break const";
+        "return while else let fn if continue break const for in step struct import as loop do match static";
 
         TokenTag::Return;
         TokenTag::While;
@@ -63,6 +107,30 @@ fn keywords() {
         TokenTag::Continue;
         TokenTag::Break;
         TokenTag::Const;
+        TokenTag::For;
+        TokenTag::In;
+        TokenTag::Step;
+        TokenTag::Struct;
+        TokenTag::Import;
+        TokenTag::As;
+        TokenTag::Loop;
+        TokenTag::Do;
+        TokenTag::Match;
+        TokenTag::Static;
+    ];
+}
+
+#[test]
+fn labels() {
+    lexer_test![
+        "'outer: while break 'outer;";
+
+        TokenTag::Label, "'outer";
+        TokenTag::Colon;
+        TokenTag::While;
+        TokenTag::Break;
+        TokenTag::Label, "'outer";
+        TokenTag::Semicolon;
     ];
 }
 
@@ -78,10 +146,13 @@ fn id() {
 fn specials() {
     lexer_test![
         "
-            ;,(){}=
+            ;,(){}[]=
             != >= > <= < ==
             | & !
-            + - * /
+            + - * / %
+            += -= *= /=
+            ||| &&& ^^^ ~ << >>
+            . => #
         ";
         TokenTag::Semicolon;
         TokenTag::Comma;
@@ -89,6 +160,8 @@ fn specials() {
         TokenTag::RParen;
         TokenTag::LBrace;
         TokenTag::RBrace;
+        TokenTag::LBracket;
+        TokenTag::RBracket;
         TokenTag::Assign;
 
         TokenTag::Ne;
@@ -106,5 +179,46 @@ fn specials() {
         TokenTag::Minus;
         TokenTag::Star;
         TokenTag::Slash;
+        TokenTag::Percent;
+
+        TokenTag::PlusAssign;
+        TokenTag::MinusAssign;
+        TokenTag::StarAssign;
+        TokenTag::SlashAssign;
+
+        TokenTag::BitOr;
+        TokenTag::BitAnd;
+        TokenTag::BitXor;
+        TokenTag::Tilde;
+        TokenTag::Shl;
+        TokenTag::Shr;
+
+        TokenTag::Dot;
+        TokenTag::FatArrow;
+        TokenTag::Hash;
     ];
 }
+
+#[test]
+fn errors() {
+    let mut lexer = Lexer::new("a @ b \"abc\nc \"def");
+
+    assert_eq!(lexer.next_token().unwrap().value, "a");
+    assert_eq!(
+        lexer.next_token().unwrap_err().kind,
+        LexErrorKind::UnexpectedChar('@')
+    );
+    assert_eq!(lexer.next_token().unwrap().value, "b");
+
+    let error = lexer.next_token().unwrap_err();
+
+    assert_eq!(error.kind, LexErrorKind::UnterminatedString);
+    assert_eq!(error.span.len(), 4);
+
+    assert_eq!(lexer.next_token().unwrap().value, "c");
+    assert_eq!(
+        lexer.next_token().unwrap_err().kind,
+        LexErrorKind::UnterminatedString
+    );
+    assert_eq!(lexer.next_token().unwrap().tag, TokenTag::EOF);
+}
@@ -12,14 +12,24 @@ pub struct Token<'source> {
 #[derive(Clone, Debug, PartialEq, Copy)]
 pub enum TokenTag {
     // keywords
+    As,
     Break,
     Continue,
     Const,
+    Do,
     Else,
     Fn,
+    For,
     If,
+    Import,
+    In,
     Let,
+    Loop,
+    Match,
     Return,
+    Static,
+    Step,
+    Struct,
     While,
 
     // special chars
@@ -28,27 +38,46 @@ pub enum TokenTag {
     RBrace,
     LParen,
     RParen,
+    LBracket,
+    RBracket,
     Assign,
+    PlusAssign,
+    MinusAssign,
+    StarAssign,
+    SlashAssign,
     Arrow,
+    FatArrow,
     Colon,
     Comma,
     Or,
     And,
+    BitOr,
+    BitAnd,
+    BitXor,
     Ne,
     Eq,
     Ge,
     Gt,
     Le,
     Lt,
+    Shl,
+    Shr,
     Plus,
     Minus,
     Star,
     Slash,
+    Percent,
     Not,
+    Tilde,
+    Dot,
+    DotDot,
+    DotDotEq,
+    Hash,
 
     // other
     Literal(LiteralType),
     Id,
+    Label,
 
     EOF,
 }
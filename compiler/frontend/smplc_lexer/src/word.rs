@@ -8,13 +8,23 @@ pub fn lex_word(cursor: &mut Cursor) -> Option<TokenTag> {
     }
 
     Some(match word_literal(cursor) {
+        "as" => TokenTag::As,
         "let" => TokenTag::Let,
         "else" => TokenTag::Else,
         "fn" => TokenTag::Fn,
         "if" => TokenTag::If,
+        "import" => TokenTag::Import,
         "return" => TokenTag::Return,
         "while" => TokenTag::While,
+        "loop" => TokenTag::Loop,
+        "do" => TokenTag::Do,
+        "match" => TokenTag::Match,
         "const" => TokenTag::Const,
+        "static" => TokenTag::Static,
+        "for" => TokenTag::For,
+        "in" => TokenTag::In,
+        "step" => TokenTag::Step,
+        "struct" => TokenTag::Struct,
 
         "continue" => TokenTag::Continue,
         "break" => TokenTag::Break,
@@ -26,6 +36,19 @@ pub fn lex_word(cursor: &mut Cursor) -> Option<TokenTag> {
     })
 }
 
+/// `'outer`, the value of the token keeps the quote
+pub fn lex_label(cursor: &mut Cursor) -> Option<TokenTag> {
+    if !cursor.check('\'') || !cursor.peek().is_some_and(is_word_start) {
+        return None;
+    }
+
+    cursor.next_ch();
+
+    word_literal(cursor);
+
+    Some(TokenTag::Label)
+}
+
 fn is_word_start(char: char) -> bool {
     char.is_alphabetic() || matches!(char, '_' | '$')
 }
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use smplc_ast::Span;
-use smplc_lexer::{Token, TokenTag};
+use smplc_lexer::{LexError, LexErrorKind, Token, TokenTag};
 
 pub type ParseResult<'source, T> = Result<T, ParseError<'source>>;
 
@@ -14,10 +14,13 @@ pub struct ParseError<'source> {
 #[derive(Debug)]
 pub enum ParseErrorKind<'source> {
     UnexpectedToken(TokenTag, &'source str),
-    UnexpectedChar(char),
+    Lex(LexErrorKind),
     InvalidSwizzle,
+    InvalidAssignment,
     BreakOutsideLoop,
     ContinueOutsideLoop,
+    UnknownLabel(&'source str),
+    MissingElse,
 }
 
 impl<'source> ParseError<'source> {
@@ -33,6 +36,13 @@ impl<'source> ParseError<'source> {
             span,
         }
     }
+
+    pub fn missing_else(span: Span) -> Self {
+        Self {
+            kind: ParseErrorKind::MissingElse,
+            span,
+        }
+    }
 }
 
 impl fmt::Display for ParseErrorKind<'_> {
@@ -50,22 +60,32 @@ impl fmt::Display for ParseErrorKind<'_> {
                 write!(f, "invalid swizzle combination")
             }
 
+            Self::InvalidAssignment => {
+                write!(f, "invalid left-hand side of assignment")
+            }
+
             Self::ContinueOutsideLoop => {
                 write!(f, "using continue outside loop")
             }
 
-            Self::UnexpectedChar(char) => {
-                write!(f, "unexpected char '{char}'")
+            Self::UnknownLabel(label) => {
+                write!(f, "unknown label \"{label}\"")
+            }
+
+            Self::MissingElse => {
+                write!(f, "if-expression requires an else branch")
             }
+
+            Self::Lex(kind) => kind.fmt(f),
         }
     }
 }
 
-impl From<smplc_lexer::LexError> for ParseError<'_> {
-    fn from(value: smplc_lexer::LexError) -> Self {
+impl From<LexError> for ParseError<'_> {
+    fn from(LexError { kind, span }: LexError) -> Self {
         Self {
-            kind: ParseErrorKind::UnexpectedChar(value.char),
-            span: Span::with_len(value.pos, 1),
+            kind: ParseErrorKind::Lex(kind),
+            span,
         }
     }
 }
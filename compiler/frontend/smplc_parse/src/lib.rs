@@ -5,7 +5,6 @@ mod token_stream;
 #[cfg(test)]
 mod tests;
 
-use error::ParseResult;
 use parse::{Parse, TryParse};
 use smplc_ast::Declaration;
 
@@ -15,16 +14,25 @@ pub use token_stream::TokenStream;
 
 use token_stream::Tokens;
 
+/// Parses the whole file, a broken declaration or statement is skipped
+/// and the parsing goes on, so all the syntax errors are returned at once
 pub fn parse<'source, TS: Tokens<'source>>(
     mut token_stream: TokenStream<'source, TS>,
-) -> ParseResult<'source, Vec<Declaration<'source>>> {
+) -> Result<Vec<Declaration<'source>>, Vec<ParseError<'source>>> {
     let mut declarations = Vec::new();
 
     while !token_stream.is_end() {
-        let declaration = Declaration::parse(&mut token_stream)?;
-
-        declarations.push(declaration);
+        match Declaration::parse(&mut token_stream) {
+            Ok(declaration) => declarations.push(declaration),
+            Err(err) => token_stream.recover_declaration(err),
+        }
     }
 
-    Ok(declarations)
+    let errors = token_stream.into_errors();
+
+    if errors.is_empty() {
+        Ok(declarations)
+    } else {
+        Err(errors)
+    }
 }
@@ -1,10 +1,12 @@
-use smplc_ast::{Block, Statement};
+use smplc_ast::{Block, BlockExpr, Expr, MakeSpanned, Span, Spanned, Statement};
 use smplc_lexer::TokenTag;
 
 use crate::error::ParseResult;
 use crate::token_stream::Tokens;
 use crate::{Parse, TokenStream};
 
+use super::statement::expr_statement;
+
 impl<'source> Parse<'source> for Block<'source> {
     fn parse<TS: Tokens<'source>>(
         token_stream: &mut TokenStream<'source, TS>,
@@ -13,10 +15,18 @@ impl<'source> Parse<'source> for Block<'source> {
 
         token_stream.consume(TokenTag::LBrace)?;
 
-        while !token_stream.check(TokenTag::RBrace) {
-            let next_stmt = Statement::parse(token_stream)?;
+        let depth = token_stream.depth();
 
-            statements.push(next_stmt);
+        // a broken statement is skipped, so the following ones are checked as well,
+        // a declaration means that the block misses `}`
+        while !token_stream.check(TokenTag::RBrace)
+            && !token_stream.is_end()
+            && !token_stream.is_declaration()
+        {
+            match token_stream.work(Statement::parse) {
+                Ok(stmt) => statements.push(stmt),
+                Err(err) => token_stream.recover_statement(err, depth),
+            }
         }
 
         token_stream.consume(TokenTag::RBrace)?;
@@ -24,3 +34,57 @@ impl<'source> Parse<'source> for Block<'source> {
         Ok(Block { statements })
     }
 }
+
+impl<'source> Parse<'source> for BlockExpr<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        let mut statements = Vec::new();
+
+        token_stream.consume(TokenTag::LBrace)?;
+
+        // the value is the expression that isn't followed by `;`,
+        // a block that ends with a statement, like `return`, has no value
+        let value = loop {
+            if token_stream.check(TokenTag::RBrace) && !statements.is_empty() {
+                break None;
+            }
+
+            let starts_statement = matches!(
+                token_stream.current().tag,
+                TokenTag::Let
+                    | TokenTag::If
+                    | TokenTag::Return
+                    | TokenTag::While
+                    | TokenTag::Do
+                    | TokenTag::Loop
+                    | TokenTag::For
+                    | TokenTag::Match
+                    | TokenTag::Continue
+                    | TokenTag::Break
+                    | TokenTag::Label
+            );
+
+            if starts_statement {
+                statements.push(token_stream.work(Statement::parse)?);
+
+                continue;
+            }
+
+            let expr = Spanned::<Expr>::parse(token_stream)?;
+
+            if token_stream.check(TokenTag::RBrace) {
+                break Some(Box::new(expr));
+            }
+
+            let span = expr.span();
+            let stmt = Statement::Expr(expr_statement(token_stream, expr)?);
+
+            statements.push(stmt.spanned(Span::unite(span, token_stream.prev_span())));
+        };
+
+        token_stream.consume(TokenTag::RBrace)?;
+
+        Ok(BlockExpr { statements, value })
+    }
+}
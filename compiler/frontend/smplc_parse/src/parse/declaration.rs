@@ -9,15 +9,64 @@ impl<'source> Parse<'source> for Declaration<'source> {
     fn parse<TS: Tokens<'source>>(
         token_stream: &mut TokenStream<'source, TS>,
     ) -> ParseResult<'source, Self> {
+        let mut attributes = Vec::new();
+
+        while token_stream.check(TokenTag::Hash) {
+            attributes.push(Attribute::parse(token_stream)?);
+        }
+
         match token_stream.current().tag {
-            TokenTag::Const => ConstantDeclaration::parse(token_stream).map(Self::Constant),
-            TokenTag::Fn => FunctionDeclaration::parse(token_stream).map(Self::Function),
+            TokenTag::Const if token_stream.peek(1)?.is_some_and(|t| t.tag == TokenTag::Fn) => {
+                FunctionDeclaration::parse(token_stream)
+                    .map(|decl| Self::Function(FunctionDeclaration { attributes, ..decl }))
+            }
+            TokenTag::Const => ConstantDeclaration::parse(token_stream)
+                .map(|decl| Self::Constant(ConstantDeclaration { attributes, ..decl })),
+            TokenTag::Static => StaticDeclaration::parse(token_stream)
+                .map(|decl| Self::Static(StaticDeclaration { attributes, ..decl })),
+            TokenTag::Fn => FunctionDeclaration::parse(token_stream)
+                .map(|decl| Self::Function(FunctionDeclaration { attributes, ..decl })),
+
+            // structs and imports have no attributes
+            TokenTag::Struct if attributes.is_empty() => {
+                StructDeclaration::parse(token_stream).map(Self::Struct)
+            }
+            TokenTag::Import if attributes.is_empty() => {
+                ImportDeclaration::parse(token_stream).map(Self::Import)
+            }
 
             _ => Err(token_stream.unexpected_token()),
         }
     }
 }
 
+/// `#[id]` or `#[id(arg, ...)]`
+impl<'source> Parse<'source> for Attribute<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        token_stream.consume(TokenTag::Hash)?;
+        token_stream.consume(TokenTag::LBracket)?;
+
+        let id = Id::parse(token_stream)?;
+        let mut args = Vec::new();
+
+        if token_stream.try_consume(TokenTag::LParen)? {
+            args.push(Id::parse(token_stream)?);
+
+            while token_stream.try_consume(TokenTag::Comma)? {
+                args.push(Id::parse(token_stream)?);
+            }
+
+            token_stream.consume(TokenTag::RParen)?;
+        }
+
+        token_stream.consume(TokenTag::RBracket)?;
+
+        Ok(Self { id, args })
+    }
+}
+
 impl<'source> Parse<'source> for ConstantDeclaration<'source> {
     fn parse<TS: Tokens<'source>>(
         token_stream: &mut TokenStream<'source, TS>,
@@ -28,7 +77,7 @@ impl<'source> Parse<'source> for ConstantDeclaration<'source> {
 
         token_stream.consume(TokenTag::Colon)?;
 
-        let ty = Id::parse(token_stream)?;
+        let ty = Spanned::<Type>::parse(token_stream)?;
 
         token_stream.consume(TokenTag::Assign)?;
 
@@ -36,7 +85,58 @@ impl<'source> Parse<'source> for ConstantDeclaration<'source> {
 
         token_stream.consume(TokenTag::Semicolon)?;
 
-        Ok(Self { id, ty, value })
+        Ok(Self {
+            id,
+            ty,
+            value,
+            attributes: Vec::new(),
+        })
+    }
+}
+
+impl<'source> Parse<'source> for StaticDeclaration<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        token_stream.consume(TokenTag::Static)?;
+
+        let id = Id::parse(token_stream)?;
+
+        token_stream.consume(TokenTag::Colon)?;
+
+        let ty = Spanned::<Type>::parse(token_stream)?;
+
+        token_stream.consume(TokenTag::Assign)?;
+
+        let value = Spanned::<Expr>::parse(token_stream)?;
+
+        token_stream.consume(TokenTag::Semicolon)?;
+
+        Ok(Self {
+            id,
+            ty,
+            value,
+            attributes: Vec::new(),
+        })
+    }
+}
+
+impl<'source> Parse<'source> for ImportDeclaration<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        token_stream.consume(TokenTag::Import)?;
+
+        let path = token_stream.consume(TokenTag::Literal(LiteralType::String))?;
+
+        token_stream.consume(TokenTag::Semicolon)?;
+
+        // the path is taken as is, without the quotes
+        let value = &path.value[1..path.value.len() - 1];
+
+        Ok(Self {
+            path: Spanned(value, path.span),
+        })
     }
 }
 
@@ -44,6 +144,8 @@ impl<'source> Parse<'source> for FunctionDeclaration<'source> {
     fn parse<TS: Tokens<'source>>(
         token_stream: &mut TokenStream<'source, TS>,
     ) -> ParseResult<'source, Self> {
+        let is_const = token_stream.try_consume(TokenTag::Const)?;
+
         token_stream.consume(TokenTag::Fn)?;
 
         let id = Id::parse(token_stream)?;
@@ -52,17 +154,21 @@ impl<'source> Parse<'source> for FunctionDeclaration<'source> {
         let ret_ty = {
             token_stream
                 .try_consume(TokenTag::Arrow)?
-                .then(|| Id::parse(token_stream))
+                .then(|| Spanned::<Type>::parse(token_stream))
                 .transpose()?
         };
 
         let body = Block::parse(token_stream)?;
+        let end = token_stream.prev_span();
 
         Ok(Self {
             id,
             ret_ty,
             args,
             body,
+            end,
+            is_const,
+            attributes: Vec::new(),
         })
     }
 }
@@ -97,8 +203,48 @@ impl<'source> Parse<'source> for FunctionArg<'source> {
 
         token_stream.consume(TokenTag::Colon)?;
 
-        let ty = Id::parse(token_stream)?;
+        let ty = Spanned::<Type>::parse(token_stream)?;
 
         Ok(FunctionArg { id, ty })
     }
 }
+
+impl<'source> Parse<'source> for StructDeclaration<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        token_stream.consume(TokenTag::Struct)?;
+
+        let id = Id::parse(token_stream)?;
+
+        token_stream.consume(TokenTag::LBrace)?;
+
+        let mut fields = vec![StructField::parse(token_stream)?];
+
+        while token_stream.try_consume(TokenTag::Comma)? {
+            if token_stream.check(TokenTag::RBrace) {
+                break;
+            }
+
+            fields.push(StructField::parse(token_stream)?);
+        }
+
+        token_stream.consume(TokenTag::RBrace)?;
+
+        Ok(Self { id, fields })
+    }
+}
+
+impl<'source> Parse<'source> for StructField<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        let id = Id::parse(token_stream)?;
+
+        token_stream.consume(TokenTag::Colon)?;
+
+        let ty = Spanned::<Type>::parse(token_stream)?;
+
+        Ok(StructField { id, ty })
+    }
+}
@@ -11,6 +11,9 @@ impl<'source> Parse<'source> for Spanned<Expr<'source>> {
     }
 }
 
+/// `as` binds tighter than the binary operators, but looser than the unary ones
+const AS_POWER: usize = 23;
+
 fn expr_bp<'source, TS: Tokens<'source>>(
     token_stream: &mut TokenStream<'source, TS>,
     min_bp: usize,
@@ -29,6 +32,56 @@ fn expr_bp<'source, TS: Tokens<'source>>(
                 swizzle,
             }
             .spanned(span);
+        } else if token_stream.try_consume(TokenTag::LBracket)? {
+            let index = Spanned::<Expr>::parse(token_stream)?;
+
+            token_stream.consume(TokenTag::RBracket)?;
+
+            let span = Span::unite(lhs.span(), token_stream.prev_span());
+
+            lhs = Expr::Index {
+                lhs: Box::new(lhs),
+                index: Box::new(index),
+            }
+            .spanned(span);
+        } else if token_stream.try_consume(TokenTag::Dot)? {
+            let field = match token_stream.current() {
+                Token {
+                    tag: TokenTag::Literal(LiteralType::Int),
+                    value,
+                    span,
+                } => {
+                    token_stream.next_token()?;
+
+                    Id::new(value, span)
+                }
+
+                _ => Id::parse(token_stream)?,
+            };
+
+            let span = Span::unite(lhs.span(), field.span());
+
+            lhs = Expr::Field {
+                lhs: Box::new(lhs),
+                field,
+            }
+            .spanned(span);
+        } else if token_stream.check(TokenTag::As) {
+            if AS_POWER < min_bp {
+                break;
+            }
+
+            token_stream.next_token()?;
+
+            let ty = Spanned::<Type>::parse(token_stream)?;
+
+            let span = Span::unite(lhs.span(), ty.span());
+
+            lhs = Expr::As {
+                expr: Box::new(lhs),
+                ty,
+            }
+            .spanned(span);
         } else if let Some(op) = BinOp::try_parse(token_stream) {
             let (l_bp, r_bp) = op.power();
 
@@ -56,7 +109,7 @@ fn expr_bp<'source, TS: Tokens<'source>>(
     Ok(lhs)
 }
 
-fn parse_fact<'source, TS: Tokens<'source>>(
+pub fn parse_fact<'source, TS: Tokens<'source>>(
     token_stream: &mut TokenStream<'source, TS>,
 ) -> ParseResult<'source, Expr<'source>> {
     let fact = match token_stream.current() {
@@ -75,6 +128,10 @@ fn parse_fact<'source, TS: Tokens<'source>>(
                 token_stream.consume(TokenTag::RParen)?;
 
                 Expr::Call(Call { id, args })
+            } else if is_struct_literal(token_stream)? {
+                let fields = parse_field_inits(token_stream)?;
+
+                Expr::Struct(StructLiteral { id, fields })
             } else {
                 Expr::Atom(Atom::Id(id))
             }
@@ -88,9 +145,97 @@ fn parse_fact<'source, TS: Tokens<'source>>(
 
             let expr = Spanned::<Expr>::parse(token_stream)?;
 
+            // `(a)` is just `a` in parentheses, `(a,)` is a tuple
+            let value = if token_stream.check(TokenTag::Comma) {
+                let mut elems = vec![expr];
+
+                while token_stream.try_consume(TokenTag::Comma)? {
+                    if token_stream.check(TokenTag::RParen) {
+                        break;
+                    }
+
+                    elems.push(Spanned::<Expr>::parse(token_stream)?);
+                }
+
+                Expr::Tuple(elems)
+            } else {
+                expr.0
+            };
+
             token_stream.consume(TokenTag::RParen)?;
 
-            expr.0
+            value
+        }
+
+        Token {
+            tag: TokenTag::If, ..
+        } => {
+            token_stream.consume(TokenTag::If)?;
+
+            let cond = Spanned::<Expr>::parse(token_stream)?;
+            let body = BlockExpr::parse(token_stream)?;
+
+            if !token_stream.check(TokenTag::Else) {
+                return Err(ParseError::missing_else(token_stream.current().span));
+            }
+
+            token_stream.consume(TokenTag::Else)?;
+
+            let else_body = if token_stream.check(TokenTag::If) {
+                let value = token_stream.work(|token_stream| parse_fact(token_stream))?;
+
+                BlockExpr {
+                    statements: Vec::new(),
+                    value: Some(Box::new(value)),
+                }
+            } else {
+                BlockExpr::parse(token_stream)?
+            };
+
+            Expr::If {
+                cond: Box::new(cond),
+                body,
+                else_body,
+            }
+        }
+
+        Token {
+            tag: TokenTag::LBrace,
+            ..
+        } => Expr::Block(BlockExpr::parse(token_stream)?),
+
+        Token {
+            tag: TokenTag::LBracket,
+            ..
+        } => {
+            token_stream.consume(TokenTag::LBracket)?;
+
+            let first = Spanned::<Expr>::parse(token_stream)?;
+
+            let array = if token_stream.try_consume(TokenTag::Semicolon)? {
+                let len = Spanned::<Expr>::parse(token_stream)?;
+
+                Expr::ArrayRepeat {
+                    value: Box::new(first),
+                    len: Box::new(len),
+                }
+            } else {
+                let mut elems = vec![first];
+
+                while token_stream.try_consume(TokenTag::Comma)? {
+                    if token_stream.check(TokenTag::RBracket) {
+                        break;
+                    }
+
+                    elems.push(Spanned::<Expr>::parse(token_stream)?);
+                }
+
+                Expr::Array(elems)
+            };
+
+            token_stream.consume(TokenTag::RBracket)?;
+
+            array
         }
 
         Token {
@@ -121,6 +266,52 @@ fn parse_fact<'source, TS: Tokens<'source>>(
     Ok(fact)
 }
 
+/// A struct literal is told apart from a block
+/// that follows an identifier by looking for `{ field:`
+fn is_struct_literal<'source, TS: Tokens<'source>>(
+    token_stream: &mut TokenStream<'source, TS>,
+) -> ParseResult<'source, bool> {
+    let tag = |token: Option<Token>| token.map(|token| token.tag);
+
+    Ok(token_stream.check(TokenTag::LBrace)
+        && tag(token_stream.peek(1)?) == Some(TokenTag::Id)
+        && tag(token_stream.peek(2)?) == Some(TokenTag::Colon))
+}
+
+fn parse_field_inits<'source, TS: Tokens<'source>>(
+    token_stream: &mut TokenStream<'source, TS>,
+) -> ParseResult<'source, Vec<FieldInit<'source>>> {
+    token_stream.consume(TokenTag::LBrace)?;
+
+    let mut fields = vec![FieldInit::parse(token_stream)?];
+
+    while token_stream.try_consume(TokenTag::Comma)? {
+        if token_stream.check(TokenTag::RBrace) {
+            break;
+        }
+
+        fields.push(FieldInit::parse(token_stream)?);
+    }
+
+    token_stream.consume(TokenTag::RBrace)?;
+
+    Ok(fields)
+}
+
+impl<'source> Parse<'source> for FieldInit<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        let id = Id::parse(token_stream)?;
+
+        token_stream.consume(TokenTag::Colon)?;
+
+        let value = Spanned::<Expr>::parse(token_stream)?;
+
+        Ok(FieldInit { id, value })
+    }
+}
+
 fn parse_call_args<'source, TS: Tokens<'source>>(
     token_stream: &mut TokenStream<'source, TS>,
 ) -> ParseResult<'source, Vec<Spanned<Expr<'source>>>> {
@@ -18,6 +18,12 @@ impl<'source> TryParse<'source> for BinOp {
             TokenTag::Minus => Self::Sub,
             TokenTag::Star => Self::Mul,
             TokenTag::Slash => Self::Div,
+            TokenTag::Percent => Self::Rem,
+            TokenTag::BitOr => Self::BitOr,
+            TokenTag::BitXor => Self::BitXor,
+            TokenTag::BitAnd => Self::BitAnd,
+            TokenTag::Shl => Self::Shl,
+            TokenTag::Shr => Self::Shr,
 
             _ => return None,
         };
@@ -31,6 +37,7 @@ impl<'source> TryParse<'source> for UnOp {
         let op = match token_stream.current().tag {
             TokenTag::Not => Self::Not,
             TokenTag::Minus => Self::Neg,
+            TokenTag::Tilde => Self::BitNot,
 
             _ => return None,
         };
@@ -1,5 +1,5 @@
 use smplc_ast::*;
-use smplc_lexer::TokenTag;
+use smplc_lexer::{Token, TokenTag};
 
 use crate::{
     error::{ParseError, ParseErrorKind, ParseResult},
@@ -7,42 +7,44 @@ use crate::{
     Parse, TokenStream,
 };
 
+use super::expr::parse_fact;
+
 impl<'source> Parse<'source> for Statement<'source> {
     fn parse<TS: Tokens<'source>>(
         token_stream: &mut TokenStream<'source, TS>,
     ) -> ParseResult<'source, Self> {
-        match token_stream.current().tag {
+        // a label goes before the loop: `'outer: while ...`
+        let tag = match token_stream.current().tag {
+            TokenTag::Label => token_stream
+                .peek(2)?
+                .map_or(TokenTag::EOF, |token| token.tag),
+
+            tag => tag,
+        };
+
+        match tag {
+            TokenTag::Let if is_destructure(token_stream)? => {
+                DestructureStatement::parse(token_stream).map(Self::Destructure)
+            }
+
             TokenTag::Let => DeclareStatement::parse(token_stream).map(Self::Declare),
             TokenTag::If => IfStatement::parse(token_stream).map(Self::If),
             TokenTag::Return => ReturnStatement::parse(token_stream).map(Self::Return),
             TokenTag::While => WhileStatement::parse(token_stream).map(Self::While),
+            TokenTag::Do => DoWhileStatement::parse(token_stream).map(Self::DoWhile),
+            TokenTag::Loop => LoopStatement::parse(token_stream).map(Self::Loop),
+            TokenTag::For => {
+                ForStatement::parse(token_stream).map(|stmt| Self::For(Box::new(stmt)))
+            }
 
-            TokenTag::Continue => {
-                if !token_stream.in_loop {
-                    return Err(ParseError {
-                        kind: ParseErrorKind::ContinueOutsideLoop,
-                        span: token_stream.current().span,
-                    });
-                }
-
-                token_stream.next_token()?;
-                token_stream.consume(TokenTag::Semicolon)?;
+            TokenTag::Match => MatchStatement::parse(token_stream).map(Self::Match),
 
-                Ok(Self::Continue)
+            TokenTag::Continue => {
+                jump_label(token_stream, ParseErrorKind::ContinueOutsideLoop).map(Self::Continue)
             }
 
             TokenTag::Break => {
-                if !token_stream.in_loop {
-                    return Err(ParseError {
-                        kind: ParseErrorKind::BreakOutsideLoop,
-                        span: token_stream.current().span,
-                    });
-                }
-
-                token_stream.next_token()?;
-                token_stream.consume(TokenTag::Semicolon)?;
-
-                Ok(Self::Break)
+                jump_label(token_stream, ParseErrorKind::BreakOutsideLoop).map(Self::Break)
             }
 
             _ => ExprStatement::parse(token_stream).map(Self::Expr),
@@ -50,6 +52,40 @@ impl<'source> Parse<'source> for Statement<'source> {
     }
 }
 
+/// Parses the rest of `break` or `continue` with an optional label
+fn jump_label<'source, TS: Tokens<'source>>(
+    token_stream: &mut TokenStream<'source, TS>,
+    outside_loop: ParseErrorKind<'source>,
+) -> ParseResult<'source, Option<Id<'source>>> {
+    if token_stream.loops.is_empty() {
+        return Err(ParseError {
+            kind: outside_loop,
+            span: token_stream.current().span,
+        });
+    }
+
+    token_stream.next_token()?;
+
+    let label = if token_stream.check(TokenTag::Label) {
+        let Token { value, span, .. } = token_stream.next_token()?;
+
+        if !token_stream.loops.contains(&Some(value)) {
+            return Err(ParseError {
+                kind: ParseErrorKind::UnknownLabel(value),
+                span,
+            });
+        }
+
+        Some(Id::new(value, span))
+    } else {
+        None
+    };
+
+    token_stream.consume(TokenTag::Semicolon)?;
+
+    Ok(label)
+}
+
 impl<'source> Parse<'source> for DeclareStatement<'source> {
     fn parse<TS: Tokens<'source>>(
         token_stream: &mut TokenStream<'source, TS>,
@@ -61,7 +97,7 @@ impl<'source> Parse<'source> for DeclareStatement<'source> {
         let ty = {
             token_stream
                 .try_consume(TokenTag::Colon)?
-                .then(|| Id::parse(token_stream))
+                .then(|| Spanned::<Type>::parse(token_stream))
                 .transpose()?
         };
 
@@ -78,25 +114,132 @@ impl<'source> Parse<'source> for DeclareStatement<'source> {
     }
 }
 
+fn is_destructure<'source, TS: Tokens<'source>>(
+    token_stream: &mut TokenStream<'source, TS>,
+) -> ParseResult<'source, bool> {
+    let next = token_stream.peek(1)?;
+
+    Ok(next.is_some_and(|token| token.tag == TokenTag::LParen))
+}
+
+impl<'source> Parse<'source> for DestructureStatement<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        token_stream.consume(TokenTag::Let)?;
+        token_stream.consume(TokenTag::LParen)?;
+
+        let mut ids = vec![Id::parse(token_stream)?];
+
+        while token_stream.try_consume(TokenTag::Comma)? {
+            if token_stream.check(TokenTag::RParen) {
+                break;
+            }
+
+            ids.push(Id::parse(token_stream)?);
+        }
+
+        token_stream.consume(TokenTag::RParen)?;
+        token_stream.consume(TokenTag::Assign)?;
+
+        let value = Spanned::<Expr>::parse(token_stream)?;
+
+        token_stream.consume(TokenTag::Semicolon)?;
+
+        Ok(DestructureStatement { ids, value })
+    }
+}
+
 impl<'source> Parse<'source> for ExprStatement<'source> {
     fn parse<TS: Tokens<'source>>(
         token_stream: &mut TokenStream<'source, TS>,
     ) -> ParseResult<'source, Self> {
         let expr = Spanned::<Expr>::parse(token_stream)?;
 
-        if let Expr::Atom(Atom::Id(id)) = expr.0 {
-            if token_stream.try_consume(TokenTag::Assign)? {
-                let rhs = Spanned::<Expr>::parse(token_stream)?;
+        expr_statement(token_stream, expr)
+    }
+}
 
-                token_stream.consume(TokenTag::Semicolon)?;
+/// Parses the rest of an expression statement which begins with `expr`
+pub fn expr_statement<'source, TS: Tokens<'source>>(
+    token_stream: &mut TokenStream<'source, TS>,
+    expr: Spanned<Expr<'source>>,
+) -> ParseResult<'source, ExprStatement<'source>> {
+    let stmt = assignment(token_stream, expr)?;
 
-                return Ok(ExprStatement::Assign { id, rhs });
-            }
+    token_stream.consume(TokenTag::Semicolon)?;
+
+    Ok(stmt)
+}
+
+/// `expr` itself or an assignment to it without `;`
+fn assignment<'source, TS: Tokens<'source>>(
+    token_stream: &mut TokenStream<'source, TS>,
+    expr: Spanned<Expr<'source>>,
+) -> ParseResult<'source, ExprStatement<'source>> {
+    let op = match token_stream.current().tag {
+        TokenTag::Assign => None,
+        TokenTag::PlusAssign => Some(BinOp::Add),
+        TokenTag::MinusAssign => Some(BinOp::Sub),
+        TokenTag::StarAssign => Some(BinOp::Mul),
+        TokenTag::SlashAssign => Some(BinOp::Div),
+
+        _ => return Ok(ExprStatement::Expr(expr)),
+    };
+
+    let span = expr.span();
+
+    let place = place(expr).ok_or(ParseError {
+        kind: ParseErrorKind::InvalidAssignment,
+        span,
+    })?;
+
+    token_stream.next_token()?;
+
+    let rhs = Spanned::<Expr>::parse(token_stream)?;
+
+    Ok(ExprStatement::Assign { place, op, rhs })
+}
+
+fn place<'source>(expr: Spanned<Expr<'source>>) -> Option<Place<'source>> {
+    let span = expr.span();
+
+    // nothing can follow a swizzle
+    let inner =
+        |lhs: Box<Spanned<Expr<'source>>>| place(*lhs).filter(|inner| inner.swizzle.is_none());
+
+    match expr.0 {
+        Expr::Atom(Atom::Id(id)) => Some(Place {
+            id,
+            projections: Vec::new(),
+            swizzle: None,
+        }),
+
+        Expr::Index { lhs, index } => {
+            let mut place = inner(lhs)?;
+
+            place.projections.push(Projection::Index(*index));
+
+            Some(place)
         }
 
-        token_stream.consume(TokenTag::Semicolon)?;
+        Expr::Field { lhs, field } => {
+            let mut place = inner(lhs)?;
+
+            place.projections.push(Projection::Field(field));
 
-        Ok(ExprStatement::Expr(expr))
+            Some(place)
+        }
+
+        Expr::Swizzle { lhs, swizzle } => {
+            let mut place = inner(lhs)?;
+
+            place.swizzle = Some(swizzle.spanned(span));
+
+            Some(place)
+        }
+
+        _ => None,
     }
 }
 
@@ -128,7 +271,7 @@ impl<'source> Parse<'source> for ReturnStatement<'source> {
     fn parse<TS: Tokens<'source>>(
         token_stream: &mut TokenStream<'source, TS>,
     ) -> ParseResult<'source, Self> {
-        token_stream.consume(TokenTag::Return)?;
+        let span = token_stream.consume(TokenTag::Return)?.span;
 
         let value = {
             (!token_stream.check(TokenTag::Semicolon))
@@ -138,7 +281,7 @@ impl<'source> Parse<'source> for ReturnStatement<'source> {
 
         token_stream.consume(TokenTag::Semicolon)?;
 
-        Ok(ReturnStatement { value })
+        Ok(ReturnStatement { value, span })
     }
 }
 
@@ -146,17 +289,224 @@ impl<'source> Parse<'source> for WhileStatement<'source> {
     fn parse<TS: Tokens<'source>>(
         token_stream: &mut TokenStream<'source, TS>,
     ) -> ParseResult<'source, Self> {
+        let label = loop_label(token_stream)?;
+
         token_stream.consume(TokenTag::While)?;
 
         let cond = Spanned::<Expr>::parse(token_stream)?;
+        let body = loop_body(token_stream, label)?;
 
-        let in_loop = token_stream.in_loop;
-        token_stream.in_loop = true;
+        Ok(WhileStatement { label, cond, body })
+    }
+}
 
-        let body = Block::parse(token_stream)?;
+impl<'source> Parse<'source> for DoWhileStatement<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        let label = loop_label(token_stream)?;
+
+        token_stream.consume(TokenTag::Do)?;
+
+        let body = loop_body(token_stream, label)?;
+
+        token_stream.consume(TokenTag::While)?;
+
+        let cond = Spanned::<Expr>::parse(token_stream)?;
 
-        token_stream.in_loop = in_loop;
+        token_stream.consume(TokenTag::Semicolon)?;
+
+        Ok(DoWhileStatement { label, body, cond })
+    }
+}
+
+impl<'source> Parse<'source> for LoopStatement<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        let label = loop_label(token_stream)?;
 
-        Ok(WhileStatement { cond, body })
+        token_stream.consume(TokenTag::Loop)?;
+
+        let body = loop_body(token_stream, label)?;
+
+        Ok(LoopStatement { label, body })
+    }
+}
+
+impl<'source> Parse<'source> for ForStatement<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        let label = loop_label(token_stream)?;
+
+        token_stream.consume(TokenTag::For)?;
+
+        let var = Id::parse(token_stream)?;
+
+        token_stream.consume(TokenTag::In)?;
+
+        let range = Range::parse(token_stream)?;
+        let body = loop_body(token_stream, label)?;
+
+        Ok(ForStatement {
+            label,
+            var,
+            range,
+            body,
+        })
+    }
+}
+
+/// `'label:` before a loop
+fn loop_label<'source, TS: Tokens<'source>>(
+    token_stream: &mut TokenStream<'source, TS>,
+) -> ParseResult<'source, Option<Id<'source>>> {
+    if !token_stream.check(TokenTag::Label) {
+        return Ok(None);
+    }
+
+    let Token { value, span, .. } = token_stream.next_token()?;
+
+    token_stream.consume(TokenTag::Colon)?;
+
+    Ok(Some(Id::new(value, span)))
+}
+
+fn loop_body<'source, TS: Tokens<'source>>(
+    token_stream: &mut TokenStream<'source, TS>,
+    label: Option<Id<'source>>,
+) -> ParseResult<'source, Block<'source>> {
+    token_stream.loops.push(label.map(|label| label.0));
+
+    let body = Block::parse(token_stream);
+
+    token_stream.loops.pop();
+
+    body
+}
+
+impl<'source> Parse<'source> for MatchStatement<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        token_stream.consume(TokenTag::Match)?;
+
+        let value = Spanned::<Expr>::parse(token_stream)?;
+
+        token_stream.consume(TokenTag::LBrace)?;
+
+        let mut arms = Vec::new();
+
+        while !token_stream.check(TokenTag::RBrace) {
+            arms.push(MatchArm::parse(token_stream)?);
+        }
+
+        token_stream.consume(TokenTag::RBrace)?;
+
+        Ok(MatchStatement { value, arms })
+    }
+}
+
+impl<'source> Parse<'source> for MatchArm<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        let mut patterns = vec![token_stream.work(|token_stream| Pattern::parse(token_stream))?];
+
+        while token_stream.try_consume(TokenTag::Or)? {
+            patterns.push(token_stream.work(|token_stream| Pattern::parse(token_stream))?);
+        }
+
+        token_stream.consume(TokenTag::FatArrow)?;
+
+        // `=> { ... }` needs no comma, `=> expr` or `=> a = expr`
+        // is followed by `,` or the end of the match
+        let body = if token_stream.check(TokenTag::LBrace) {
+            let body = Block::parse(token_stream)?;
+
+            token_stream.try_consume(TokenTag::Comma)?;
+
+            body
+        } else {
+            let stmt = token_stream.work(|token_stream| {
+                let expr = Spanned::<Expr>::parse(token_stream)?;
+
+                assignment(token_stream, expr).map(Statement::Expr)
+            })?;
+
+            if !token_stream.check(TokenTag::RBrace) {
+                token_stream.consume(TokenTag::Comma)?;
+            }
+
+            Block {
+                statements: vec![stmt],
+            }
+        };
+
+        Ok(MatchArm { patterns, body })
+    }
+}
+
+impl<'source> Parse<'source> for Pattern<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        if token_stream.current().tag == TokenTag::Id && token_stream.current().value == "_" {
+            token_stream.next_token()?;
+
+            return Ok(Pattern::Wildcard);
+        }
+
+        // `|` separates the patterns, so the bounds are just literals or `-literal`
+        let start = parse_fact(token_stream)?;
+
+        let inclusive = if token_stream.try_consume(TokenTag::DotDotEq)? {
+            true
+        } else if token_stream.try_consume(TokenTag::DotDot)? {
+            false
+        } else {
+            return Ok(Pattern::Value(start));
+        };
+
+        let end = parse_fact(token_stream)?;
+
+        Ok(Pattern::Range {
+            start,
+            end,
+            inclusive,
+        })
+    }
+}
+
+impl<'source> Parse<'source> for Range<'source> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        let start = Spanned::<Expr>::parse(token_stream)?;
+
+        let inclusive = if token_stream.try_consume(TokenTag::DotDotEq)? {
+            true
+        } else {
+            token_stream.consume(TokenTag::DotDot)?;
+
+            false
+        };
+
+        let end = Spanned::<Expr>::parse(token_stream)?;
+
+        let step = {
+            token_stream
+                .try_consume(TokenTag::Step)?
+                .then(|| Spanned::<Expr>::parse(token_stream))
+                .transpose()?
+        };
+
+        Ok(Range {
+            start,
+            end,
+            inclusive,
+            step,
+        })
     }
 }
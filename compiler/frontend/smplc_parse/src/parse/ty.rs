@@ -0,0 +1,59 @@
+use smplc_ast::{Expr, MakeSpanned, Spanned, Type};
+use smplc_lexer::TokenTag;
+
+use crate::{error::ParseResult, token_stream::Tokens, Parse, TokenStream};
+
+impl<'source> Parse<'source> for Spanned<Type<'source>> {
+    fn parse<TS: Tokens<'source>>(
+        token_stream: &mut TokenStream<'source, TS>,
+    ) -> ParseResult<'source, Self> {
+        if token_stream.check(TokenTag::LParen) {
+            return token_stream.work(|token_stream| {
+                token_stream.consume(TokenTag::LParen)?;
+
+                let first = Spanned::<Type>::parse(token_stream)?;
+
+                // `(T)` is just `T` in parentheses, `(T,)` is a tuple
+                if !token_stream.check(TokenTag::Comma) {
+                    token_stream.consume(TokenTag::RParen)?;
+
+                    return Ok(first.0);
+                }
+
+                let mut elems = vec![first];
+
+                while token_stream.try_consume(TokenTag::Comma)? {
+                    if token_stream.check(TokenTag::RParen) {
+                        break;
+                    }
+
+                    elems.push(Spanned::<Type>::parse(token_stream)?);
+                }
+
+                token_stream.consume(TokenTag::RParen)?;
+
+                Ok(Type::Tuple(elems))
+            });
+        }
+
+        if !token_stream.check(TokenTag::LBracket) {
+            let token = token_stream.consume(TokenTag::Id)?;
+
+            return Ok(Type::Named(token.value).spanned(token.span));
+        }
+
+        token_stream.work(|token_stream| {
+            token_stream.consume(TokenTag::LBracket)?;
+
+            let elem = Box::new(Spanned::<Type>::parse(token_stream)?);
+
+            token_stream.consume(TokenTag::Semicolon)?;
+
+            let len = Box::new(Spanned::<Expr>::parse(token_stream)?);
+
+            token_stream.consume(TokenTag::RBracket)?;
+
+            Ok(Type::Array { elem, len })
+        })
+    }
+}
@@ -1,11 +1,12 @@
 use smplc_ast::*;
 use smplc_lexer::Lexer;
 
-use crate::{Parse, TokenStream};
+use crate::error::{ParseError, ParseErrorKind};
+use crate::{parse, Parse, TokenStream};
 
 macro_rules! parse_test {
     ($code:expr; $decl:pat $(=> $block:block)?) => {{
-        let mut token_stream = TokenStream::new(Lexer::new($code)).unwrap();
+        let mut token_stream = TokenStream::new(Lexer::new($code));
 
         let decl = Declaration::parse(&mut token_stream).unwrap();
 
@@ -22,7 +23,7 @@ macro_rules! parse_test {
 
 macro_rules! stmt_test {
     ($code:expr; $stmt:pat $(=> $block:block)?) => {{
-        let mut token_stream = TokenStream::new(Lexer::new($code)).unwrap();
+        let mut token_stream = TokenStream::new(Lexer::new($code));
 
         let stmt = Statement::parse(&mut token_stream).unwrap();
 
@@ -39,7 +40,7 @@ macro_rules! stmt_test {
 
 macro_rules! expr_test {
     ($code:expr; $expr:pat $(=> $block:block)?) => {{
-        let mut token_stream = TokenStream::new(Lexer::new($code)).unwrap();
+        let mut token_stream = TokenStream::new(Lexer::new($code));
 
         let expr = Spanned::<Expr>::parse(&mut token_stream).unwrap().0;
 
@@ -62,7 +63,7 @@ let a: int;
         ";
         Statement::Declare(DeclareStatement {
             id: Spanned("a", _),
-            ty: Some(Spanned("int", _)),
+            ty: Some(Spanned(Type::Named("int"), _)),
             value: None,
         })
     );
@@ -73,7 +74,7 @@ let a: real = a;
         ";
         Statement::Declare(DeclareStatement {
             id: Spanned("a", _),
-            ty: Some(Spanned("real", _)),
+            ty: Some(Spanned(Type::Named("real"), _)),
             value: Some(Spanned(Expr::Atom(Atom::Id(
                 Spanned("a", _)
             )), _)),
@@ -102,6 +103,8 @@ fn name() {}
             args,
             ret_ty: None,
             body: Block { statements },
+            is_const: false,
+            ..
         }) => {
             assert!(matches!(args.as_slice(), []));
             assert!(matches!(statements.as_slice(), []));
@@ -115,13 +118,15 @@ fn name(a: real) -> real {}
         Declaration::Function(FunctionDeclaration {
             id: Spanned("name", _),
             args,
-            ret_ty: Some(Spanned("real", _)),
+            ret_ty: Some(Spanned(Type::Named("real"), _)),
             body: Block { statements },
+            is_const: false,
+            ..
         }) => {
             assert!(matches!(args.as_slice(), [
                 FunctionArg {
                     id:  Spanned("a", _),
-                    ty: Spanned("real", _),
+                    ty: Spanned(Type::Named("real"), _),
                 }
             ]));
 
@@ -136,17 +141,19 @@ fn name(a: bool, b: bool) -> bool {}
         Declaration::Function(FunctionDeclaration {
             id: Spanned("name", _),
             args,
-            ret_ty: Some(Spanned("bool", _)),
+            ret_ty: Some(Spanned(Type::Named("bool"), _)),
             body: Block { statements },
+            is_const: false,
+            ..
         }) => {
             assert!(matches!(args.as_slice(), [
                 FunctionArg {
                     id: Spanned("a", _),
-                    ty: Spanned("bool", _),
+                    ty: Spanned(Type::Named("bool"), _),
                 },
                 FunctionArg {
                     id: Spanned("b", _),
-                    ty: Spanned("bool", _),
+                    ty: Spanned(Type::Named("bool"), _),
                 }
             ]));
 
@@ -203,10 +210,12 @@ fn name() {
             body: Block {
                 statements,
             },
+            is_const: false,
+            ..
         }) => {
             assert!(matches!(args.as_slice(), []));
             assert!(matches!(statements.as_slice(), [
-                Statement::Return(ReturnStatement { value: None })
+                Spanned(Statement::Return(ReturnStatement { value: None, .. }), _)
             ]));
         }
     );
@@ -225,12 +234,15 @@ fn name() {
             body: Block {
                 statements,
             },
+            is_const: false,
+            ..
         }) => {
             assert!(matches!(args.as_slice(), []));
             assert!(matches!(statements.as_slice(), [
-                Statement::Return(ReturnStatement {
+                Spanned(Statement::Return(ReturnStatement {
                     value: Some(Spanned(Expr::Atom(Atom::Id(Spanned("a", _))), _)),
-                })
+                    ..
+                }), _)
             ]));
         }
     );
@@ -244,6 +256,7 @@ while a {}
         ";
 
         Statement::While(WhileStatement {
+            label: None,
             cond: Spanned(Expr::Atom(Atom::Id(Spanned("a", _))), _),
             body: Block { statements },
         }) => {
@@ -252,6 +265,141 @@ while a {}
     );
 }
 
+#[test]
+pub fn for_statement() {
+    stmt_test!(
+        "\
+for i in 0..n {}
+        ";
+
+        Statement::For(for_statement) => {
+            assert!(matches!(
+                *for_statement,
+                ForStatement {
+                    label: None,
+                    var: Spanned("i", _),
+                    range: Range {
+                        start: Spanned(Expr::Atom(Atom::Literal(Literal { value: "0", .. })), _),
+                        end: Spanned(Expr::Atom(Atom::Id(Spanned("n", _))), _),
+                        inclusive: false,
+                        step: None,
+                    },
+                    body: Block { statements },
+                } if statements.is_empty()
+            ));
+        }
+    );
+
+    stmt_test!(
+        "\
+for x in 0.0..=1.0 step 0.25 { break; }
+        ";
+
+        Statement::For(for_statement) => {
+            assert!(matches!(
+                *for_statement,
+                ForStatement {
+                    label: None,
+                    var: Spanned("x", _),
+                    range: Range {
+                        inclusive: true,
+                        step: Some(Spanned(Expr::Atom(Atom::Literal(Literal { value: "0.25", .. })), _)),
+                        ..
+                    },
+                    body: Block { statements },
+                } if matches!(statements.as_slice(), [Spanned(Statement::Break(None), _)])
+            ));
+        }
+    );
+}
+
+#[test]
+pub fn loops() {
+    stmt_test!(
+        "\
+'outer: for i in 0..n { loop { continue 'outer; } }
+        ";
+
+        Statement::For(for_statement) => {
+            let ForStatement {
+                label: Some(Spanned("'outer", _)),
+                body: Block { statements },
+                ..
+            } = *for_statement
+            else {
+                panic!()
+            };
+
+            assert!(matches!(
+                statements.as_slice(),
+                [Spanned(Statement::Loop(LoopStatement { label: None, body: Block { statements } }), _)]
+                    if matches!(statements.as_slice(), [Spanned(Statement::Continue(Some(Spanned("'outer", _))), _)])
+            ));
+        }
+    );
+
+    stmt_test!(
+        "\
+do { break; } while a;
+        ";
+
+        Statement::DoWhile(DoWhileStatement {
+            label: None,
+            body: Block { statements },
+            cond: Spanned(Expr::Atom(Atom::Id(Spanned("a", _))), _),
+        }) => {
+            assert!(matches!(statements.as_slice(), [Spanned(Statement::Break(None), _)]));
+        }
+    );
+
+    let mut token_stream = TokenStream::new(Lexer::new("'a: loop { break 'b; }"));
+
+    // the body recovers from the error and the loop itself is parsed
+    assert!(Statement::parse(&mut token_stream).is_ok());
+
+    assert!(matches!(
+        token_stream.into_errors().as_slice(),
+        [ParseError {
+            kind: ParseErrorKind::UnknownLabel("'b"),
+            ..
+        }]
+    ));
+}
+
+#[test]
+pub fn match_statement() {
+    stmt_test!(
+        "\
+match x { 0 => a(), -1 | 2 => { b(); } 3..=9 => c(), _ => d() }
+        ";
+
+        Statement::Match(MatchStatement {
+            value: Spanned(Expr::Atom(Atom::Id(Spanned("x", _))), _),
+            arms,
+        }) => {
+            let patterns: Vec<_> = arms
+                .iter()
+                .flat_map(|arm| arm.patterns.iter().map(|pattern| &pattern.0))
+                .collect();
+
+            assert!(matches!(
+                patterns.as_slice(),
+                [
+                    Pattern::Value(Expr::Atom(Atom::Literal(Literal { value: "0", .. }))),
+                    Pattern::Value(Expr::Prefix { op: UnOp::Neg, .. }),
+                    Pattern::Value(_),
+                    Pattern::Range { inclusive: true, .. },
+                    Pattern::Wildcard,
+                ]
+            ));
+
+            assert_eq!(arms[1].patterns.len(), 2);
+
+            assert!(arms.iter().all(|arm| arm.body.statements.len() == 1));
+        }
+    );
+}
+
 #[test]
 pub fn expr_call() {
     expr_test!(
@@ -286,3 +434,445 @@ pub fn expr_call() {
         }
     );
 }
+
+#[test]
+pub fn int_operators() {
+    expr_test!(
+        "x &&& 1 == 0";
+        Expr::Infix { lhs, op: BinOp::Eq, .. } => {
+            assert!(matches!(lhs.0, Expr::Infix { op: BinOp::BitAnd, .. }));
+        }
+    );
+
+    expr_test!(
+        "a ||| b ^^^ c << 1 + d % 2";
+        Expr::Infix { op: BinOp::BitOr, rhs, .. } => {
+            let Expr::Infix { op: BinOp::BitXor, rhs, .. } = rhs.0 else { panic!() };
+            let Expr::Infix { op: BinOp::Shl, rhs, .. } = rhs.0 else { panic!() };
+            let Expr::Infix { op: BinOp::Add, rhs, .. } = rhs.0 else { panic!() };
+
+            assert!(matches!(rhs.0, Expr::Infix { op: BinOp::Rem, .. }));
+        }
+    );
+
+    expr_test!(
+        "~a >> b";
+        Expr::Infix { lhs, op: BinOp::Shr, .. } => {
+            assert!(matches!(lhs.0, Expr::Prefix { op: UnOp::BitNot, .. }));
+        }
+    );
+}
+
+#[test]
+pub fn casts() {
+    expr_test!(
+        "-n as real * 2.0";
+        Expr::Infix { lhs, op: BinOp::Mul, .. } => {
+            let Expr::As { expr, ty } = lhs.0 else { panic!() };
+
+            assert!(matches!(expr.0, Expr::Prefix { op: UnOp::Neg, .. }));
+            assert!(matches!(ty.0, Type::Named("real")));
+        }
+    );
+
+    expr_test!(
+        "a + v:xy as vec3";
+        Expr::Infix { op: BinOp::Add, rhs, .. } => {
+            let Expr::As { expr, ty } = rhs.0 else { panic!() };
+
+            assert!(matches!(expr.0, Expr::Swizzle { .. }));
+            assert!(matches!(ty.0, Type::Named("vec3")));
+        }
+    );
+}
+
+#[test]
+pub fn if_expressions() {
+    expr_test!(
+        "if a > b { a } else { b }";
+        Expr::If { cond, body, else_body } => {
+            assert!(matches!(cond.0, Expr::Infix { op: BinOp::Gt, .. }));
+            assert!(body.statements.is_empty());
+            assert!(matches!(body.value.unwrap().0, Expr::Atom(Atom::Id(Spanned("a", _)))));
+            assert!(matches!(else_body.value.unwrap().0, Expr::Atom(Atom::Id(Spanned("b", _)))));
+        }
+    );
+
+    expr_test!(
+        "if a { 1 } else if b { 2 } else { 3 }";
+        Expr::If { else_body, .. } => {
+            assert!(else_body.statements.is_empty());
+            assert!(matches!(else_body.value.unwrap().0, Expr::If { .. }));
+        }
+    );
+
+    expr_test!(
+        "{ let t = a * 2; t += 1; t }";
+        Expr::Block(BlockExpr { statements, value }) => {
+            assert!(matches!(
+                statements.as_slice(),
+                [Spanned(Statement::Declare(_), _), Spanned(Statement::Expr(ExprStatement::Assign { .. }), _)]
+            ));
+            assert!(matches!(value.unwrap().0, Expr::Atom(Atom::Id(Spanned("t", _)))));
+        }
+    );
+
+    // a diverging branch has no value
+    expr_test!(
+        "if a { return 1; } else { 2 }";
+        Expr::If { body, else_body, .. } => {
+            assert!(matches!(body.statements.as_slice(), [Spanned(Statement::Return(_), _)]));
+            assert!(body.value.is_none());
+            assert!(else_body.value.is_some());
+        }
+    );
+
+    let mut token_stream = TokenStream::new(Lexer::new("if a { 1 } + 2"));
+
+    assert!(matches!(
+        Spanned::<Expr>::parse(&mut token_stream),
+        Err(ParseError {
+            kind: ParseErrorKind::MissingElse,
+            ..
+        })
+    ));
+}
+
+#[test]
+pub fn arrays() {
+    stmt_test!(
+        "\
+let a: [int; 3] = [1, 2, 3];
+        ";
+
+        Statement::Declare(DeclareStatement {
+            id: Spanned("a", _),
+            ty: Some(Spanned(Type::Array { elem, len }, _)),
+            value: Some(Spanned(Expr::Array(elems), _)),
+        }) => {
+            assert!(matches!(elem.0, Type::Named("int")));
+            assert!(matches!(len.0, Expr::Atom(Atom::Literal(Literal { value: "3", .. }))));
+            assert_eq!(elems.len(), 3);
+        }
+    );
+
+    stmt_test!(
+        "\
+a[i][1] = 0;
+        ";
+
+        Statement::Expr(ExprStatement::Assign {
+            place: Place { id: Spanned("a", _), projections, .. },
+            ..
+        }) => {
+            assert!(matches!(projections.as_slice(), [
+                Projection::Index(Spanned(Expr::Atom(Atom::Id(Spanned("i", _))), _)),
+                Projection::Index(Spanned(Expr::Atom(Atom::Literal(Literal { value: "1", .. })), _)),
+            ]));
+        }
+    );
+
+    expr_test!(
+        "[0.0; 4]";
+        Expr::ArrayRepeat { value, len } => {
+            assert!(matches!(value.0, Expr::Atom(Atom::Literal(Literal { value: "0.0", .. }))));
+            assert!(matches!(len.0, Expr::Atom(Atom::Literal(Literal { value: "4", .. }))));
+        }
+    );
+}
+
+#[test]
+pub fn structs() {
+    parse_test!(
+        "\
+struct Particle {
+    pos: vec3,
+    mass: real,
+}
+        ";
+
+        Declaration::Struct(StructDeclaration {
+            id: Spanned("Particle", _),
+            fields,
+        }) => {
+            assert!(matches!(fields.as_slice(), [
+                StructField { id: Spanned("pos", _), ty: Spanned(Type::Named("vec3"), _) },
+                StructField { id: Spanned("mass", _), ty: Spanned(Type::Named("real"), _) },
+            ]));
+        }
+    );
+
+    expr_test!(
+        "Particle { pos: p, mass: 1.0 }.pos";
+        Expr::Field { lhs, field: Spanned("pos", _) } => {
+            let Expr::Struct(StructLiteral { id: Spanned("Particle", _), fields }) = lhs.0 else {
+                panic!()
+            };
+
+            assert!(matches!(fields.as_slice(), [
+                FieldInit { id: Spanned("pos", _), .. },
+                FieldInit { id: Spanned("mass", _), .. },
+            ]));
+        }
+    );
+
+    stmt_test!(
+        "\
+if a { b = c; }
+        ";
+
+        Statement::If(IfStatement {
+            cond: Spanned(Expr::Atom(Atom::Id(Spanned("a", _))), _),
+            ..
+        })
+    );
+
+    stmt_test!(
+        "\
+ps[i].pos = p;
+        ";
+
+        Statement::Expr(ExprStatement::Assign {
+            place: Place { id: Spanned("ps", _), projections, .. },
+            ..
+        }) => {
+            assert!(matches!(projections.as_slice(), [
+                Projection::Index(_),
+                Projection::Field(Spanned("pos", _)),
+            ]));
+        }
+    );
+}
+
+#[test]
+pub fn tuples() {
+    parse_test!(
+        "fn divmod(a: int, b: int) -> (int, int) {}";
+
+        Declaration::Function(FunctionDeclaration {
+            ret_ty: Some(Spanned(Type::Tuple(elems), _)),
+            ..
+        }) => {
+            assert!(matches!(elems.as_slice(), [
+                Spanned(Type::Named("int"), _),
+                Spanned(Type::Named("int"), _),
+            ]));
+        }
+    );
+
+    expr_test!(
+        "(a, 1.0).1";
+        Expr::Field { lhs, field: Spanned("1", _) } => {
+            assert!(matches!(lhs.0, Expr::Tuple(elems) if elems.len() == 2));
+        }
+    );
+
+    expr_test!(
+        "t.0.1";
+        Expr::Field { lhs, field: Spanned("1", _) } => {
+            assert!(matches!(lhs.0, Expr::Field { field: Spanned("0", _), .. }));
+        }
+    );
+
+    stmt_test!(
+        "let (q, r) = divmod(a, b);";
+
+        Statement::Destructure(DestructureStatement { ids, value }) => {
+            assert!(matches!(ids.as_slice(), [Spanned("q", _), Spanned("r", _)]));
+            assert!(matches!(value.0, Expr::Call(_)));
+        }
+    );
+}
+
+#[test]
+pub fn assignments() {
+    stmt_test!(
+        "\
+x -= 1;
+        ";
+
+        Statement::Expr(ExprStatement::Assign {
+            place: Place { id: Spanned("x", _), swizzle: None, .. },
+            op: Some(BinOp::Sub),
+            ..
+        })
+    );
+
+    stmt_test!(
+        "\
+ps[i].pos:xz *= 2.0;
+        ";
+
+        Statement::Expr(ExprStatement::Assign {
+            place: Place { id: Spanned("ps", _), projections, swizzle: Some(Spanned(swizzle, _)) },
+            op: Some(BinOp::Mul),
+            ..
+        }) => {
+            assert_eq!(projections.len(), 2);
+            assert_eq!(swizzle.as_slice(), [Component::X, Component::Z]);
+        }
+    );
+}
+
+#[test]
+pub fn imports() {
+    parse_test!(
+        r#"import "lib/geometry.smpl";"#;
+
+        Declaration::Import(ImportDeclaration {
+            path: Spanned("lib/geometry.smpl", _),
+        })
+    );
+}
+
+#[test]
+pub fn statics() {
+    parse_test!(
+        "static seed: int = 42;";
+
+        Declaration::Static(StaticDeclaration {
+            id: Spanned("seed", _),
+            ty: Spanned(Type::Named("int"), _),
+            value: Spanned(Expr::Atom(Atom::Literal(Literal { value: "42", .. })), _),
+            attributes,
+        }) => {
+            assert!(attributes.is_empty());
+        }
+    );
+}
+
+#[test]
+pub fn const_fn() {
+    parse_test!(
+        "const fn twice(x: int) -> int { return x * 2; }";
+
+        Declaration::Function(FunctionDeclaration {
+            id: Spanned("twice", _),
+            is_const: true,
+            ..
+        })
+    );
+
+    parse_test!(
+        "const TWICE: int = twice(2);";
+
+        Declaration::Constant(ConstantDeclaration {
+            id: Spanned("TWICE", _),
+            value: Spanned(Expr::Call(_), _),
+            ..
+        })
+    );
+}
+
+#[test]
+pub fn attributes() {
+    parse_test!(
+        "#[allow(unused_variables, dead_code)] #[inline] fn f() {}";
+
+        Declaration::Function(FunctionDeclaration { attributes, .. }) => {
+            let [allow, inline] = attributes.as_slice() else {
+                panic!()
+            };
+
+            assert!(matches!(allow.id, Spanned("allow", _)));
+            assert!(matches!(
+                allow.args.as_slice(),
+                [Spanned("unused_variables", _), Spanned("dead_code", _)]
+            ));
+
+            assert!(matches!(inline.id, Spanned("inline", _)));
+            assert!(inline.args.is_empty());
+        }
+    );
+
+    parse_test!(
+        "#[allow(dead_code)] const C: int = 1;";
+
+        Declaration::Constant(ConstantDeclaration { attributes, .. }) => {
+            assert_eq!(attributes.len(), 1);
+        }
+    );
+
+    let mut token_stream = TokenStream::new(Lexer::new("#[allow(dead_code)] struct S { x: int }"));
+
+    assert!(Declaration::parse(&mut token_stream).is_err());
+}
+
+#[test]
+pub fn error_recovery() {
+    let code = "\
+struct P { x: int y: int }
+
+fn f(a: int) -> int {
+    let x = 1 +;
+    if a > { return 1; } else { return 2; }
+    return x;
+}
+
+const C: int = ;
+
+fn g() {
+    while true { break }
+";
+
+    let Err(errors) = parse(TokenStream::new(Lexer::new(code))) else {
+        panic!()
+    };
+
+    let errors = errors
+        .iter()
+        .map(|error| match error.kind {
+            ParseErrorKind::UnexpectedToken(_, value) => (error.span.start().line(), value),
+            _ => panic!(),
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        errors,
+        [
+            (1, "y"),
+            (4, ";"),
+            (5, "else"),
+            (9, ";"),
+            (12, "}"),
+            (13, "")
+        ]
+    );
+
+    let Ok(declarations) = parse(TokenStream::new(Lexer::new("fn f() { g(); }"))) else {
+        panic!()
+    };
+
+    assert_eq!(declarations.len(), 1);
+}
+
+#[test]
+pub fn lex_error_recovery() {
+    let code = "\
+@
+fn main() {
+    let a = 1 @ 2;
+    let b = a +;
+    let s = \"abc;
+}
+";
+
+    let Err(errors) = parse(TokenStream::new(Lexer::new(code))) else {
+        panic!()
+    };
+
+    let errors = errors
+        .iter()
+        .map(|error| (error.span.start().line(), error.kind.to_string()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        errors,
+        [
+            (1, "unexpected char '@'".to_string()),
+            (3, "unexpected char '@'".to_string()),
+            (4, "unexpected token \";\"".to_string()),
+            (5, "unterminated string".to_string()),
+        ]
+    );
+}
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use smplc_ast::{MakeSpanned, Pos, Span, Spanned};
 use smplc_lexer::{LexError, Token, TokenTag};
 
@@ -9,31 +11,72 @@ impl<'source, T: Iterator<Item = Result<Token<'source>, LexError>>> Tokens<'sour
 pub struct TokenStream<'source, TS: Tokens<'source>> {
     tokens: TS,
     current: Token<'source>,
+    lookahead: VecDeque<Token<'source>>,
 
     prev_span: Span,
 
-    pub in_loop: bool,
+    /// the number of the open braces before the current token
+    depth: usize,
+
+    /// the errors the parser has recovered from
+    errors: Vec<ParseError<'source>>,
+
+    /// the labels of the loops around the current token
+    pub loops: Vec<Option<&'source str>>,
 }
 
 impl<'source, TS: Tokens<'source>> TokenStream<'source, TS> {
-    pub fn new(mut tokens: TS) -> Result<Self, LexError> {
-        Ok(Self {
-            current: tokens.next().unwrap()?,
+    /// the lexing errors before the first token are recorded
+    pub fn new(mut tokens: TS) -> Self {
+        let mut errors = Vec::new();
+
+        let current = loop {
+            match tokens.next().unwrap() {
+                Ok(token) => break token,
+                Err(error) => errors.push(error.into()),
+            }
+        };
+
+        Self {
+            current,
             tokens,
+            lookahead: VecDeque::new(),
             prev_span: Span::default(),
-            in_loop: false,
-        })
+            depth: 0,
+            errors,
+            loops: Vec::new(),
+        }
     }
 
     pub fn current(&self) -> Token<'source> {
         self.current
     }
 
+    /// Returns the token `n` positions after the current one
+    /// without consuming anything, or `None` if the stream ends before it
+    pub fn peek(&mut self, n: usize) -> ParseResult<'source, Option<Token<'source>>> {
+        if n == 0 {
+            return Ok(Some(self.current));
+        }
+
+        while self.lookahead.len() < n {
+            let last = self.lookahead.back().copied().unwrap_or(self.current);
+
+            if last.tag == TokenTag::EOF {
+                return Ok(None);
+            }
+
+            self.lookahead.push_back(self.tokens.next().unwrap()?);
+        }
+
+        Ok(Some(self.lookahead[n - 1]))
+    }
+
     pub fn check(&self, value: TokenTag) -> bool {
         !self.is_end() && self.current().tag == value
     }
 
-    pub fn consume(&mut self, value: TokenTag) -> ParseResult<'source, Token> {
+    pub fn consume(&mut self, value: TokenTag) -> ParseResult<'source, Token<'source>> {
         if self.check(value) {
             Ok(self.next_token()?)
         } else {
@@ -56,7 +99,16 @@ impl<'source, TS: Tokens<'source>> TokenStream<'source, TS> {
 
         self.prev_span = token.span;
 
-        self.current = self.tokens.next().unwrap()?;
+        self.current = match self.lookahead.pop_front() {
+            Some(token) => token,
+            None => self.tokens.next().unwrap()?,
+        };
+
+        match token.tag {
+            TokenTag::LBrace => self.depth += 1,
+            TokenTag::RBrace => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
 
         Ok(token)
     }
@@ -65,6 +117,10 @@ impl<'source, TS: Tokens<'source>> TokenStream<'source, TS> {
         ParseError::unexpected_token(self.current())
     }
 
+    pub fn prev_span(&self) -> Span {
+        self.prev_span
+    }
+
     pub fn get_pos(&self) -> Pos {
         self.current().span.start()
     }
@@ -73,6 +129,89 @@ impl<'source, TS: Tokens<'source>> TokenStream<'source, TS> {
         self.current().tag == TokenTag::EOF
     }
 
+    /// Checks if the current token starts a declaration,
+    /// statements never contain these tokens
+    pub fn is_declaration(&self) -> bool {
+        matches!(
+            self.current().tag,
+            TokenTag::Fn
+                | TokenTag::Const
+                | TokenTag::Static
+                | TokenTag::Struct
+                | TokenTag::Import
+                | TokenTag::Hash
+        )
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Records the error and skips the rest of the broken statement,
+    /// which started at the brace `depth`: the `;` ending it or the block
+    /// it ends with are consumed, the `}` closing the enclosing block
+    /// and the next declaration are kept
+    pub fn recover_statement(&mut self, error: ParseError<'source>, depth: usize) {
+        self.record(error);
+
+        while !self.is_end() && !self.is_declaration() {
+            if self.depth <= depth {
+                if self.check(TokenTag::Semicolon) {
+                    self.skip();
+
+                    return;
+                }
+
+                if self.check(TokenTag::RBrace) {
+                    return;
+                }
+            }
+
+            let closes_block = self.check(TokenTag::RBrace) && self.depth == depth + 1;
+
+            self.skip();
+
+            // `if` and `do`-`while` go on after the block
+            if closes_block && !self.check(TokenTag::Else) && !self.check(TokenTag::While) {
+                return;
+            }
+        }
+    }
+
+    /// Records the error and skips everything up to the next declaration
+    pub fn recover_declaration(&mut self, error: ParseError<'source>) {
+        self.record(error);
+
+        while !self.is_end() && !self.is_declaration() {
+            self.skip();
+        }
+    }
+
+    pub fn into_errors(self) -> Vec<ParseError<'source>> {
+        self.errors
+    }
+
+    /// an error is reported once, even if both a statement and its block
+    /// are broken at the same token, e.g. by the missing `}`
+    fn record(&mut self, error: ParseError<'source>) {
+        if self
+            .errors
+            .last()
+            .is_some_and(|last| last.span == error.span)
+        {
+            return;
+        }
+
+        self.errors.push(error);
+    }
+
+    /// Skips the current token, the lexing errors on the way are recorded
+    fn skip(&mut self) {
+        while let Err(error) = self.next_token() {
+            self.record(error);
+        }
+    }
+
     pub fn work<T>(
         &mut self,
         mut f: impl FnMut(&mut Self) -> ParseResult<'source, T>,
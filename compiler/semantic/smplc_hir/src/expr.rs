@@ -1,6 +1,8 @@
-use smplc_ast::{Spanned, Swizzle};
+use std::rc::Rc;
 
-use crate::{FunId, VarId};
+use smplc_ast::{Id, Spanned, Swizzle};
+
+use crate::{BlockExpr, StructType, Type, VarId};
 
 pub use smplc_ast::{BinOp, Literal, Pos, UnOp};
 
@@ -18,8 +20,50 @@ pub enum Expr<'source> {
         lhs: Box<Spanned<Self>>,
         swizzle: Swizzle,
     },
+    Index {
+        lhs: Box<Spanned<Self>>,
+        index: Box<Spanned<Self>>,
+    },
+    Field {
+        lhs: Box<Spanned<Self>>,
+        field: Id<'source>,
+    },
+    /// `expr as ty`
+    As {
+        expr: Box<Spanned<Self>>,
+        ty: Type,
+    },
+    If {
+        cond: Box<Spanned<Self>>,
+        body: BlockExpr<'source>,
+        else_body: BlockExpr<'source>,
+    },
+    Block(BlockExpr<'source>),
+    /// fields are stored in the declaration order
+    Struct {
+        ty: Rc<StructType>,
+        fields: Vec<Spanned<Self>>,
+    },
+    Tuple(Vec<Spanned<Self>>),
+    Array(Vec<Spanned<Self>>),
+    ArrayRepeat {
+        value: Box<Spanned<Self>>,
+        len: usize,
+    },
+    Len(Box<Spanned<Self>>),
+    Intrinsic {
+        intrinsic: Intrinsic,
+        arg: Box<Spanned<Self>>,
+    },
+    /// the function can be overloaded, the called one is chosen by the typecheck
     Call {
-        fun: FunId,
+        id: Id<'source>,
+        args: Vec<Spanned<Self>>,
+    },
+    /// `pieces` is the unescaped text around the arguments,
+    /// it has one more element than `args`
+    Print {
+        pieces: Vec<String>,
         args: Vec<Spanned<Self>>,
     },
     Atom(Atom<'source>),
@@ -30,3 +74,25 @@ pub enum Atom<'source> {
     Var(VarId),
     Literal(Literal<'source>),
 }
+
+/// built-in functions that work with several types
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Intrinsic {
+    Transpose,
+    Determinant,
+    Inverse,
+}
+
+impl TryFrom<&str> for Intrinsic {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "transpose" => Ok(Self::Transpose),
+            "determinant" => Ok(Self::Determinant),
+            "inverse" => Ok(Self::Inverse),
+
+            _ => Err(()),
+        }
+    }
+}
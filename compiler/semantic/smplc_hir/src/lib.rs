@@ -8,13 +8,14 @@ use smplc_ast::Spanned;
 pub use expr::*;
 pub use statement::*;
 pub use symbols::*;
-pub use ty::Type;
+pub use ty::{StructField, StructType, Type};
 
 #[derive(Default)]
 pub struct HIR<'source> {
     pub symbols: Symbols<'source>,
 
     pub constants: Vec<Constant<'source>>,
+    pub statics: Vec<Static<'source>>,
     pub functions: Vec<Function<'source>>,
 }
 
@@ -30,6 +31,18 @@ pub struct Constant<'source> {
     pub value: Spanned<Expr<'source>>,
 }
 
+pub struct Static<'source> {
+    pub id: VarId,
+    pub ty: Type,
+    pub value: Spanned<Expr<'source>>,
+}
+
 pub struct Block<'source> {
     pub statements: Vec<Statement<'source>>,
 }
+
+/// `value` is `None` if the block diverges
+pub struct BlockExpr<'source> {
+    pub statements: Vec<Statement<'source>>,
+    pub value: Option<Box<Spanned<Expr<'source>>>>,
+}
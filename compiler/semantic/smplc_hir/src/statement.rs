@@ -1,14 +1,19 @@
-use smplc_ast::Spanned;
+use smplc_ast::{BinOp, Id, Span, Spanned, Swizzle};
 
-use crate::{Block, Expr, FunId, VarId};
+use crate::{Block, Expr, FunId, Type, VarId};
 
 pub enum Statement<'source> {
     Expr(ExprStatement<'source>),
     If(IfStatement<'source>),
     Return(ReturnStatement<'source>),
     While(WhileStatement<'source>),
-    Break,
-    Continue,
+    DoWhile(DoWhileStatement<'source>),
+    Loop(LoopStatement<'source>),
+    For(Box<ForStatement<'source>>),
+    Match(MatchStatement<'source>),
+    /// the label is set for `break 'outer;`
+    Break(Option<Id<'source>>),
+    Continue(Option<Id<'source>>),
 }
 
 pub enum ExprStatement<'source> {
@@ -16,9 +21,32 @@ pub enum ExprStatement<'source> {
         var: VarId,
         rhs: Spanned<Expr<'source>>,
     },
+    /// `op` is set for compound assignments like `a[i] += b`
+    Store {
+        place: Place<'source>,
+        op: Option<BinOp>,
+        rhs: Spanned<Expr<'source>>,
+    },
+    /// `let (a, b) = rhs;`
+    Destructure {
+        vars: Vec<VarId>,
+        rhs: Spanned<Expr<'source>>,
+    },
     Expr(Spanned<Expr<'source>>),
 }
 
+pub struct Place<'source> {
+    pub var: Spanned<VarId>,
+    pub projections: Vec<Projection<'source>>,
+    /// the components are distinct
+    pub swizzle: Option<Swizzle>,
+}
+
+pub enum Projection<'source> {
+    Index(Spanned<Expr<'source>>),
+    Field(Id<'source>),
+}
+
 pub struct IfStatement<'source> {
     pub cond: Spanned<Expr<'source>>,
     pub body: Block<'source>,
@@ -28,9 +56,54 @@ pub struct IfStatement<'source> {
 pub struct ReturnStatement<'source> {
     pub fun: FunId,
     pub value: Option<Spanned<Expr<'source>>>,
+    /// the `return` keyword
+    pub span: Span,
 }
 
 pub struct WhileStatement<'source> {
+    pub label: Option<Id<'source>>,
     pub cond: Spanned<Expr<'source>>,
     pub body: Block<'source>,
 }
+
+pub struct DoWhileStatement<'source> {
+    pub label: Option<Id<'source>>,
+    pub body: Block<'source>,
+    pub cond: Spanned<Expr<'source>>,
+}
+
+pub struct LoopStatement<'source> {
+    pub label: Option<Id<'source>>,
+    pub body: Block<'source>,
+}
+
+pub struct ForStatement<'source> {
+    pub label: Option<Id<'source>>,
+    pub var: VarId,
+    pub start: Spanned<Expr<'source>>,
+    pub end: Spanned<Expr<'source>>,
+    pub inclusive: bool,
+    pub step: Option<Spanned<Expr<'source>>>,
+    pub body: Block<'source>,
+}
+
+/// `match value { 0 => ..., 1 | 2 => ..., 3..=9 => ..., _ => ... }`
+pub struct MatchStatement<'source> {
+    pub value: Spanned<Expr<'source>>,
+    /// `Int` or `Bool`, `None` if all the patterns are `_`
+    pub ty: Option<Type>,
+    pub arms: Vec<MatchArm<'source>>,
+}
+
+pub struct MatchArm<'source> {
+    pub patterns: Vec<Pattern>,
+    pub body: Block<'source>,
+}
+
+/// `false` and `true` are `0` and `1`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pattern {
+    /// an inclusive range, a single value is `Range(n, n)`
+    Range(i32, i32),
+    Wildcard,
+}
@@ -4,7 +4,7 @@ use std::{
     ops::{Index, IndexMut},
 };
 
-use smplc_ast as ast;
+use smplc_ast::{self as ast, Span};
 
 use crate::Type;
 
@@ -37,12 +37,24 @@ pub struct FunData<'source> {
     pub id: ast::Id<'source>,
     pub ret_ty: Option<Type>,
     pub args_types: Vec<Type>,
+    /// can be called in constants, std functions are const if they're folded at compile time
+    pub is_const: bool,
+}
+
+impl FunData<'_> {
+    /// the std functions are declared without a position in the source
+    pub fn is_std(&self) -> bool {
+        self.id.span() == Span::default()
+    }
 }
 
 #[derive(Hash)]
 pub struct VarData<'source> {
     pub id: ast::Id<'source>,
     pub ty: Option<Type>,
+    /// the type is unknown because of a semantic error, e.g. the name is undefined
+    /// or the value is broken, so the typecheck ignores the uses of the variable
+    pub poisoned: bool,
 }
 
 pub struct SymbolsTable<K: Hash + Eq + From<usize> + Copy, V> {
@@ -59,10 +71,6 @@ impl<K: Hash + Eq + From<usize> + Copy, V> SymbolsTable<K, V> {
         id
     }
 
-    pub fn into_iter(self) -> impl Iterator<Item = (K, V)> {
-        self.data.into_iter()
-    }
-
     pub fn iter(&self) -> impl Iterator<Item = (K, &V)> {
         self.data.iter().map(|(&k, v)| (k, v))
     }
@@ -74,6 +82,15 @@ impl<K: Hash + Eq + From<usize> + Copy, V> SymbolsTable<K, V> {
     }
 }
 
+impl<K: Hash + Eq + From<usize> + Copy, V> IntoIterator for SymbolsTable<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::collections::hash_map::IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
 impl<K: Hash + Eq + From<usize> + Copy, V> FromIterator<(K, V)> for SymbolsTable<K, V> {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
         Self {
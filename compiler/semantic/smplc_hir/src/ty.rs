@@ -1,9 +1,10 @@
 use std::fmt;
+use std::rc::Rc;
 
 use comet_ir as lir;
 use smplc_ast::LiteralType;
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub enum Type {
     Real,
     Int,
@@ -11,21 +12,133 @@ pub enum Type {
     Vec2,
     Vec3,
     Vec4,
+    Mat2,
+    Mat3,
+    Mat4,
     Complex,
+    /// stored as `[x, y, z, w]`, where `w` is the real part
+    Quat,
+    Array(Box<Type>, usize),
+    Struct(Rc<StructType>),
+    Tuple(Vec<Type>),
 }
 
-impl Into<lir::Type> for Type {
-    fn into(self) -> lir::Type {
+impl Type {
+    pub fn array(elem: Type, len: usize) -> Self {
+        Self::Array(Box::new(elem), len)
+    }
+
+    /// the type of a matrix column
+    pub fn column(&self) -> Option<Type> {
+        match self {
+            Self::Mat2 => Some(Self::Vec2),
+            Self::Mat3 => Some(Self::Vec3),
+            Self::Mat4 => Some(Self::Vec4),
+
+            _ => None,
+        }
+    }
+
+    /// the count of the elements of an array, the lanes of a vector
+    /// or the columns of a matrix, the valid indices are below it
+    pub fn index_bound(&self) -> Option<usize> {
+        match self {
+            Self::Vec2 | Self::Mat2 => Some(2),
+            Self::Vec3 | Self::Mat3 => Some(3),
+            Self::Vec4 | Self::Mat4 => Some(4),
+            Self::Array(_, len) => Some(*len),
+
+            _ => None,
+        }
+    }
+
+    /// the type of a struct field or a tuple element and its offset in slots
+    pub fn field(&self, index: usize) -> (Type, usize) {
         match self {
+            Self::Struct(ty) => (ty.fields[index].ty.clone(), ty.offset(index)),
+            Self::Tuple(elems) => (elems[index].clone(), slots(&elems[..index])),
+
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn slots(types: &[Type]) -> usize {
+    types.iter().map(|ty| lir::Type::from(ty).slots()).sum()
+}
+
+/// Struct names are unique, so comparing
+/// whole declarations gives nominal typing
+#[derive(PartialEq, Eq, Debug, Hash)]
+pub struct StructType {
+    pub name: String,
+    pub fields: Vec<StructField>,
+}
+
+#[derive(PartialEq, Eq, Debug, Hash)]
+pub struct StructField {
+    pub name: String,
+    pub ty: Type,
+}
+
+impl StructType {
+    pub fn field(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|field| field.name == name)
+    }
+
+    /// offset of the field in the struct layout in slots
+    pub fn offset(&self, index: usize) -> usize {
+        self.fields[..index]
+            .iter()
+            .map(|field| lir::Type::from(&field.ty).slots())
+            .sum()
+    }
+}
+
+impl From<&Type> for lir::Format {
+    fn from(value: &Type) -> Self {
+        match value {
+            Type::Real => lir::Format::Real,
+            Type::Int => lir::Format::Int,
+            Type::Bool => lir::Format::Bool,
+            Type::Complex => lir::Format::Complex,
+            Type::Quat => lir::Format::Quat,
+            Type::Vec2 => lir::Format::F32s(lir::Dims::X2),
+            Type::Vec3 => lir::Format::F32s(lir::Dims::X3),
+            Type::Vec4 => lir::Format::F32s(lir::Dims::X4),
+            Type::Mat2 => lir::Format::Mat(lir::Dims::X2),
+            Type::Mat3 => lir::Format::Mat(lir::Dims::X3),
+            Type::Mat4 => lir::Format::Mat(lir::Dims::X4),
+
+            Type::Array(..) | Type::Struct(_) | Type::Tuple(_) => unreachable!(),
+        }
+    }
+}
+
+impl From<&Type> for lir::Type {
+    fn from(val: &Type) -> Self {
+        match val {
             Type::Real => lir::Type::Real,
             Type::Int | Type::Bool => lir::Type::Int,
             Type::Complex | Type::Vec2 => lir::Type::F32x2,
             Type::Vec3 => lir::Type::F32x3,
-            Type::Vec4 => lir::Type::F32x4,
+            Type::Vec4 | Type::Quat => lir::Type::F32x4,
+            Type::Mat2 => lir::Type::F32x2x2,
+            Type::Mat3 => lir::Type::F32x3x3,
+            Type::Mat4 => lir::Type::F32x4x4,
+            Type::Array(elem, len) => lir::Type::Aggregate(lir::Type::from(&**elem).slots() * len),
+            Type::Struct(ty) => lir::Type::Aggregate(ty.offset(ty.fields.len())),
+            Type::Tuple(elems) => lir::Type::Aggregate(slots(elems)),
         }
     }
 }
 
+impl From<Type> for lir::Type {
+    fn from(val: Type) -> Self {
+        Self::from(&val)
+    }
+}
+
 impl TryFrom<&str> for Type {
     type Error = ();
 
@@ -37,7 +150,11 @@ impl TryFrom<&str> for Type {
             "vec2" => Ok(Self::Vec2),
             "vec3" => Ok(Self::Vec3),
             "vec4" => Ok(Self::Vec4),
+            "mat2" => Ok(Self::Mat2),
+            "mat3" => Ok(Self::Mat3),
+            "mat4" => Ok(Self::Mat4),
             "complex" => Ok(Self::Complex),
+            "quat" => Ok(Self::Quat),
 
             _ => Err(()),
         }
@@ -51,6 +168,8 @@ impl From<LiteralType> for Type {
             LiteralType::Int => Self::Int,
             LiteralType::Bool => Self::Bool,
             LiteralType::Complex => Self::Complex,
+
+            LiteralType::String => unreachable!(),
         }
     }
 }
@@ -64,7 +183,22 @@ impl fmt::Display for Type {
             Type::Vec2 => write!(f, "vec2"),
             Type::Vec3 => write!(f, "vec3"),
             Type::Vec4 => write!(f, "vec4"),
+            Type::Mat2 => write!(f, "mat2"),
+            Type::Mat3 => write!(f, "mat3"),
+            Type::Mat4 => write!(f, "mat4"),
             Type::Complex => write!(f, "complex"),
+            Type::Quat => write!(f, "quat"),
+            Type::Array(elem, len) => write!(f, "[{elem}; {len}]"),
+            Type::Struct(ty) => write!(f, "{}", ty.name),
+            Type::Tuple(elems) => {
+                let elems = elems.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+                if let [elem] = elems.as_slice() {
+                    write!(f, "({elem},)")
+                } else {
+                    write!(f, "({})", elems.join(", "))
+                }
+            }
         }
     }
 }
@@ -1,24 +1,121 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
 use smplc_ast as ast;
-use smplc_hir::{FunData, FunId, SymbolsTable, Type, VarData, VarId};
+use smplc_hir::{FunData, FunId, StructType, SymbolsTable, Type, VarData, VarId};
 
-use crate::{
-    error::{SemError, SemResult},
-    semcheck::RawType,
-};
+use crate::error::{SemError, SemResult};
+use crate::semcheck::LenEval;
+use crate::warning::{Lint, Warning};
 
 #[derive(Default)]
 pub struct Env<'source> {
     pub variables: Variables<'source>,
     pub functions: Functions<'source>,
+    pub types: Types<'source>,
+    pub loops: Loops<'source>,
 
     pub current_fn: Option<FunId>,
+    /// the code is evaluated at compile time:
+    /// the value of a constant or a static, or the body of a `const fn`
+    pub in_const: bool,
+    pub statics: HashSet<VarId>,
+
+    /// the errors the check goes on after
+    pub errors: Vec<SemError<'source>>,
+
+    /// the lints allowed by the attributes of the current declaration
+    pub allowed: Vec<Lint>,
+    pub warnings: Vec<Warning<'source>>,
+
+    /// the variables declared since the last lint of the unused ones
+    pub declared: Vec<VarId>,
+    pub read: HashSet<VarId>,
+    /// the variables assigned after their declaration
+    pub assigned: HashSet<VarId>,
+    /// all the overloads of a called function are marked, the typecheck chooses one of them
+    pub called: HashSet<FunId>,
+}
+
+impl<'source> Env<'source> {
+    pub fn error(&mut self, error: SemError<'source>) {
+        self.errors.push(error);
+    }
+
+    pub fn warn(&mut self, warning: Warning<'source>) {
+        if !self.allowed.contains(&warning.kind.lint()) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Reports the declared variables that are never read,
+    /// the names starting with `_` are unused on purpose
+    pub fn lint_variables(&mut self) {
+        for var in std::mem::take(&mut self.declared) {
+            let id = self.variables.symbols[var].id;
+
+            if self.read.contains(&var) || id.0.starts_with('_') {
+                continue;
+            }
+
+            if self.assigned.contains(&var) {
+                self.warn(Warning::unused_assignment(id));
+            } else {
+                self.warn(Warning::unused_variable(id));
+            }
+        }
+    }
+
+    /// statics change at runtime, so the code evaluated at compile time can't use them
+    pub fn check_static(&mut self, id: ast::Id<'source>, var: VarId) {
+        if self.in_const && self.statics.contains(&var) {
+            self.error(SemError::static_in_const(id));
+        }
+    }
+
+    /// Resolves the variable, an undefined one is reported and replaced
+    /// with a placeholder, so its other uses in the scope aren't reported again
+    pub fn variable(&mut self, id: ast::Id<'source>) -> VarId {
+        self.variables.get(id).unwrap_or_else(|err| {
+            self.error(err);
+
+            let var = self.variables.add_placeholder(id, None);
+
+            self.variables.placeholders.insert(var);
+            self.poison(var);
+
+            var
+        })
+    }
+
+    /// the typecheck ignores the uses of the variable, its type is broken
+    pub fn poison(&mut self, var: VarId) {
+        self.variables.symbols[var].poisoned = true;
+    }
+
+    /// Declares the variable, a redeclared one is reported
+    /// and shadows the first declaration
+    pub fn declare(&mut self, id: ast::Id<'source>, ty: Option<Type>) -> VarId {
+        let var = self
+            .variables
+            .add_variable(id, ty.clone())
+            .unwrap_or_else(|err| {
+                self.error(err);
+
+                self.variables.add_placeholder(id, ty)
+            });
+
+        self.declared.push(var);
+
+        var
+    }
 }
 
 #[derive(Default)]
 pub struct Variables<'source> {
     data: Vec<Scope<'source, VarId>>,
+    /// the undefined variables, which can be declared later
+    placeholders: HashSet<VarId>,
 
     pub symbols: SymbolsTable<VarId, VarData<'source>>,
 }
@@ -55,12 +152,18 @@ impl<'source> Variables<'source> {
         id: ast::Id<'source>,
         ty: Option<Type>,
     ) -> SemResult<'source, VarId> {
-        if let Some(symbol_id) = self.last().get(id.0) {
+        let declared = self.last().get(id.0);
+
+        if let Some(symbol_id) = declared.filter(|&var| !self.is_placeholder(var)) {
             let first_declaration = self.symbols[symbol_id].id.span();
 
             Err(SemError::redeclaring_variable(id, first_declaration))
         } else {
-            let var_data = VarData { id, ty };
+            let var_data = VarData {
+                id,
+                ty,
+                poisoned: false,
+            };
             let var_id = self.symbols.add(var_data);
 
             self.last_mut().add(id.0, var_id);
@@ -69,62 +172,203 @@ impl<'source> Variables<'source> {
         }
     }
 
-    pub fn add_argument(&mut self, arg: ast::FunctionArg<'source>) -> SemResult<'source, VarId> {
-        if self.last().has(arg.id.0) {
-            Err(SemError::duplicate_args_names(arg.id))
+    pub fn is_placeholder(&self, var: VarId) -> bool {
+        self.placeholders.contains(&var)
+    }
+
+    /// adds the variable to the current scope without any checks
+    pub fn add_placeholder(&mut self, id: ast::Id<'source>, ty: Option<Type>) -> VarId {
+        let var_id = self.symbols.add(VarData {
+            id,
+            ty,
+            poisoned: false,
+        });
+
+        self.last_mut().add(id.0, var_id);
+
+        var_id
+    }
+
+    pub fn add_argument(&mut self, id: ast::Id<'source>, ty: Type) -> SemResult<'source, VarId> {
+        if self.last().has(id.0) {
+            Err(SemError::duplicate_args_names(id))
         } else {
             let var_data = VarData {
-                id: arg.id,
-                ty: Some(RawType(arg.ty).checked()?),
+                id,
+                ty: Some(ty),
+                poisoned: false,
             };
 
             let var_id = self.symbols.add(var_data);
 
-            self.last_mut().add(arg.id.0, var_id);
+            self.last_mut().add(id.0, var_id);
 
             Ok(var_id)
         }
     }
 }
 
+/// The loops around the current statement
+#[derive(Default)]
+pub struct Loops<'source> {
+    data: Vec<Loop<'source>>,
+}
+
+pub struct Loop<'source> {
+    label: Option<&'source str>,
+    /// a `break` leaves the loop
+    pub broken: bool,
+    /// the variables that are inited at every `break`
+    pub inited_at_break: HashSet<VarId>,
+    /// a `continue` skips the rest of the body
+    pub continued: bool,
+}
+
+impl<'source> Loops<'source> {
+    pub fn join(&mut self, label: Option<ast::Id<'source>>) {
+        self.data.push(Loop {
+            label: label.map(|label| label.0),
+            broken: false,
+            continued: false,
+            inited_at_break: HashSet::new(),
+        });
+    }
+
+    pub fn exit(&mut self) -> Loop<'source> {
+        self.data.pop().unwrap()
+    }
+
+    pub fn break_loop(&mut self, label: Option<ast::Id<'source>>, inited: HashSet<VarId>) {
+        if let Some(target) = self.find(label) {
+            if target.broken {
+                target.inited_at_break.retain(|var| inited.contains(var));
+            } else {
+                target.inited_at_break = inited;
+            }
+
+            target.broken = true;
+        }
+    }
+
+    pub fn continue_loop(&mut self, label: Option<ast::Id<'source>>) {
+        if let Some(target) = self.find(label) {
+            target.continued = true;
+        }
+    }
+
+    /// the parser checks that the label exists
+    fn find(&mut self, label: Option<ast::Id<'source>>) -> Option<&mut Loop<'source>> {
+        self.data
+            .iter_mut()
+            .rev()
+            .find(|target| label.is_none_or(|label| target.label == Some(label.0)))
+    }
+}
+
 #[derive(Default)]
 pub struct Functions<'source> {
-    data: Scope<'source, FunId>,
+    data: Scope<'source, Vec<FunId>>,
 
     pub symbols: SymbolsTable<FunId, FunData<'source>>,
 }
 
 impl<'source> Functions<'source> {
-    pub fn get(&self, id: ast::Id<'source>) -> SemResult<'source, FunId> {
+    /// all the overloads of the function
+    pub fn get(&self, id: ast::Id<'source>) -> SemResult<'source, Vec<FunId>> {
         self.data
             .get(id.0)
             .ok_or_else(|| SemError::non_existent_function(id))
     }
 
+    /// functions can be overloaded by the arguments types, but `main` can't
     pub fn add(
         &mut self,
         id: ast::Id<'source>,
         args_types: Vec<Type>,
         ret_ty: Option<Type>,
     ) -> SemResult<'source, FunId> {
-        if let Some(fun_id) = self.data.get(id.0) {
+        let mut overloads = self.data.get(id.0).unwrap_or_default();
+
+        let redeclared = overloads
+            .iter()
+            .find(|&&fun_id| id.0 == "main" || self.symbols[fun_id].args_types == args_types);
+
+        if let Some(&fun_id) = redeclared {
             let first_declaration = self.symbols[fun_id].id.span();
 
-            Err(SemError::redeclaring_function(id, first_declaration))
-        } else {
-            let fun_data = FunData {
-                id,
-                args_types,
-                ret_ty,
-            };
+            return Err(SemError::redeclaring_function(id, first_declaration));
+        }
 
-            let fun_id = self.symbols.add(fun_data);
+        let fun_data = FunData {
+            id,
+            args_types,
+            ret_ty,
+            is_const: false,
+        };
 
-            self.data.add(id.0, fun_id);
+        let fun_id = self.symbols.add(fun_data);
 
-            Ok(fun_id)
+        overloads.push(fun_id);
+        self.data.add(id.0, overloads);
+
+        Ok(fun_id)
+    }
+
+    /// the name of an undefined function or a function with a broken signature,
+    /// it has no overloads, so its calls aren't reported
+    pub fn add_unresolved(&mut self, id: ast::Id<'source>) {
+        if !self.data.has(id.0) {
+            self.data.add(id.0, Vec::new());
         }
     }
+
+    /// a function that can't be called, e.g. a redeclared one,
+    /// so its body is still checked
+    pub fn add_placeholder(
+        &mut self,
+        id: ast::Id<'source>,
+        args_types: Vec<Type>,
+        ret_ty: Option<Type>,
+    ) -> FunId {
+        self.symbols.add(FunData {
+            id,
+            args_types,
+            ret_ty,
+            is_const: false,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct Types<'source> {
+    data: Scope<'source, Rc<StructType>>,
+    /// evaluates the array lengths that aren't literals
+    pub eval_len: Option<Box<LenEval<'source>>>,
+    /// the names used by the evaluated lengths
+    pub len_names: HashSet<&'source str>,
+}
+
+impl<'source> Types<'source> {
+    pub fn get(&self, id: ast::Id<'source>) -> SemResult<'source, Type> {
+        Type::try_from(id.0)
+            .ok()
+            .or_else(|| self.data.get(id.0).map(Type::Struct))
+            .ok_or_else(|| SemError::unknown_type(id))
+    }
+
+    pub fn get_struct(&self, id: ast::Id<'source>) -> SemResult<'source, Rc<StructType>> {
+        self.data
+            .get(id.0)
+            .ok_or_else(|| SemError::unknown_type(id))
+    }
+
+    pub fn has(&self, id: &'source str) -> bool {
+        self.data.has(id)
+    }
+
+    pub fn add(&mut self, id: &'source str, ty: Rc<StructType>) {
+        self.data.add(id, ty);
+    }
 }
 
 pub struct Scope<'source, V: Clone> {
@@ -3,6 +3,8 @@ use std::fmt;
 use smplc_ast as ast;
 use smplc_ast::Span;
 
+use crate::semcheck::MAX_ARRAY_LEN;
+
 pub type SemResult<'source, T> = Result<T, SemError<'source>>;
 
 #[derive(Debug)]
@@ -18,6 +20,42 @@ pub enum SemErrorKind<'source> {
     DuplicateArgsNames(&'source str),
     UsingUninitedVar(&'source str),
     UnknownType(&'source str),
+    InvalidArrayLen,
+    ArrayTooLong(usize),
+    UnexpectedString,
+    ExpectedFormatString,
+    InvalidFormatString(&'static str),
+    RecursiveStruct(&'source str),
+    BuiltinTypeName(&'source str),
+    DuplicateField(&'source str),
+    DuplicateSwizzleComponent(ast::Component),
+    InvalidPattern(&'static str),
+    OverlappingPattern,
+    NonConstCall(&'source str),
+    StaticInConst(&'source str),
+    UnknownAttribute(&'source str),
+    UnknownLint(&'source str),
+    MissingReturn,
+    MissingBlockValue,
+
+    NonExhaustiveMatch {
+        missing: String,
+    },
+
+    NonExistentField {
+        ty: &'source str,
+        field: &'source str,
+    },
+
+    MissingField {
+        ty: &'source str,
+        field: String,
+    },
+
+    RedeclaringType {
+        id: &'source str,
+        first_declaration: Span,
+    },
 
     RedeclaringVariable {
         id: &'source str,
@@ -34,6 +72,11 @@ pub enum SemErrorKind<'source> {
         received: usize,
         fun_id: &'source str,
     },
+
+    FormatArgsCount {
+        placeholders: usize,
+        received: usize,
+    },
 }
 
 impl<'source> SemError<'source> {
@@ -77,6 +120,17 @@ impl<'source> SemError<'source> {
         Self { kind, span }
     }
 
+    pub fn redeclaring_type(id: ast::Id<'source>, first_declaration: Span) -> Self {
+        let ast::Spanned(id, span) = id;
+
+        let kind = SemErrorKind::RedeclaringType {
+            id,
+            first_declaration,
+        };
+
+        Self { kind, span }
+    }
+
     pub fn invalid_arguments_count(id: ast::Id<'source>, expected: usize, received: usize) -> Self {
         let kind = SemErrorKind::InvalidArgumentsCount {
             expected,
@@ -116,6 +170,174 @@ impl<'source> SemError<'source> {
             span,
         }
     }
+
+    pub fn invalid_array_len(span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::InvalidArrayLen,
+            span,
+        }
+    }
+
+    pub fn array_too_long(len: usize, span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::ArrayTooLong(len),
+            span,
+        }
+    }
+
+    pub fn unexpected_string(span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::UnexpectedString,
+            span,
+        }
+    }
+
+    pub fn expected_format_string(span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::ExpectedFormatString,
+            span,
+        }
+    }
+
+    pub fn invalid_format_string(reason: &'static str, span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::InvalidFormatString(reason),
+            span,
+        }
+    }
+
+    pub fn format_args_count(placeholders: usize, received: usize, span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::FormatArgsCount {
+                placeholders,
+                received,
+            },
+            span,
+        }
+    }
+
+    pub fn recursive_struct(ty: ast::Id<'source>) -> Self {
+        let ast::Spanned(id, span) = ty;
+
+        Self {
+            kind: SemErrorKind::RecursiveStruct(id),
+            span,
+        }
+    }
+
+    pub fn builtin_type_name(ty: ast::Id<'source>) -> Self {
+        let ast::Spanned(id, span) = ty;
+
+        Self {
+            kind: SemErrorKind::BuiltinTypeName(id),
+            span,
+        }
+    }
+
+    pub fn duplicate_field(field: ast::Id<'source>) -> Self {
+        let ast::Spanned(id, span) = field;
+
+        Self {
+            kind: SemErrorKind::DuplicateField(id),
+            span,
+        }
+    }
+
+    pub fn duplicate_swizzle_component(component: ast::Component, span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::DuplicateSwizzleComponent(component),
+            span,
+        }
+    }
+
+    pub fn invalid_pattern(reason: &'static str, span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::InvalidPattern(reason),
+            span,
+        }
+    }
+
+    pub fn overlapping_pattern(span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::OverlappingPattern,
+            span,
+        }
+    }
+
+    pub fn non_exhaustive_match(missing: String, span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::NonExhaustiveMatch { missing },
+            span,
+        }
+    }
+
+    pub fn non_const_call(id: ast::Id<'source>) -> Self {
+        let ast::Spanned(id, span) = id;
+
+        Self {
+            kind: SemErrorKind::NonConstCall(id),
+            span,
+        }
+    }
+
+    pub fn static_in_const(id: ast::Id<'source>) -> Self {
+        let ast::Spanned(id, span) = id;
+
+        Self {
+            kind: SemErrorKind::StaticInConst(id),
+            span,
+        }
+    }
+
+    pub fn unknown_attribute(id: ast::Id<'source>) -> Self {
+        let ast::Spanned(id, span) = id;
+
+        Self {
+            kind: SemErrorKind::UnknownAttribute(id),
+            span,
+        }
+    }
+
+    pub fn unknown_lint(id: ast::Id<'source>) -> Self {
+        let ast::Spanned(id, span) = id;
+
+        Self {
+            kind: SemErrorKind::UnknownLint(id),
+            span,
+        }
+    }
+
+    pub fn missing_return(span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::MissingReturn,
+            span,
+        }
+    }
+
+    pub fn missing_block_value(span: Span) -> Self {
+        Self {
+            kind: SemErrorKind::MissingBlockValue,
+            span,
+        }
+    }
+
+    pub fn non_existent_field(ty: ast::Id<'source>, field: ast::Id<'source>) -> Self {
+        let ast::Spanned(field, span) = field;
+
+        Self {
+            kind: SemErrorKind::NonExistentField { ty: ty.0, field },
+            span,
+        }
+    }
+
+    pub fn missing_field(ty: ast::Id<'source>, field: String) -> Self {
+        let ast::Spanned(ty, span) = ty;
+
+        Self {
+            kind: SemErrorKind::MissingField { ty, field },
+            span,
+        }
+    }
 }
 
 impl fmt::Display for SemErrorKind<'_> {
@@ -139,6 +361,16 @@ impl fmt::Display for SemErrorKind<'_> {
                 )
             }
 
+            SemErrorKind::RedeclaringType {
+                id,
+                first_declaration,
+            } => {
+                write!(
+                    f,
+                    "type \"{id}\" is already declared at {first_declaration}"
+                )
+            }
+
             SemErrorKind::InvalidArgumentsCount {
                 expected,
                 received,
@@ -169,6 +401,102 @@ impl fmt::Display for SemErrorKind<'_> {
             SemErrorKind::UnknownType(ty) => {
                 write!(f, "unknow type: \"{ty}\"")
             }
+
+            SemErrorKind::InvalidArrayLen => {
+                write!(f, "array length must be a constant expression of type int")
+            }
+
+            SemErrorKind::ArrayTooLong(len) => {
+                write!(
+                    f,
+                    "array length {len} is above the limit of {MAX_ARRAY_LEN}"
+                )
+            }
+
+            SemErrorKind::UnexpectedString => {
+                write!(f, "strings can only be used as a format of print")
+            }
+
+            SemErrorKind::ExpectedFormatString => {
+                write!(f, "expected a format string")
+            }
+
+            SemErrorKind::InvalidFormatString(reason) => {
+                write!(f, "invalid format string: {reason}")
+            }
+
+            SemErrorKind::FormatArgsCount {
+                placeholders,
+                received,
+            } => {
+                write!(
+                    f,
+                    "format string has {placeholders} placeholders, but received {received} arguments"
+                )
+            }
+
+            SemErrorKind::RecursiveStruct(ty) => {
+                write!(f, "struct \"{ty}\" contains itself")
+            }
+
+            SemErrorKind::BuiltinTypeName(ty) => {
+                write!(f, "a struct can't be named \"{ty}\", it's a built-in type")
+            }
+
+            SemErrorKind::DuplicateField(field) => {
+                write!(f, "field \"{field}\" is specified twice")
+            }
+
+            SemErrorKind::DuplicateSwizzleComponent(component) => {
+                write!(f, "component \"{component}\" is assigned twice")
+            }
+
+            SemErrorKind::InvalidPattern(reason) => {
+                write!(f, "invalid pattern: {reason}")
+            }
+
+            SemErrorKind::OverlappingPattern => {
+                write!(f, "pattern is unreachable, its values are already matched")
+            }
+
+            SemErrorKind::NonExhaustiveMatch { missing } => {
+                write!(f, "match doesn't cover {missing}")
+            }
+
+            SemErrorKind::NonConstCall(name) => {
+                write!(
+                    f,
+                    "function \"{name}\" can't be called at compile time, it isn't a const fn"
+                )
+            }
+
+            SemErrorKind::StaticInConst(id) => {
+                write!(f, "static \"{id}\" can't be used at compile time")
+            }
+
+            SemErrorKind::UnknownAttribute(id) => {
+                write!(f, "unknown attribute \"{id}\"")
+            }
+
+            SemErrorKind::UnknownLint(id) => {
+                write!(f, "unknown lint \"{id}\"")
+            }
+
+            SemErrorKind::MissingReturn => {
+                write!(f, "not all paths return a value")
+            }
+
+            SemErrorKind::MissingBlockValue => {
+                write!(f, "the block has no value and its end is reachable")
+            }
+
+            SemErrorKind::NonExistentField { ty, field } => {
+                write!(f, "struct \"{ty}\" has no field \"{field}\"")
+            }
+
+            SemErrorKind::MissingField { ty, field } => {
+                write!(f, "missing field \"{field}\" in \"{ty}\" literal")
+            }
         }
     }
 }
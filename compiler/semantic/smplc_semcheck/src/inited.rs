@@ -9,6 +9,11 @@ pub trait Inited {
     fn add(&mut self, vars: HashSet<VarId>);
     fn init(&mut self, var: VarId);
     fn is_inited(&self, var: VarId) -> bool;
+    /// all the variables that are inited at the current statement
+    fn inited_vars(&self) -> HashSet<VarId>;
+    /// the code after the current statement is never reached,
+    /// e.g. after a `loop` without `break`
+    fn diverge(&mut self);
     fn exit(self);
 }
 
@@ -16,6 +21,7 @@ pub trait Inited {
 pub struct GeneralInited<'parent> {
     parent: Option<&'parent mut dyn Inited>,
     inited: HashSet<VarId>,
+    diverged: bool,
 }
 
 impl<'parent> GeneralInited<'parent> {
@@ -44,21 +50,42 @@ impl Inited for GeneralInited<'_> {
                 .is_some_and(|parent| parent.is_inited(var))
     }
 
+    fn inited_vars(&self) -> HashSet<VarId> {
+        let mut vars = self
+            .parent
+            .as_ref()
+            .map(|parent| parent.inited_vars())
+            .unwrap_or_default();
+
+        vars.extend(&self.inited);
+        vars
+    }
+
+    fn diverge(&mut self) {
+        self.diverged = true;
+    }
+
     fn exit(self) {
         let Some(parent) = self.parent else {
             return;
         };
 
-        parent.add(self.inited);
+        if self.diverged {
+            parent.diverge();
+        } else {
+            parent.add(self.inited);
+        }
     }
 }
 
 /// requires initializing in all the nested Inited
+/// except the diverging ones
 /// useful in if statements
 #[derive(Default)]
 pub struct AndInited<'parent> {
     parent: Option<&'parent mut dyn Inited>,
     first: Option<HashSet<VarId>>,
+    diverged: bool,
 }
 
 impl<'parent> AndInited<'parent> {
@@ -83,16 +110,21 @@ impl Inited for AndInited<'_> {
         unreachable!()
     }
 
+    fn diverge(&mut self) {
+        self.diverged = true;
+    }
+
     fn exit(self) {
         let Some(parent) = self.parent else {
             return;
         };
 
-        let Some(first) = self.first else {
-            return;
-        };
-
-        parent.add(first);
+        match self.first {
+            Some(first) => parent.add(first),
+            // all the branches diverge
+            None if self.diverged => parent.diverge(),
+            None => {}
+        }
     }
 
     fn is_inited(&self, var: VarId) -> bool {
@@ -102,6 +134,13 @@ impl Inited for AndInited<'_> {
             .as_ref()
             .is_some_and(|parent| parent.is_inited(var))
     }
+
+    fn inited_vars(&self) -> HashSet<VarId> {
+        self.parent
+            .as_ref()
+            .map(|parent| parent.inited_vars())
+            .unwrap_or_default()
+    }
 }
 
 /// init variables only at it level and doesn't pass them below
@@ -138,5 +177,19 @@ impl Inited for NothingInited<'_> {
                 .is_some_and(|parent| parent.is_inited(var))
     }
 
+    fn inited_vars(&self) -> HashSet<VarId> {
+        let mut vars = self
+            .parent
+            .as_ref()
+            .map(|parent| parent.inited_vars())
+            .unwrap_or_default();
+
+        vars.extend(&self.inited);
+        vars
+    }
+
+    // the body of a loop can be not run at all
+    fn diverge(&mut self) {}
+
     fn exit(self) {}
 }
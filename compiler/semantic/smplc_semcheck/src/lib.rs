@@ -1,7 +1,9 @@
 pub mod error;
+pub mod warning;
 
 mod env;
 mod inited;
+mod returns;
 mod semcheck;
 
 #[cfg(test)]
@@ -9,51 +11,138 @@ mod tests;
 
 use smplc_ast as ast;
 use smplc_ast::Span;
-use smplc_hir::{Symbols, Type, HIR};
+use smplc_hir::{FunId, Symbols, Type, VarId, HIR};
 
 use env::Env;
-use error::SemResult;
+use error::SemError;
 use inited::GeneralInited;
-use semcheck::{RawType, SemCheck};
+use semcheck::{declare_structs, RawType, SemCheck};
+use warning::{Lint, Warning};
 
-pub fn sem_check(ast: Vec<ast::Declaration>) -> SemResult<HIR> {
+pub use semcheck::LenEval;
+
+/// Checks the whole program and collects all the errors and the warnings.
+/// The HIR is built even if there are some errors, but the broken statements
+/// and declarations are left out and the undefined variables are replaced with placeholders.
+/// Only integer literals are allowed as array lengths
+pub fn sem_check(ast: Vec<ast::Declaration>) -> (HIR, Vec<SemError>, Vec<Warning>) {
+    check(ast, None)
+}
+
+/// Checks the program like `sem_check`,
+/// the array lengths that aren't literals are evaluated by `eval_len`
+pub fn sem_check_with_lengths<'source>(
+    ast: Vec<ast::Declaration<'source>>,
+    eval_len: Box<LenEval<'source>>,
+) -> (HIR<'source>, Vec<SemError<'source>>, Vec<Warning<'source>>) {
+    check(ast, Some(eval_len))
+}
+
+fn check<'source>(
+    ast: Vec<ast::Declaration<'source>>,
+    eval_len: Option<Box<LenEval<'source>>>,
+) -> (HIR<'source>, Vec<SemError<'source>>, Vec<Warning<'source>>) {
     let mut env = Env::default();
     env.variables.fork();
+    env.types.eval_len = eval_len;
 
     init_std(&mut env);
 
     let mut hir = HIR::default();
 
+    let structs = ast
+        .iter()
+        .filter_map(|declaration| match declaration {
+            ast::Declaration::Struct(decl) => Some(decl),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    declare_structs(&mut env, &structs);
+
+    let mut fun_ids = Vec::new();
+
     for declaration in ast.iter() {
         if let ast::Declaration::Function(function) = declaration {
-            let args_types = function
-                .args
-                .iter()
-                .map(|arg| RawType(arg.ty).checked())
-                .collect::<Result<_, _>>()?;
-
-            let ret_ty = function
-                .ret_ty
-                .map(|arg| RawType(arg).checked())
-                .transpose()?;
-
-            env.functions.add(function.id, args_types, ret_ty)?;
+            fun_ids.push(declare_function(&mut env, function));
         }
     }
 
     let mut inited = GeneralInited::default();
+    let mut fun_ids = fun_ids.into_iter();
+
+    // the globals and the functions are linted when all their uses are known
+    let mut globals = Vec::new();
+    let mut functions = Vec::new();
 
     for declaration in ast {
         match declaration {
             ast::Declaration::Function(function) => {
-                env.current_fn = Some(env.functions.get(function.id).unwrap());
+                let Some(fun_id) = fun_ids.next().unwrap() else {
+                    continue;
+                };
+
+                env.current_fn = Some(fun_id);
+                env.allowed = allowed_lints(&mut env, &function.attributes);
 
-                hir.functions.push(function.check(&mut env, &mut inited)?);
+                match function.check(&mut env, &mut inited) {
+                    Ok(function) => hir.functions.push(function),
+                    Err(err) => env.error(err),
+                }
+
+                env.lint_variables();
+
+                functions.push((fun_id, std::mem::take(&mut env.allowed)));
             }
 
             ast::Declaration::Constant(constant) => {
-                hir.constants.push(constant.check(&mut env, &mut inited)?);
+                env.allowed = allowed_lints(&mut env, &constant.attributes);
+
+                match constant.check(&mut env, &mut inited) {
+                    Ok(constant) => hir.constants.push(constant),
+                    Err(err) => env.error(err),
+                }
+
+                lint_global(&mut env, &mut globals);
             }
+
+            ast::Declaration::Static(static_) => {
+                env.allowed = allowed_lints(&mut env, &static_.attributes);
+
+                match static_.check(&mut env, &mut inited) {
+                    Ok(static_) => hir.statics.push(static_),
+                    Err(err) => env.error(err),
+                }
+
+                lint_global(&mut env, &mut globals);
+            }
+
+            // imports are resolved by the driver
+            ast::Declaration::Struct(_) | ast::Declaration::Import(_) => {}
+        }
+    }
+
+    for (var, allowed) in globals {
+        let id = env.variables.symbols[var].id;
+
+        env.allowed = allowed;
+
+        let used = env.read.contains(&var) || env.types.len_names.contains(id.0);
+
+        if !used && !id.0.starts_with('_') {
+            env.warn(Warning::unused_constant(id));
+        }
+    }
+
+    for (fun_id, allowed) in functions {
+        let id = env.functions.symbols[fun_id].id;
+
+        env.allowed = allowed;
+
+        let called = env.called.contains(&fun_id) || env.types.len_names.contains(id.0);
+
+        if !called && id.0 != "main" && !id.0.starts_with('_') {
+            env.warn(Warning::unused_function(id));
         }
     }
 
@@ -62,7 +151,99 @@ pub fn sem_check(ast: Vec<ast::Declaration>) -> SemResult<HIR> {
         variables: env.variables.symbols,
     };
 
-    Ok(hir)
+    let mut errors = env.errors;
+
+    // an error of an expression is found before the error of its statement
+    errors.sort_by_key(|error| (error.span.file().0, error.span.start().index()));
+
+    let mut warnings = env.warnings;
+
+    warnings.sort_by_key(|warning| (warning.span.file().0, warning.span.start().index()));
+
+    (hir, errors, warnings)
+}
+
+/// Collects the lints allowed by `#[allow(...)]`, there are no other attributes
+fn allowed_lints<'source>(
+    env: &mut Env<'source>,
+    attributes: &[ast::Attribute<'source>],
+) -> Vec<Lint> {
+    let mut allowed = Vec::new();
+
+    for attribute in attributes {
+        if attribute.id.0 != "allow" {
+            env.error(SemError::unknown_attribute(attribute.id));
+
+            continue;
+        }
+
+        for &lint in &attribute.args {
+            match Lint::named(lint.0) {
+                Some(lints) => allowed.extend(lints),
+                None => env.error(SemError::unknown_lint(lint)),
+            }
+        }
+    }
+
+    allowed
+}
+
+/// The constant or the static is declared before the variables of its value,
+/// it's kept with its allowed lints, and the variables are linted right away
+fn lint_global(env: &mut Env, globals: &mut Vec<(VarId, Vec<Lint>)>) {
+    let global = env.declared.remove(0);
+
+    env.lint_variables();
+
+    globals.push((global, std::mem::take(&mut env.allowed)));
+}
+
+/// Registers the function, a redeclared one gets a placeholder symbol,
+/// so its body is checked too. The function with an unknown type
+/// in its signature is left out
+fn declare_function<'source>(
+    env: &mut Env<'source>,
+    function: &ast::FunctionDeclaration<'source>,
+) -> Option<FunId> {
+    let mut checked = |ty: &ast::Spanned<ast::Type<'source>>| {
+        RawType(ty)
+            .checked(&mut env.types)
+            .map_err(|err| env.error(err))
+            .ok()
+    };
+
+    let args_types = function
+        .args
+        .iter()
+        .map(|arg| checked(&arg.ty))
+        .collect::<Vec<_>>();
+
+    let ret_ty = function.ret_ty.as_ref().map(&mut checked);
+
+    let signature = args_types
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .zip(ret_ty.map_or(Some(None), |ty| ty.map(Some)));
+
+    let Some((args_types, ret_ty)) = signature else {
+        env.functions.add_unresolved(function.id);
+
+        return None;
+    };
+
+    let fun_id = env
+        .functions
+        .add(function.id, args_types.clone(), ret_ty.clone())
+        .unwrap_or_else(|err| {
+            env.error(err);
+
+            env.functions
+                .add_placeholder(function.id, args_types, ret_ty)
+        });
+
+    env.functions.symbols[fun_id].is_const = function.is_const;
+
+    Some(fun_id)
 }
 
 pub fn init_std(env: &mut Env) {
@@ -138,6 +319,54 @@ pub fn init_std(env: &mut Env) {
         )
         .unwrap();
 
+    env.functions
+        .add(
+            ast::Id::new("mat2", Span::default()),
+            vec![Type::Vec2, Type::Vec2],
+            Some(Type::Mat2),
+        )
+        .unwrap();
+
+    env.functions
+        .add(
+            ast::Id::new("mat3", Span::default()),
+            vec![Type::Vec3, Type::Vec3, Type::Vec3],
+            Some(Type::Mat3),
+        )
+        .unwrap();
+
+    env.functions
+        .add(
+            ast::Id::new("mat4", Span::default()),
+            vec![Type::Vec4, Type::Vec4, Type::Vec4, Type::Vec4],
+            Some(Type::Mat4),
+        )
+        .unwrap();
+
+    env.functions
+        .add(
+            ast::Id::new("printmat2", Span::default()),
+            vec![Type::Mat2],
+            None,
+        )
+        .unwrap();
+
+    env.functions
+        .add(
+            ast::Id::new("printmat3", Span::default()),
+            vec![Type::Mat3],
+            None,
+        )
+        .unwrap();
+
+    env.functions
+        .add(
+            ast::Id::new("printmat4", Span::default()),
+            vec![Type::Mat4],
+            None,
+        )
+        .unwrap();
+
     env.functions
         .add(
             ast::Id::new("printc", Span::default()),
@@ -145,4 +374,151 @@ pub fn init_std(env: &mut Env) {
             None,
         )
         .unwrap();
+
+    // they read stdin and exit the program if the input is malformed
+    for (name, ty) in [
+        ("readi", Type::Int),
+        ("readr", Type::Real),
+        ("readc", Type::Complex),
+        ("readvec2", Type::Vec2),
+        ("readvec3", Type::Vec3),
+        ("readvec4", Type::Vec4),
+    ] {
+        env.functions
+            .add(ast::Id::new(name, Span::default()), Vec::new(), Some(ty))
+            .unwrap();
+    }
+
+    for name in [
+        "sqrt", "sin", "cos", "tan", "exp", "ln", "floor", "ceil", "abs",
+    ] {
+        env.functions
+            .add(
+                ast::Id::new(name, Span::default()),
+                vec![Type::Real],
+                Some(Type::Real),
+            )
+            .unwrap();
+    }
+
+    for name in ["atan2", "pow", "min", "max"] {
+        env.functions
+            .add(
+                ast::Id::new(name, Span::default()),
+                vec![Type::Real, Type::Real],
+                Some(Type::Real),
+            )
+            .unwrap();
+    }
+
+    env.functions
+        .add(
+            ast::Id::new("clamp", Span::default()),
+            vec![Type::Real, Type::Real, Type::Real],
+            Some(Type::Real),
+        )
+        .unwrap();
+
+    // int overloads
+    for (name, arity) in [("abs", 1), ("min", 2), ("max", 2), ("clamp", 3)] {
+        env.functions
+            .add(
+                ast::Id::new(name, Span::default()),
+                vec![Type::Int; arity],
+                Some(Type::Int),
+            )
+            .unwrap();
+    }
+
+    for (name, ret_ty) in [
+        ("re", Type::Real),
+        ("im", Type::Real),
+        ("abs", Type::Real),
+        ("arg", Type::Real),
+        ("conj", Type::Complex),
+        ("cexp", Type::Complex),
+        ("clog", Type::Complex),
+        ("csqrt", Type::Complex),
+    ] {
+        env.functions
+            .add(
+                ast::Id::new(name, Span::default()),
+                vec![Type::Complex],
+                Some(ret_ty),
+            )
+            .unwrap();
+    }
+
+    // `polar(r, theta)`
+    env.functions
+        .add(
+            ast::Id::new("polar", Span::default()),
+            vec![Type::Real, Type::Real],
+            Some(Type::Complex),
+        )
+        .unwrap();
+
+    env.functions
+        .add(
+            ast::Id::new("cpow", Span::default()),
+            vec![Type::Complex, Type::Int],
+            Some(Type::Complex),
+        )
+        .unwrap();
+
+    // the real part goes first
+    env.functions
+        .add(
+            ast::Id::new("quat", Span::default()),
+            vec![Type::Real; 4],
+            Some(Type::Quat),
+        )
+        .unwrap();
+
+    for name in ["conjugate", "normalize"] {
+        env.functions
+            .add(
+                ast::Id::new(name, Span::default()),
+                vec![Type::Quat],
+                Some(Type::Quat),
+            )
+            .unwrap();
+    }
+
+    env.functions
+        .add(
+            ast::Id::new("quat_from_axis_angle", Span::default()),
+            vec![Type::Vec3, Type::Real],
+            Some(Type::Quat),
+        )
+        .unwrap();
+
+    env.functions
+        .add(
+            ast::Id::new("rotate", Span::default()),
+            vec![Type::Quat, Type::Vec3],
+            Some(Type::Vec3),
+        )
+        .unwrap();
+
+    env.functions
+        .add(
+            ast::Id::new("slerp", Span::default()),
+            vec![Type::Quat, Type::Quat, Type::Real],
+            Some(Type::Quat),
+        )
+        .unwrap();
+
+    // the constants evaluator folds the calls of all the std functions but printing and reading
+    let folded = env
+        .functions
+        .symbols
+        .iter()
+        .filter(|(_, fun)| !fun.id.0.starts_with("print") && !fun.id.0.starts_with("read"))
+        .map(|(fun_id, _)| fun_id)
+        .collect::<Vec<_>>();
+
+    for fun_id in folded {
+        env.functions.symbols[fun_id].is_const = true;
+    }
 }
@@ -0,0 +1,101 @@
+use smplc_ast::Id;
+use smplc_hir::{Atom, Block, Expr, Literal, Statement};
+
+/// Checks that the end of the block is never reached: every path returns,
+/// jumps out of it or gets stuck in an endless loop
+pub fn diverges(block: &Block) -> bool {
+    statements_diverge(&block.statements)
+}
+
+/// the same check for the statements of a block with a value
+pub fn statements_diverge(statements: &[Statement]) -> bool {
+    statements.iter().any(|stmt| match stmt {
+        Statement::Return(_) | Statement::Break(_) | Statement::Continue(_) => true,
+
+        Statement::If(if_stmt) => if_stmt
+            .else_body
+            .as_ref()
+            .is_some_and(|else_body| diverges(&if_stmt.body) && diverges(else_body)),
+
+        // the match is exhaustive, so one of the arms is run
+        Statement::Match(match_stmt) => match_stmt.arms.iter().all(|arm| diverges(&arm.body)),
+
+        // a loop without `break` never ends
+        Statement::Loop(loop_stmt) => !has_jump(&loop_stmt.body, Jump::Break, loop_stmt.label),
+
+        Statement::While(while_stmt) => {
+            is_true(&while_stmt.cond.0)
+                && !has_jump(&while_stmt.body, Jump::Break, while_stmt.label)
+        }
+
+        // the body is run at least once, the condition is checked after `continue`
+        Statement::DoWhile(do_while) => {
+            let body_diverges = diverges(&do_while.body)
+                && !has_jump(&do_while.body, Jump::Continue, do_while.label);
+
+            (is_true(&do_while.cond.0) || body_diverges)
+                && !has_jump(&do_while.body, Jump::Break, do_while.label)
+        }
+
+        // the range can be empty
+        Statement::For(_) | Statement::Expr(_) => false,
+    })
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Jump {
+    Break,
+    Continue,
+}
+
+/// checks if the body of the loop with the label has a jump of this loop
+fn has_jump(body: &Block, jump: Jump, label: Option<Id>) -> bool {
+    find_jump(body, jump, label.map(|label| label.0), false)
+}
+
+/// `nested` is set inside the inner loops, the unlabeled jumps there are their own
+fn find_jump(block: &Block, jump: Jump, label: Option<&str>, nested: bool) -> bool {
+    let targets = |jump_label: &Option<Id>| match jump_label {
+        Some(jump_label) => Some(jump_label.0) == label,
+        None => !nested,
+    };
+
+    // an inner loop with the same label takes its jumps
+    let inner_loop = |body: &Block, inner_label: Option<Id>| {
+        let shadows = label.is_some() && inner_label.map(|inner| inner.0) == label;
+
+        !shadows && find_jump(body, jump, label, true)
+    };
+
+    block.statements.iter().any(|stmt| match stmt {
+        Statement::Break(jump_label) => jump == Jump::Break && targets(jump_label),
+        Statement::Continue(jump_label) => jump == Jump::Continue && targets(jump_label),
+
+        Statement::If(if_stmt) => {
+            find_jump(&if_stmt.body, jump, label, nested)
+                || if_stmt
+                    .else_body
+                    .as_ref()
+                    .is_some_and(|else_body| find_jump(else_body, jump, label, nested))
+        }
+
+        Statement::Match(match_stmt) => match_stmt
+            .arms
+            .iter()
+            .any(|arm| find_jump(&arm.body, jump, label, nested)),
+
+        Statement::While(while_stmt) => inner_loop(&while_stmt.body, while_stmt.label),
+        Statement::DoWhile(do_while) => inner_loop(&do_while.body, do_while.label),
+        Statement::Loop(loop_stmt) => inner_loop(&loop_stmt.body, loop_stmt.label),
+        Statement::For(for_stmt) => inner_loop(&for_stmt.body, for_stmt.label),
+
+        Statement::Return(_) | Statement::Expr(_) => false,
+    })
+}
+
+fn is_true(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Atom(Atom::Literal(Literal { value: "true", .. }))
+    )
+}
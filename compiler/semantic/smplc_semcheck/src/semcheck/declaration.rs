@@ -1,9 +1,20 @@
-use smplc_ast::{ConstantDeclaration, FunctionDeclaration};
-use smplc_hir::{Constant, Function};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
-use crate::{env::Env, error::SemResult, inited::Inited};
+use smplc_ast as ast;
+use smplc_ast::{
+    ConstantDeclaration, FunctionDeclaration, Spanned, StaticDeclaration, StructDeclaration,
+};
+use smplc_hir::{Constant, Function, Static, StructField, StructType, Type};
 
-use super::{RawType, SemCheck};
+use crate::{
+    env::Env,
+    error::{SemError, SemResult},
+    inited::Inited,
+    returns::diverges,
+};
+
+use super::{array_len, RawType, SemCheck};
 
 impl<'source> SemCheck<'source> for FunctionDeclaration<'source> {
     type Checked = Function<'source>;
@@ -13,23 +24,43 @@ impl<'source> SemCheck<'source> for FunctionDeclaration<'source> {
         env: &mut Env<'source>,
         inited: &mut impl Inited,
     ) -> SemResult<'source, Self::Checked> {
-        let id = env.functions.get(self.id).unwrap();
+        let id = env.current_fn.unwrap();
 
+        env.in_const = self.is_const;
         env.variables.fork();
 
+        let args_types = env.functions.symbols[id].args_types.clone();
+
         let args = self
             .args
             .into_iter()
-            .map(|id| env.variables.add_argument(id))
-            .collect::<Result<_, _>>()?;
+            .zip(args_types)
+            .map(|(arg, ty)| {
+                env.variables
+                    .add_argument(arg.id, ty.clone())
+                    .unwrap_or_else(|err| {
+                        env.error(err);
+
+                        env.variables.add_placeholder(arg.id, Some(ty))
+                    })
+            })
+            .collect::<Vec<_>>();
 
         for &arg in &args {
             inited.init(arg);
         }
 
+        env.declared.extend(&args);
+
         let body = self.body.check(env, inited)?;
 
+        // otherwise the function would run past its end with no value to return
+        if env.functions.symbols[id].ret_ty.is_some() && !diverges(&body) {
+            env.error(SemError::missing_return(self.end));
+        }
+
         env.variables.exit();
+        env.in_const = false;
 
         Ok(Function { id, args, body })
     }
@@ -43,12 +74,190 @@ impl<'source> SemCheck<'source> for ConstantDeclaration<'source> {
         env: &mut Env<'source>,
         inited: &mut impl Inited,
     ) -> SemResult<'source, Self::Checked> {
-        let ty = RawType(self.ty).checked()?;
-        let id = env.variables.add_variable(self.id, Some(ty))?;
-        let value = self.value.check(env, inited)?;
+        // the constant is declared even if it's broken, so its uses aren't reported
+        let ty = RawType(&self.ty).checked(&mut env.types);
+        let id = env.declare(self.id, ty.as_ref().ok().cloned());
+
+        env.in_const = true;
+        let value = self.value.check(env, inited);
+        env.in_const = false;
 
         inited.init(id);
 
+        let (ty, value) = (ty?, value?);
+
         Ok(Constant { id, ty, value })
     }
 }
+
+impl<'source> SemCheck<'source> for StaticDeclaration<'source> {
+    type Checked = Static<'source>;
+
+    fn check(
+        self,
+        env: &mut Env<'source>,
+        inited: &mut impl Inited,
+    ) -> SemResult<'source, Self::Checked> {
+        // the static is declared even if it's broken, so its uses aren't reported
+        let ty = RawType(&self.ty).checked(&mut env.types);
+        let id = env.declare(self.id, ty.as_ref().ok().cloned());
+
+        env.in_const = true;
+        let value = self.value.check(env, inited);
+        env.in_const = false;
+
+        env.statics.insert(id);
+
+        // a static holds its initializer from the very start of the program
+        inited.init(id);
+
+        let (ty, value) = (ty?, value?);
+
+        Ok(Static { id, ty, value })
+    }
+}
+
+/// Registers all the structs at once,
+/// so they can refer to each other regardless of the order.
+/// A broken struct is reported and left undeclared, the others are still declared
+pub fn declare_structs<'source>(env: &mut Env<'source>, structs: &[&StructDeclaration<'source>]) {
+    let mut declared: HashMap<_, &StructDeclaration> = HashMap::new();
+
+    for &decl in structs {
+        if Type::try_from(decl.id.0).is_ok() {
+            env.error(SemError::builtin_type_name(decl.id));
+            continue;
+        }
+
+        match declared.get(decl.id.0) {
+            Some(first) => env.error(SemError::redeclaring_type(decl.id, first.id.span())),
+            None => {
+                declared.insert(decl.id.0, decl);
+            }
+        }
+    }
+
+    let mut resolver = StructResolver {
+        declared,
+        resolving: Vec::new(),
+        broken: HashSet::new(),
+    };
+
+    // the rejected declarations are skipped
+    for &decl in structs {
+        if resolver
+            .declared
+            .get(decl.id.0)
+            .is_some_and(|&declared| std::ptr::eq(declared, decl))
+        {
+            resolver.resolve(env, decl);
+        }
+    }
+}
+
+struct StructResolver<'a, 'source> {
+    declared: HashMap<&'source str, &'a StructDeclaration<'source>>,
+    resolving: Vec<&'source str>,
+    /// the structs with errors, they're reported once
+    broken: HashSet<&'source str>,
+}
+
+impl<'a, 'source> StructResolver<'a, 'source> {
+    /// reports the errors of the struct, `None` if it's broken
+    fn resolve(
+        &mut self,
+        env: &mut Env<'source>,
+        decl: &StructDeclaration<'source>,
+    ) -> Option<Rc<StructType>> {
+        if env.types.has(decl.id.0) {
+            return env.types.get_struct(decl.id).ok();
+        }
+
+        if self.broken.contains(decl.id.0) {
+            return None;
+        }
+
+        self.resolving.push(decl.id.0);
+
+        let mut fields: Vec<StructField> = Vec::new();
+        let mut broken = false;
+
+        for field in decl.fields.iter() {
+            if fields.iter().any(|prev| prev.name == field.id.0) {
+                env.error(SemError::duplicate_field(field.id));
+                broken = true;
+
+                continue;
+            }
+
+            match self.field_ty(env, &field.ty) {
+                Some(ty) => fields.push(StructField {
+                    name: field.id.0.into(),
+                    ty,
+                }),
+                None => broken = true,
+            }
+        }
+
+        self.resolving.pop();
+
+        if broken {
+            self.broken.insert(decl.id.0);
+
+            return None;
+        }
+
+        let ty = Rc::new(StructType {
+            name: decl.id.0.into(),
+            fields,
+        });
+
+        env.types.add(decl.id.0, Rc::clone(&ty));
+
+        Some(ty)
+    }
+
+    /// reports the errors of the type, `None` if it's broken
+    fn field_ty(
+        &mut self,
+        env: &mut Env<'source>,
+        ty: &Spanned<ast::Type<'source>>,
+    ) -> Option<Type> {
+        let &Spanned(ref raw, span) = ty;
+
+        let ty = match raw {
+            &ast::Type::Named(name) if self.resolving.contains(&name) => {
+                Err(SemError::recursive_struct(Spanned(name, span)))
+            }
+
+            &ast::Type::Named(name) if self.declared.contains_key(name) => {
+                let decl = self.declared[name];
+
+                return self.resolve(env, decl).map(Type::Struct);
+            }
+
+            ast::Type::Array { elem, len } => {
+                let elem = self.field_ty(env, elem);
+                let len = array_len(&mut env.types, len)
+                    .map_err(|err| env.error(err))
+                    .ok();
+
+                return Some(Type::array(elem?, len?));
+            }
+
+            ast::Type::Tuple(elems) => {
+                // all the elements are reported
+                let elems = elems
+                    .iter()
+                    .map(|elem| self.field_ty(env, elem))
+                    .collect::<Vec<_>>();
+
+                return elems.into_iter().collect::<Option<_>>().map(Type::Tuple);
+            }
+
+            _ => RawType(ty).checked(&mut env.types),
+        };
+
+        ty.map_err(|err| env.error(err)).ok()
+    }
+}
@@ -1,14 +1,18 @@
 use smplc_ast as ast;
 use smplc_ast::{Call, Spanned};
-use smplc_hir::{Atom, Expr, FunData};
+use smplc_hir::{Atom, BlockExpr, Expr, FunData, Intrinsic};
 
 use crate::{
     env::Env,
     error::{SemError, SemResult},
-    inited::Inited,
+    inited::{AndInited, GeneralInited, Inited},
+    returns::statements_diverge,
+    warning::Warning,
     SemCheck,
 };
 
+use super::{array_len, check_statements, format::format_pieces, literal_bool, RawType};
+
 impl<'source> SemCheck<'source> for Spanned<ast::Expr<'source>> {
     type Checked = Spanned<Expr<'source>>;
 
@@ -17,6 +21,12 @@ impl<'source> SemCheck<'source> for Spanned<ast::Expr<'source>> {
         env: &mut Env<'source>,
         inited: &mut impl Inited,
     ) -> SemResult<'source, Self::Checked> {
+        if let Spanned(ast::Expr::Atom(ast::Atom::Literal(literal)), span) = &self {
+            if literal.ty == ast::LiteralType::String {
+                return Err(SemError::unexpected_string(*span));
+            }
+        }
+
         self.map(|expr| expr.check(env, inited)).transpose()
     }
 }
@@ -49,11 +59,179 @@ impl<'source> SemCheck<'source> for ast::Expr<'source> {
                 Ok(Expr::Swizzle { lhs, swizzle })
             }
 
+            ast::Expr::Index { lhs, index } => {
+                let lhs = Box::new(lhs.check(env, inited)?);
+                let index = Box::new(index.check(env, inited)?);
+
+                Ok(Expr::Index { lhs, index })
+            }
+
+            ast::Expr::Field { lhs, field } => {
+                let lhs = Box::new(lhs.check(env, inited)?);
+
+                Ok(Expr::Field { lhs, field })
+            }
+
+            ast::Expr::As { expr, ty } => {
+                let expr = Box::new(expr.check(env, inited)?);
+                let ty = RawType(&ty).checked(&mut env.types)?;
+
+                Ok(Expr::As { expr, ty })
+            }
+
+            ast::Expr::If {
+                cond,
+                body,
+                else_body,
+            } => {
+                if let Some(value) = literal_bool(&cond.0) {
+                    env.warn(Warning::constant_condition(value, cond.span()));
+                }
+
+                let cond = Box::new(cond.check(env, inited)?);
+
+                // a variable is inited after the expression if both branches init it
+                let mut inited = AndInited::with_parent(inited);
+
+                let body = body.check(env, &mut inited)?;
+                let else_body = else_body.check(env, &mut inited)?;
+
+                inited.exit();
+
+                Ok(Expr::If {
+                    cond,
+                    body,
+                    else_body,
+                })
+            }
+
+            ast::Expr::Block(block) => block.check(env, inited).map(Expr::Block),
+
+            ast::Expr::Struct(literal) => literal.check(env, inited),
+
+            ast::Expr::Tuple(elems) => {
+                let elems = elems
+                    .into_iter()
+                    .map(|elem| elem.check(env, inited))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Expr::Tuple(elems))
+            }
+
+            ast::Expr::Array(elems) => {
+                let elems = elems
+                    .into_iter()
+                    .map(|elem| elem.check(env, inited))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Expr::Array(elems))
+            }
+
+            ast::Expr::ArrayRepeat { value, len } => {
+                let value = Box::new(value.check(env, inited)?);
+                let len = array_len(&mut env.types, &len)?;
+
+                Ok(Expr::ArrayRepeat { value, len })
+            }
+
+            ast::Expr::Call(mut call) if call.id.0 == "len" => {
+                if call.args.len() != 1 {
+                    return Err(SemError::invalid_arguments_count(
+                        call.id,
+                        1,
+                        call.args.len(),
+                    ));
+                }
+
+                let arg = call.args.pop().unwrap().check(env, inited)?;
+
+                Ok(Expr::Len(Box::new(arg)))
+            }
+
+            ast::Expr::Call(mut call) if Intrinsic::try_from(call.id.0).is_ok() => {
+                let intrinsic = Intrinsic::try_from(call.id.0).unwrap();
+
+                if call.args.len() != 1 {
+                    return Err(SemError::invalid_arguments_count(
+                        call.id,
+                        1,
+                        call.args.len(),
+                    ));
+                }
+
+                let arg = call.args.pop().unwrap().check(env, inited)?;
+
+                Ok(Expr::Intrinsic {
+                    intrinsic,
+                    arg: Box::new(arg),
+                })
+            }
+
+            ast::Expr::Call(call) if matches!(call.id.0, "print" | "println") => {
+                let mut args = call.args.into_iter();
+
+                let Some(Spanned(
+                    ast::Expr::Atom(ast::Atom::Literal(ast::Literal {
+                        value: format,
+                        ty: ast::LiteralType::String,
+                    })),
+                    format_span,
+                )) = args.next()
+                else {
+                    return Err(SemError::expected_format_string(call.id.span()));
+                };
+
+                if env.in_const {
+                    env.error(SemError::non_const_call(call.id));
+                }
+
+                let mut pieces = format_pieces(format, format_span)?;
+
+                if call.id.0 == "println" {
+                    pieces.last_mut().unwrap().push('\n');
+                }
+
+                let args = args
+                    .map(|arg| arg.check(env, inited))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if pieces.len() != args.len() + 1 {
+                    env.error(SemError::format_args_count(
+                        pieces.len() - 1,
+                        args.len(),
+                        format_span,
+                    ));
+                }
+
+                Ok(Expr::Print { pieces, args })
+            }
+
             ast::Expr::Call(call) => {
-                let fun = env.functions.get(call.id)?;
-                let fun_data = &env.functions.symbols[fun];
+                // the arguments of an undefined function are still checked
+                let overloads = env.functions.get(call.id).unwrap_or_else(|err| {
+                    env.error(err);
+                    env.functions.add_unresolved(call.id);
+
+                    Vec::new()
+                });
+
+                env.called.extend(overloads.iter().copied());
+
+                // the overload is chosen by the typecheck, so one const overload is enough here
+                let is_const = overloads
+                    .iter()
+                    .any(|&fun| env.functions.symbols[fun].is_const);
+
+                if env.in_const && !is_const && !overloads.is_empty() {
+                    env.error(SemError::non_const_call(call.id));
+                }
 
-                check_args_count(fun_data, &call)?;
+                // the arguments of an overloaded call are checked by the typecheck
+                if let &[fun] = overloads.as_slice() {
+                    if let Err(err) = check_args_count(&env.functions.symbols[fun], &call) {
+                        env.error(err);
+                    }
+                }
 
                 let args = call
                     .args
@@ -61,15 +239,21 @@ impl<'source> SemCheck<'source> for ast::Expr<'source> {
                     .map(|arg| arg.check(env, inited))
                     .collect::<Result<_, _>>()?;
 
-                Ok(Expr::Call { fun, args })
+                Ok(Expr::Call { id: call.id, args })
             }
 
             ast::Expr::Atom(atom) => Ok(Expr::Atom(match atom {
                 ast::Atom::Id(id) => {
-                    let var = env.variables.get(id)?;
+                    let var = env.variable(id);
+
+                    env.check_static(id, var);
+                    env.read.insert(var);
 
-                    if !inited.is_inited(var) {
-                        return Err(SemError::using_uninited(id));
+                    // an uninited variable is reported once
+                    if !inited.is_inited(var) && !env.variables.is_placeholder(var) {
+                        env.error(SemError::using_uninited(id));
+
+                        inited.init(var);
                     }
 
                     Atom::Var(var)
@@ -81,6 +265,79 @@ impl<'source> SemCheck<'source> for ast::Expr<'source> {
     }
 }
 
+impl<'source> SemCheck<'source> for ast::BlockExpr<'source> {
+    type Checked = BlockExpr<'source>;
+
+    fn check(
+        self,
+        env: &mut Env<'source>,
+        inited: &mut impl Inited,
+    ) -> SemResult<'source, Self::Checked> {
+        env.variables.fork();
+
+        let mut inited = GeneralInited::with_parent(inited);
+
+        let last_span = self.statements.last().map(Spanned::span);
+        let statements = check_statements(self.statements, env, &mut inited);
+
+        let value = match self.value {
+            Some(value) => value
+                .check(env, &mut inited)
+                .map(|value| Some(Box::new(value))),
+
+            None if statements_diverge(&statements) => Ok(None),
+
+            // the parser leaves the value out only after a statement
+            None => Err(SemError::missing_block_value(last_span.unwrap())),
+        };
+
+        env.variables.exit();
+        inited.exit();
+
+        Ok(BlockExpr {
+            statements,
+            value: value?,
+        })
+    }
+}
+
+impl<'source> SemCheck<'source> for ast::StructLiteral<'source> {
+    type Checked = Expr<'source>;
+
+    fn check(
+        self,
+        env: &mut Env<'source>,
+        inited: &mut impl Inited,
+    ) -> SemResult<'source, Self::Checked> {
+        let ty = env.types.get_struct(self.id)?;
+
+        let mut fields = Vec::with_capacity(ty.fields.len());
+        fields.resize_with(ty.fields.len(), || None);
+
+        for init in self.fields {
+            let index = ty
+                .field(init.id.0)
+                .ok_or_else(|| SemError::non_existent_field(self.id, init.id))?;
+
+            if fields[index].is_some() {
+                return Err(SemError::duplicate_field(init.id));
+            }
+
+            fields[index] = Some(init.value.check(env, inited)?);
+        }
+
+        let fields = fields
+            .into_iter()
+            .zip(ty.fields.iter())
+            .map(|(value, field)| {
+                value.ok_or_else(|| SemError::missing_field(self.id, field.name.clone()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Expr::Struct { ty, fields })
+    }
+}
+
 pub fn check_args_count<'source>(data: &FunData, call: &Call<'source>) -> SemResult<'source, ()> {
     let expected = data.args_types.len();
     let received = call.args.len();
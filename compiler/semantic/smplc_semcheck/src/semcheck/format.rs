@@ -0,0 +1,45 @@
+use smplc_ast::Span;
+
+use crate::error::{SemError, SemResult};
+
+/// unescapes the quoted string and splits it by `{}` placeholders,
+/// `{{` and `}}` stand for the braces themselves
+pub fn format_pieces<'source>(quoted: &str, span: Span) -> SemResult<'source, Vec<String>> {
+    let error = |reason| Err(SemError::invalid_format_string(reason, span));
+
+    let mut pieces = vec![String::new()];
+    let mut chars = quoted[1..quoted.len() - 1].chars();
+
+    while let Some(ch) = chars.next() {
+        let piece = pieces.last_mut().unwrap();
+
+        match ch {
+            '\\' => piece.push(match chars.next() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some('0') => '\0',
+                Some('\\') => '\\',
+                Some('"') => '"',
+
+                _ => return error("unknown escape sequence"),
+            }),
+
+            '{' => match chars.next() {
+                Some('{') => piece.push('{'),
+                Some('}') => pieces.push(String::new()),
+
+                _ => return error("unclosed `{`"),
+            },
+
+            '}' => match chars.next() {
+                Some('}') => piece.push('}'),
+
+                _ => return error("unopened `}`"),
+            },
+
+            ch => piece.push(ch),
+        }
+    }
+
+    Ok(pieces)
+}
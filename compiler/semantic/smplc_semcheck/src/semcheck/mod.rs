@@ -1,18 +1,23 @@
 mod declaration;
 mod expr;
+mod format;
+mod pattern;
 mod statement;
 mod ty;
 
 use smplc_ast as ast;
-use smplc_hir::Block;
+use smplc_hir::{Block, Statement};
 
 use crate::{
     error::SemResult,
     inited::{GeneralInited, Inited},
+    warning::Warning,
     Env,
 };
 
-pub use ty::RawType;
+pub use declaration::declare_structs;
+pub(crate) use ty::MAX_ARRAY_LEN;
+pub use ty::{array_len, LenEval, RawType};
 
 pub trait SemCheck<'source>: Sized {
     type Checked;
@@ -36,12 +41,7 @@ impl<'source> SemCheck<'source> for ast::Block<'source> {
 
         let mut inited = GeneralInited::with_parent(inited);
 
-        let statements = self
-            .statements
-            .into_iter()
-            .map(|stmt| stmt.check(env, &mut inited))
-            .filter_map(Result::transpose)
-            .collect::<Result<_, _>>()?;
+        let statements = check_statements(self.statements, env, &mut inited);
 
         env.variables.exit();
         inited.exit();
@@ -49,3 +49,47 @@ impl<'source> SemCheck<'source> for ast::Block<'source> {
         Ok(Block { statements })
     }
 }
+
+/// A broken statement is reported and left out, so the following ones are checked as well.
+/// The first statement after `return`, `break` or `continue` is reported as unreachable
+pub fn check_statements<'source>(
+    statements: Vec<ast::Spanned<ast::Statement<'source>>>,
+    env: &mut Env<'source>,
+    inited: &mut impl Inited,
+) -> Vec<Statement<'source>> {
+    let mut checked = Vec::new();
+    let mut jumped = false;
+    let mut reported = false;
+
+    for ast::Spanned(stmt, span) in statements {
+        if jumped && !reported {
+            env.warn(Warning::unreachable_statement(span));
+
+            reported = true;
+        }
+
+        jumped |= matches!(
+            stmt,
+            ast::Statement::Return(_) | ast::Statement::Break(_) | ast::Statement::Continue(_)
+        );
+
+        match stmt.check(env, inited) {
+            Ok(stmt) => checked.extend(stmt),
+            Err(err) => env.error(err),
+        }
+    }
+
+    checked
+}
+
+/// the `true` or `false` literal, a condition that never changes
+pub fn literal_bool(expr: &ast::Expr) -> Option<bool> {
+    match expr {
+        ast::Expr::Atom(ast::Atom::Literal(ast::Literal {
+            value,
+            ty: ast::LiteralType::Bool,
+        })) => Some(*value == "true"),
+
+        _ => None,
+    }
+}
@@ -0,0 +1,179 @@
+use smplc_ast as ast;
+use smplc_ast::{Span, Spanned};
+use smplc_hir::{Pattern, Type};
+
+use crate::error::{SemError, SemResult};
+
+/// Checks the patterns of a match one by one:
+/// they must be literals of one type and each must match something new
+#[derive(Default)]
+pub struct Patterns {
+    ty: Option<Type>,
+    /// sorted and not overlapping
+    covered: Vec<(i32, i32)>,
+    wildcard: bool,
+}
+
+impl Patterns {
+    pub fn add<'source>(
+        &mut self,
+        pattern: Spanned<ast::Pattern<'source>>,
+    ) -> SemResult<'source, Pattern> {
+        let Spanned(pattern, span) = pattern;
+
+        if self.wildcard || self.missing().is_none() {
+            return Err(SemError::overlapping_pattern(span));
+        }
+
+        let (ty, start, end) = match pattern {
+            ast::Pattern::Wildcard => {
+                self.wildcard = true;
+
+                return Ok(Pattern::Wildcard);
+            }
+
+            ast::Pattern::Value(value) => {
+                let (ty, value) = literal(&value, span)?;
+
+                (ty, value, value)
+            }
+
+            ast::Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let (start_ty, start) = literal(&start, span)?;
+                let (end_ty, end) = literal(&end, span)?;
+
+                if start_ty != Type::Int || end_ty != Type::Int {
+                    return Err(SemError::invalid_pattern("only integers form ranges", span));
+                }
+
+                let end = if inclusive {
+                    Some(end)
+                } else {
+                    end.checked_sub(1)
+                };
+
+                match end {
+                    Some(end) if start <= end => (Type::Int, start, end),
+
+                    _ => return Err(SemError::invalid_pattern("the range is empty", span)),
+                }
+            }
+        };
+
+        if self.ty.as_ref().is_some_and(|prev| *prev != ty) {
+            return Err(SemError::invalid_pattern(
+                "the patterns have different types",
+                span,
+            ));
+        }
+
+        self.ty = Some(ty);
+
+        let overlaps = self
+            .covered
+            .iter()
+            .any(|&(covered_start, covered_end)| covered_start <= end && start <= covered_end);
+
+        if overlaps {
+            return Err(SemError::overlapping_pattern(span));
+        }
+
+        let index = self
+            .covered
+            .partition_point(|&(covered, _)| covered < start);
+
+        self.covered.insert(index, (start, end));
+
+        Ok(Pattern::Range(start, end))
+    }
+
+    /// `span` is the span of the matched value
+    pub fn check_exhaustive<'source>(&self, span: Span) -> SemResult<'source, ()> {
+        if self.wildcard {
+            return Ok(());
+        }
+
+        match self.missing() {
+            Some(missing) => Err(SemError::non_exhaustive_match(missing, span)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn ty(self) -> Option<Type> {
+        self.ty
+    }
+
+    /// the first values that aren't matched
+    fn missing(&self) -> Option<String> {
+        let (min, max) = match self.ty {
+            Some(Type::Bool) => (0, 1),
+            Some(_) => (i32::MIN, i32::MAX),
+            None => return Some("_".into()),
+        };
+
+        let mut next = Some(min);
+
+        for &(start, end) in &self.covered {
+            match next {
+                Some(value) if value < start => break,
+
+                _ => next = end.checked_add(1),
+            }
+        }
+
+        let start = next.filter(|&start| start <= max)?;
+
+        let end = self
+            .covered
+            .iter()
+            .find(|&&(covered, _)| covered > start)
+            .map_or(max, |&(covered, _)| covered - 1);
+
+        Some(match self.ty {
+            Some(Type::Bool) => (start == 1).to_string(),
+            _ if start == end => start.to_string(),
+            _ => format!("{start}..={end}"),
+        })
+    }
+}
+
+/// `5`, `-5`, `true` or `false`, bools are `0` and `1`
+fn literal<'source>(expr: &ast::Expr<'source>, span: Span) -> SemResult<'source, (Type, i32)> {
+    let invalid = || SemError::invalid_pattern("expected an integer or a bool literal", span);
+
+    let (literal, negative) = match expr {
+        ast::Expr::Atom(ast::Atom::Literal(literal)) => (literal, false),
+
+        ast::Expr::Prefix {
+            op: ast::UnOp::Neg,
+            rhs,
+        } => match &rhs.0 {
+            ast::Expr::Atom(ast::Atom::Literal(literal)) => (literal, true),
+
+            _ => return Err(invalid()),
+        },
+
+        _ => return Err(invalid()),
+    };
+
+    match (literal.ty, negative) {
+        (ast::LiteralType::Bool, false) => Ok((Type::Bool, (literal.value == "true") as i32)),
+
+        (ast::LiteralType::Int, _) => {
+            let value: i64 = parse_int::parse(literal.value).map_err(|_| invalid())?;
+            let value = if negative { -value } else { value };
+
+            let value = i32::try_from(value).map_err(|_| {
+                SemError::invalid_pattern("the integer doesn't fit in 32 bits", span)
+            })?;
+
+            Ok((Type::Int, value))
+        }
+
+        _ => Err(invalid()),
+    }
+}
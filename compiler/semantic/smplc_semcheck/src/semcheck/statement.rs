@@ -3,11 +3,12 @@ use smplc_hir::*;
 
 use crate::{
     env::Env,
-    error::SemResult,
+    error::{SemError, SemResult},
     inited::{AndInited, GeneralInited, Inited, NothingInited},
+    warning::Warning,
 };
 
-use super::{RawType, SemCheck};
+use super::{literal_bool, pattern::Patterns, RawType, SemCheck};
 
 impl<'source> SemCheck<'source> for ast::Statement<'source> {
     type Checked = Option<Statement<'source>>;
@@ -19,17 +20,35 @@ impl<'source> SemCheck<'source> for ast::Statement<'source> {
     ) -> SemResult<'source, Self::Checked> {
         match self {
             ast::Statement::Declare(declare_stmt) => {
-                return Ok(declare_stmt.check(env, inited)?.map(Statement::Expr));
+                Ok(declare_stmt.check(env, inited)?.map(Statement::Expr))
             }
 
-            ast::Statement::If(if_stmt) => {
-                return Ok(Some(Statement::If(if_stmt.check(env, inited)?)));
+            ast::Statement::Destructure(destructure_stmt) => {
+                Ok(Some(Statement::Expr(destructure_stmt.check(env, inited)?)))
             }
 
+            ast::Statement::If(if_stmt) => Ok(Some(Statement::If(if_stmt.check(env, inited)?))),
+
             ast::Statement::While(while_stmt) => {
                 Ok(Some(Statement::While(while_stmt.check(env, inited)?)))
             }
 
+            ast::Statement::DoWhile(do_while_stmt) => {
+                Ok(Some(Statement::DoWhile(do_while_stmt.check(env, inited)?)))
+            }
+
+            ast::Statement::Loop(loop_stmt) => {
+                Ok(Some(Statement::Loop(loop_stmt.check(env, inited)?)))
+            }
+
+            ast::Statement::For(for_stmt) => Ok(Some(Statement::For(Box::new(
+                (*for_stmt).check(env, inited)?,
+            )))),
+
+            ast::Statement::Match(match_stmt) => {
+                Ok(Some(Statement::Match(match_stmt.check(env, inited)?)))
+            }
+
             ast::Statement::Expr(expr_stmt) => {
                 Ok(Some(Statement::Expr(expr_stmt.check(env, inited)?)))
             }
@@ -38,8 +57,17 @@ impl<'source> SemCheck<'source> for ast::Statement<'source> {
                 Ok(Some(Statement::Return(return_stmt.check(env, inited)?)))
             }
 
-            ast::Statement::Break => Ok(Some(Statement::Break)),
-            ast::Statement::Continue => Ok(Some(Statement::Continue)),
+            ast::Statement::Break(label) => {
+                env.loops.break_loop(label, inited.inited_vars());
+
+                Ok(Some(Statement::Break(label)))
+            }
+
+            ast::Statement::Continue(label) => {
+                env.loops.continue_loop(label);
+
+                Ok(Some(Statement::Continue(label)))
+            }
         }
     }
 }
@@ -52,21 +80,66 @@ impl<'source> SemCheck<'source> for ast::DeclareStatement<'source> {
         env: &mut Env<'source>,
         inited: &mut impl Inited,
     ) -> SemResult<'source, Self::Checked> {
-        let ty = self.ty.map(RawType).map(RawType::checked).transpose()?;
-        let var = env.variables.add_variable(self.id, ty)?;
+        // the variable is declared even if its type or value is broken,
+        // so its uses aren't reported
+        let ty = self.ty.as_ref().and_then(|ty| {
+            RawType(ty)
+                .checked(&mut env.types)
+                .map_err(|err| env.error(err))
+                .ok()
+        });
+        let broken_ty = self.ty.is_some() && ty.is_none();
+        let var = env.declare(self.id, ty);
+
+        if broken_ty {
+            env.poison(var);
+        }
 
         if let Some(value) = self.value {
-            let rhs = value.check(env, inited)?;
+            let rhs = value.check(env, inited);
 
             inited.init(var);
 
-            Ok(Some(ExprStatement::Assign { var, rhs }))
+            // the statement is dropped, so the typecheck never sees the declaration
+            if rhs.is_err() {
+                env.poison(var);
+            }
+
+            Ok(Some(ExprStatement::Assign { var, rhs: rhs? }))
         } else {
             Ok(None)
         }
     }
 }
 
+impl<'source> SemCheck<'source> for ast::DestructureStatement<'source> {
+    type Checked = ExprStatement<'source>;
+
+    fn check(
+        self,
+        env: &mut Env<'source>,
+        inited: &mut impl Inited,
+    ) -> SemResult<'source, Self::Checked> {
+        let rhs = self.value.check(env, inited);
+
+        let vars = self
+            .ids
+            .into_iter()
+            .map(|id| env.declare(id, None))
+            .collect::<Vec<_>>();
+
+        for &var in &vars {
+            inited.init(var);
+
+            if rhs.is_err() {
+                env.poison(var);
+            }
+        }
+
+        Ok(ExprStatement::Destructure { vars, rhs: rhs? })
+    }
+}
+
 impl<'source> SemCheck<'source> for ast::ExprStatement<'source> {
     type Checked = ExprStatement<'source>;
 
@@ -77,19 +150,95 @@ impl<'source> SemCheck<'source> for ast::ExprStatement<'source> {
     ) -> SemResult<'source, Self::Checked> {
         match self {
             ast::ExprStatement::Expr(expr) => {
-                return Ok(ExprStatement::Expr(expr.check(env, inited)?));
+                // the result of an overloaded call is unused if all the overloads return something
+                if let ast::Expr::Call(call) = &expr.0 {
+                    let overloads = env.functions.get(call.id).unwrap_or_default();
+
+                    let returns = overloads
+                        .iter()
+                        .all(|&fun| env.functions.symbols[fun].ret_ty.is_some());
+
+                    if !overloads.is_empty() && returns {
+                        env.warn(Warning::unused_result(call.id, expr.span()));
+                    }
+                }
+
+                Ok(ExprStatement::Expr(expr.check(env, inited)?))
             }
 
-            ast::ExprStatement::Assign { id, rhs } => {
-                let var = env.variables.get(id)?;
+            ast::ExprStatement::Assign {
+                place,
+                op: None,
+                rhs,
+            } if place.projections.is_empty() && place.swizzle.is_none() => {
+                let var = env.variable(place.id);
+
+                env.check_static(place.id, var);
+                env.assigned.insert(var);
+
                 let rhs = rhs.check(env, inited)?;
 
                 inited.init(var);
 
                 Ok(ExprStatement::Assign { var, rhs })
             }
+
+            ast::ExprStatement::Assign { place, op, rhs } => {
+                let var = env.variable(place.id);
+
+                env.check_static(place.id, var);
+                // a compound or partial assignment keeps the rest of the old value,
+                // so it reads the variable
+                env.read.insert(var);
+
+                if !inited.is_inited(var) && !env.variables.is_placeholder(var) {
+                    env.error(SemError::using_uninited(place.id));
+
+                    inited.init(var);
+                }
+
+                let projections = place
+                    .projections
+                    .into_iter()
+                    .map(|projection| match projection {
+                        ast::Projection::Index(index) => {
+                            index.check(env, inited).map(Projection::Index)
+                        }
+
+                        ast::Projection::Field(field) => Ok(Projection::Field(field)),
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                let swizzle = place.swizzle.map(check_swizzle).transpose()?;
+
+                let rhs = rhs.check(env, inited)?;
+
+                Ok(ExprStatement::Store {
+                    place: Place {
+                        var: ast::Spanned(var, place.id.span()),
+                        projections,
+                        swizzle,
+                    },
+                    op,
+                    rhs,
+                })
+            }
+        }
+    }
+}
+
+/// a component can't be assigned twice
+fn check_swizzle<'source>(swizzle: ast::Spanned<ast::Swizzle>) -> SemResult<'source, ast::Swizzle> {
+    let ast::Spanned(swizzle, span) = swizzle;
+    let components = swizzle.as_slice();
+
+    for (n, component) in components.iter().enumerate() {
+        if components[..n].contains(component) {
+            return Err(SemError::duplicate_swizzle_component(*component, span));
         }
     }
+
+    Ok(swizzle)
 }
 
 impl<'source> SemCheck<'source> for ast::IfStatement<'source> {
@@ -100,7 +249,12 @@ impl<'source> SemCheck<'source> for ast::IfStatement<'source> {
         env: &mut Env<'source>,
         inited: &mut impl Inited,
     ) -> SemResult<'source, Self::Checked> {
-        let cond = self.cond.check(env, inited)?;
+        if let Some(value) = literal_bool(&self.cond.0) {
+            env.warn(Warning::constant_condition(value, self.cond.span()));
+        }
+
+        // the body is checked even if the condition is broken
+        let cond = self.cond.check(env, inited);
 
         let mut inited = AndInited::with_parent(inited);
 
@@ -119,7 +273,7 @@ impl<'source> SemCheck<'source> for ast::IfStatement<'source> {
         inited.exit();
 
         Ok(IfStatement {
-            cond,
+            cond: cond?,
             body,
             else_body,
         })
@@ -139,6 +293,7 @@ impl<'source> SemCheck<'source> for ast::ReturnStatement<'source> {
         Ok(ReturnStatement {
             fun: env.current_fn.unwrap(),
             value,
+            span: self.span,
         })
     }
 }
@@ -151,12 +306,188 @@ impl<'source> SemCheck<'source> for ast::WhileStatement<'source> {
         env: &mut Env<'source>,
         inited: &mut impl Inited,
     ) -> SemResult<'source, Self::Checked> {
-        let cond = self.cond.check(env, inited)?;
+        // `while true` is an endless loop, so only `while false` is reported
+        if literal_bool(&self.cond.0) == Some(false) {
+            env.warn(Warning::constant_condition(false, self.cond.span()));
+        }
+
+        let cond = self.cond.check(env, inited);
+
+        env.loops.join(self.label);
 
         let body = self
             .body
-            .check(env, &mut NothingInited::with_parent(inited))?;
+            .check(env, &mut NothingInited::with_parent(inited));
+
+        env.loops.exit();
+
+        Ok(WhileStatement {
+            label: self.label,
+            cond: cond?,
+            body: body?,
+        })
+    }
+}
 
-        Ok(WhileStatement { cond, body })
+impl<'source> SemCheck<'source> for ast::DoWhileStatement<'source> {
+    type Checked = DoWhileStatement<'source>;
+
+    fn check(
+        self,
+        env: &mut Env<'source>,
+        inited: &mut impl Inited,
+    ) -> SemResult<'source, Self::Checked> {
+        env.loops.join(self.label);
+
+        let mut body_inited = GeneralInited::with_parent(inited);
+
+        let body = self.body.check(env, &mut body_inited);
+
+        let jumps = env.loops.exit();
+        let body = body?;
+
+        // the whole body is run at least once unless it's left
+        // by `break` or `continue`, so the condition sees its variables
+        let cond = if jumps.broken || jumps.continued {
+            drop(body_inited);
+
+            self.cond.check(env, inited)?
+        } else {
+            let cond = self.cond.check(env, &mut body_inited)?;
+
+            body_inited.exit();
+
+            cond
+        };
+
+        Ok(DoWhileStatement {
+            label: self.label,
+            body,
+            cond,
+        })
+    }
+}
+
+impl<'source> SemCheck<'source> for ast::LoopStatement<'source> {
+    type Checked = LoopStatement<'source>;
+
+    fn check(
+        self,
+        env: &mut Env<'source>,
+        inited: &mut impl Inited,
+    ) -> SemResult<'source, Self::Checked> {
+        env.loops.join(self.label);
+
+        let body = self
+            .body
+            .check(env, &mut NothingInited::with_parent(inited));
+
+        let jumps = env.loops.exit();
+
+        // a loop without `break` never ends, otherwise its body is run
+        // until a `break`, so the variables inited at every `break` are inited after it
+        if jumps.broken {
+            inited.add(jumps.inited_at_break);
+        } else {
+            inited.diverge();
+        }
+
+        Ok(LoopStatement {
+            label: self.label,
+            body: body?,
+        })
+    }
+}
+
+impl<'source> SemCheck<'source> for ast::ForStatement<'source> {
+    type Checked = ForStatement<'source>;
+
+    fn check(
+        self,
+        env: &mut Env<'source>,
+        inited: &mut impl Inited,
+    ) -> SemResult<'source, Self::Checked> {
+        let ast::Range {
+            start,
+            end,
+            inclusive,
+            step,
+        } = self.range;
+
+        let start = start.check(env, inited)?;
+        let end = end.check(env, inited)?;
+        let step = step.map(|step| step.check(env, inited)).transpose()?;
+
+        let mut inited = NothingInited::with_parent(inited);
+
+        // the loop variable is visible only inside the body
+        env.variables.fork();
+
+        let var = env.declare(self.var, None);
+
+        inited.init(var);
+
+        env.loops.join(self.label);
+
+        let body = self.body.check(env, &mut inited);
+
+        env.loops.exit();
+        env.variables.exit();
+
+        Ok(ForStatement {
+            label: self.label,
+            var,
+            start,
+            end,
+            inclusive,
+            step,
+            body: body?,
+        })
+    }
+}
+
+impl<'source> SemCheck<'source> for ast::MatchStatement<'source> {
+    type Checked = MatchStatement<'source>;
+
+    fn check(
+        self,
+        env: &mut Env<'source>,
+        inited: &mut impl Inited,
+    ) -> SemResult<'source, Self::Checked> {
+        let value = self.value.check(env, inited)?;
+
+        let mut patterns = Patterns::default();
+
+        // exactly one arm is run like in if statements
+        let mut inited = AndInited::with_parent(inited);
+
+        let arms = self
+            .arms
+            .into_iter()
+            .map(|arm| {
+                let arm_patterns = arm
+                    .patterns
+                    .into_iter()
+                    .map(|pattern| patterns.add(pattern))
+                    .collect::<Result<_, _>>()?;
+
+                let body = arm.body.check(env, &mut inited)?;
+
+                Ok(MatchArm {
+                    patterns: arm_patterns,
+                    body,
+                })
+            })
+            .collect::<SemResult<_>>()?;
+
+        patterns.check_exhaustive(value.span())?;
+
+        inited.exit();
+
+        Ok(MatchStatement {
+            value,
+            ty: patterns.ty(),
+            arms,
+        })
     }
 }
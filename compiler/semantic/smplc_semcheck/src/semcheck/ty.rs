@@ -1,12 +1,118 @@
-use smplc_ast::Id;
+use std::collections::HashSet;
+
+use smplc_ast as ast;
+use smplc_ast::Spanned;
 use smplc_hir::Type;
 
+use crate::env::Types;
 use crate::error::{SemError, SemResult};
 
-pub struct RawType<'source>(pub Id<'source>);
+pub struct RawType<'a, 'source>(pub &'a Spanned<ast::Type<'source>>);
+
+impl<'source> RawType<'_, 'source> {
+    pub fn checked(self, types: &mut Types<'source>) -> SemResult<'source, Type> {
+        let &Spanned(ref ty, span) = self.0;
+
+        match ty {
+            &ast::Type::Named(name) => types.get(Spanned(name, span)),
+
+            ast::Type::Array { elem, len } => {
+                let elem = RawType(elem).checked(types)?;
+                let len = array_len(types, len)?;
+
+                Ok(Type::array(elem, len))
+            }
+
+            ast::Type::Tuple(elems) => elems
+                .iter()
+                .map(|elem| RawType(elem).checked(types))
+                .collect::<Result<_, _>>()
+                .map(Type::Tuple),
+        }
+    }
+}
+
+/// an array is copied slot by slot, so a longer one makes the code too big
+pub(crate) const MAX_ARRAY_LEN: usize = 1 << 16;
+
+/// Evaluates a constant expression of an array length at compile time,
+/// `None` if it isn't a constant `int` or its evaluation fails
+pub type LenEval<'source> = dyn Fn(&Spanned<ast::Expr<'source>>) -> Option<i64> + 'source;
+
+/// a length that isn't a literal is evaluated by `types.eval_len`,
+/// so it can use constants and call `const fn`s
+pub fn array_len<'source>(
+    types: &mut Types<'source>,
+    len: &Spanned<ast::Expr<'source>>,
+) -> SemResult<'source, usize> {
+    let value = match (&len.0, &types.eval_len) {
+        (
+            ast::Expr::Atom(ast::Atom::Literal(ast::Literal {
+                value,
+                ty: ast::LiteralType::Int,
+            })),
+            _,
+        ) => parse_int::parse(value).map_err(|_| SemError::invalid_array_len(len.1))?,
+
+        (_, Some(eval_len)) => {
+            let value = eval_len(len)
+                .and_then(|value| usize::try_from(value).ok())
+                .ok_or_else(|| SemError::invalid_array_len(len.1))?;
+
+            used_names(&len.0, &mut types.len_names);
+
+            value
+        }
+
+        (_, None) => return Err(SemError::invalid_array_len(len.1)),
+    };
+
+    if value > MAX_ARRAY_LEN {
+        return Err(SemError::array_too_long(value, len.1));
+    }
+
+    Ok(value)
+}
+
+/// the constants and the functions used by a length aren't dead code,
+/// the blocks of a length are skipped
+fn used_names<'source>(expr: &ast::Expr<'source>, names: &mut HashSet<&'source str>) {
+    match expr {
+        ast::Expr::Atom(ast::Atom::Id(id)) => {
+            names.insert(id.0);
+        }
+
+        ast::Expr::Call(call) => {
+            names.insert(call.id.0);
+
+            for arg in &call.args {
+                used_names(&arg.0, names);
+            }
+        }
+
+        ast::Expr::Prefix { rhs: expr, .. }
+        | ast::Expr::Swizzle { lhs: expr, .. }
+        | ast::Expr::Field { lhs: expr, .. }
+        | ast::Expr::As { expr, .. } => used_names(&expr.0, names),
+
+        ast::Expr::Infix { lhs, rhs, .. }
+        | ast::Expr::Index { lhs, index: rhs }
+        | ast::Expr::ArrayRepeat {
+            value: lhs,
+            len: rhs,
+        } => {
+            used_names(&lhs.0, names);
+            used_names(&rhs.0, names);
+        }
+
+        ast::Expr::Tuple(elems) | ast::Expr::Array(elems) => {
+            for elem in elems {
+                used_names(&elem.0, names);
+            }
+        }
+
+        ast::Expr::If { cond, .. } => used_names(&cond.0, names),
 
-impl<'source> RawType<'source> {
-    pub fn checked(self) -> SemResult<'source, Type> {
-        Type::try_from(self.0 .0).map_err(|_| SemError::unknown_type(self.0))
+        ast::Expr::Block(_) | ast::Expr::Struct(_) | ast::Expr::Atom(_) => {}
     }
 }
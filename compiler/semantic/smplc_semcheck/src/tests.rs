@@ -1,18 +1,19 @@
-use smplc_ast::Span;
+use smplc_ast::{self as ast, Component, Span, Spanned};
 use smplc_hir::Pos;
 use smplc_lexer::Lexer;
 use smplc_parse::{parse, TokenStream};
 
 use crate::error::SemErrorKind;
-use crate::sem_check;
+use crate::warning::WarningKind;
+use crate::{sem_check, sem_check_with_lengths};
 
 macro_rules! semtest {
     ($code:literal => $error:expr) => {
-        let token_stream = TokenStream::new(Lexer::new($code)).unwrap();
+        let token_stream = TokenStream::new(Lexer::new($code));
         let ast = parse(token_stream).unwrap();
 
-        match sem_check(ast) {
-            Err(error) => assert_eq!(error.kind, $error),
+        match sem_check(ast).1.first() {
+            Some(error) => assert_eq!(error.kind, $error),
             _ => panic!("kaput"),
         }
     };
@@ -52,6 +53,41 @@ pub fn redeclaring_function() {
     ];
 }
 
+#[test]
+pub fn overloading() {
+    semtest![
+        "fn a(b: real) {} fn a(b: int) {} fn a(c: real) -> int { return 0; }" => SemErrorKind::RedeclaringFunction {
+            id: "a",
+            first_declaration: Span::with_len(Pos::new(1, 4, 3), 1)
+        }
+    ];
+
+    semtest![
+        "fn main() {} fn main(a: int) {}" => SemErrorKind::RedeclaringFunction {
+            id: "main",
+            first_declaration: Span::with_len(Pos::new(1, 4, 3), 4)
+        }
+    ];
+}
+
+#[test]
+pub fn std_math() {
+    semtest![
+        "fn clamp(x: int, lo: int, hi: int) -> int { return x; }" => SemErrorKind::RedeclaringFunction {
+            id: "clamp",
+            first_declaration: Span::default()
+        }
+    ];
+
+    semtest![
+        "fn main() { sqrt(1.0, 2.0); }" => SemErrorKind::InvalidArgumentsCount {
+            expected: 1,
+            received: 2,
+            fun_id: "sqrt"
+        }
+    ];
+}
+
 #[test]
 pub fn invalid_arguments() {
     semtest![
@@ -80,3 +116,523 @@ pub fn duplicate_args_names() {
         "fn a(b: real, b: real) {}" => SemErrorKind::DuplicateArgsNames("b")
     ];
 }
+
+#[test]
+pub fn for_variable_scope() {
+    semtest![
+        "fn main() { for i in 0..10 {} i; }" => SemErrorKind::NonExistentVariable("i")
+    ];
+}
+
+#[test]
+pub fn loops_initialization() {
+    // `a` is inited after the `if`, because the `else` branch never ends
+    semtest![
+        "fn main() { let a: int; if true { a = 1; } else { loop {} } a; b; }" => SemErrorKind::NonExistentVariable("b")
+    ];
+
+    semtest![
+        "fn main() { let a: int; loop { break; } a; }" => SemErrorKind::UsingUninitedVar("a")
+    ];
+
+    // the body of `loop` is run until a `break`, so `a` is inited at every one of them
+    semtest![
+        "fn main() { let a: int; loop { a = 1; break; } a; b; }" => SemErrorKind::NonExistentVariable("b")
+    ];
+
+    semtest![
+        "fn main() { let a: int; 'outer: loop { if true { a = 1; break; } loop { a = 2; break 'outer; } } a; b; }" => SemErrorKind::NonExistentVariable("b")
+    ];
+
+    semtest![
+        "fn main() { let a: int; loop { if true { break; } a = 1; } a; }" => SemErrorKind::UsingUninitedVar("a")
+    ];
+
+    semtest![
+        "fn main() { let a: int; do { a = 1; } while a > 0; a; b; }" => SemErrorKind::NonExistentVariable("b")
+    ];
+
+    semtest![
+        "fn main() { let a: int; do { if true { continue; } a = 1; } while a > 0; }" => SemErrorKind::UsingUninitedVar("a")
+    ];
+}
+
+#[test]
+pub fn statics() {
+    // a static is always inited, so it can be updated by any function
+    semtest![
+        "static a: int = 0; fn main() { a = a + 1; b; }" => SemErrorKind::NonExistentVariable("b")
+    ];
+
+    semtest![
+        "const a: int = 0; static a: int = 1;" => SemErrorKind::RedeclaringVariable {
+            id: "a",
+            first_declaration: Span::with_len(Pos::new(1, 7, 6), 1)
+        }
+    ];
+}
+
+#[test]
+pub fn const_fn() {
+    semtest![
+        "fn f() -> int { return 1; } const A: int = f();" => SemErrorKind::NonConstCall("f")
+    ];
+
+    semtest![
+        "const fn f() -> int { println(\"{}\", 1); return 1; }" => SemErrorKind::NonConstCall("println")
+    ];
+
+    // stdin is read only at runtime
+    semtest![
+        "const N: int = readi();" => SemErrorKind::NonConstCall("readi")
+    ];
+
+    semtest![
+        "static s: int = 0; const fn f() -> int { return s; }" => SemErrorKind::StaticInConst("s")
+    ];
+
+    semtest![
+        "const fn f(x: real) -> real { return sqrt(x); } const A: real = f(2.0); fn main() { f(A); b; }" => SemErrorKind::NonExistentVariable("b")
+    ];
+}
+
+#[test]
+pub fn match_patterns() {
+    semtest![
+        "fn main() { let x = 1; match x { 0..=9 => {} 5 => {} _ => {} } }" => SemErrorKind::OverlappingPattern
+    ];
+
+    semtest![
+        "fn main() { let x = 1; match x { _ => {} 0 => {} } }" => SemErrorKind::OverlappingPattern
+    ];
+
+    semtest![
+        "fn main() { let x = 1; match x { 0 | true => {} _ => {} } }" => SemErrorKind::InvalidPattern("the patterns have different types")
+    ];
+
+    semtest![
+        "fn main() { let x = 1; match x { 5..5 => {} _ => {} } }" => SemErrorKind::InvalidPattern("the range is empty")
+    ];
+
+    semtest![
+        "fn main() { let x = 1; match x { x => {} } }" => SemErrorKind::InvalidPattern("expected an integer or a bool literal")
+    ];
+}
+
+#[test]
+pub fn non_exhaustive_match() {
+    semtest![
+        "fn main() { let x = 1; match x { 0 => {} 2..=9 => {} _ => {} } y; }" => SemErrorKind::NonExistentVariable("y")
+    ];
+
+    semtest![
+        "fn main() { let x = 1; match x { 0 => {} 2..=9 => {} } }" => SemErrorKind::NonExhaustiveMatch {
+            missing: "-2147483648..=-1".into()
+        }
+    ];
+
+    semtest![
+        "fn main() { let x = 1; match x { -2147483648..=0 => {} 2..=9 => {} } }" => SemErrorKind::NonExhaustiveMatch {
+            missing: "1".into()
+        }
+    ];
+
+    semtest![
+        "fn main() { let b = true; match b { false => {} } }" => SemErrorKind::NonExhaustiveMatch {
+            missing: "true".into()
+        }
+    ];
+
+    // `a` is inited in every arm
+    semtest![
+        "fn main() { let a: int; match true { true => { a = 1; } false => { a = 2; } } a; y; }" => SemErrorKind::NonExistentVariable("y")
+    ];
+}
+
+#[test]
+pub fn if_expressions() {
+    semtest![
+        "fn main() { let m: int; let a = if true { m = 1; 2 } else { 3 }; m; }" => SemErrorKind::UsingUninitedVar("m")
+    ];
+
+    semtest![
+        "fn main() { let a = { let t = 1; t }; t; }" => SemErrorKind::NonExistentVariable("t")
+    ];
+
+    // only a diverging block can leave out the value
+    semtest![
+        "fn main() { let a = if true { printi(1); } else { 2 }; }" => SemErrorKind::MissingBlockValue
+    ];
+}
+
+#[test]
+pub fn destructuring() {
+    semtest![
+        "fn main() { let (a, a) = (1, 2); }" => SemErrorKind::RedeclaringVariable {
+            id: "a",
+            first_declaration: Span::with_len(Pos::new(1, 18, 17), 1)
+        }
+    ];
+
+    semtest![
+        "fn main() { let (a, b) = (b, 1); }" => SemErrorKind::NonExistentVariable("b")
+    ];
+}
+
+#[test]
+pub fn invalid_array_len() {
+    semtest![
+        "fn main() { let n = 3; let a: [int; n]; }" => SemErrorKind::InvalidArrayLen
+    ];
+
+    semtest![
+        "fn main() { let a = [0; 1.5]; }" => SemErrorKind::InvalidArrayLen
+    ];
+
+    // without an evaluator only the literals are allowed
+    semtest![
+        "const N: int = 3; fn main() { let a: [int; N]; }" => SemErrorKind::InvalidArrayLen
+    ];
+
+    semtest![
+        "fn main() { let a = [0; 100000000]; }" => SemErrorKind::ArrayTooLong(100000000)
+    ];
+
+    semtest![
+        "fn main() { let a: [int; 65537]; }" => SemErrorKind::ArrayTooLong(65537)
+    ];
+}
+
+#[test]
+pub fn storing_into_uninited_array() {
+    semtest![
+        "fn main() { let a: [int; 2]; a[0] = 1; }" => SemErrorKind::UsingUninitedVar("a")
+    ];
+}
+
+#[test]
+pub fn redeclaring_type() {
+    semtest![
+        "struct A { x: int } struct A { y: int }" => SemErrorKind::RedeclaringType {
+            id: "A",
+            first_declaration: Span::with_len(Pos::new(1, 8, 7), 1)
+        }
+    ];
+}
+
+#[test]
+pub fn recursive_struct() {
+    semtest![
+        "struct A { b: B } struct B { a: [A; 2] }" => SemErrorKind::RecursiveStruct("A")
+    ];
+}
+
+#[test]
+pub fn builtin_type_name() {
+    semtest![
+        "struct int { x: int }" => SemErrorKind::BuiltinTypeName("int")
+    ];
+
+    semtest![
+        "struct real { x: int } fn main() {}" => SemErrorKind::BuiltinTypeName("real")
+    ];
+}
+
+#[test]
+pub fn struct_literal_fields() {
+    semtest![
+        "struct A { x: int, x: real }" => SemErrorKind::DuplicateField("x")
+    ];
+
+    semtest![
+        "struct A { x: int }
+         fn main() { let a = A { x: 1, y: 2 }; }" => SemErrorKind::NonExistentField {
+            ty: "A",
+            field: "y"
+        }
+    ];
+
+    semtest![
+        "struct A { x: int, y: int }
+         fn main() { let a = A { y: 2 }; }" => SemErrorKind::MissingField {
+            ty: "A",
+            field: String::from("x")
+        }
+    ];
+}
+
+#[test]
+pub fn intrinsic_arguments() {
+    semtest![
+        "fn main() { transpose(); }" => SemErrorKind::InvalidArgumentsCount {
+            expected: 1,
+            received: 0,
+            fun_id: "transpose"
+        }
+    ];
+}
+
+#[test]
+pub fn swizzle_assignment() {
+    semtest![
+        "fn main() { let v = vec3(0.0, 0.0, 0.0); v:xzx = v; }" => SemErrorKind::DuplicateSwizzleComponent(Component::X)
+    ];
+
+    semtest![
+        "fn main() { let v: vec3; v:x += 1.0; }" => SemErrorKind::UsingUninitedVar("v")
+    ];
+}
+
+#[test]
+pub fn format_strings() {
+    semtest![
+        r#"fn main() { print("{} and {}", 1); }"# => SemErrorKind::FormatArgsCount {
+            placeholders: 2,
+            received: 1
+        }
+    ];
+
+    semtest![
+        r#"fn main() { println("{", 1); }"# => SemErrorKind::InvalidFormatString("unclosed `{`")
+    ];
+
+    semtest![
+        r#"fn main() { let a = "text"; }"# => SemErrorKind::UnexpectedString
+    ];
+
+    semtest![
+        "fn main() { print(1); }" => SemErrorKind::ExpectedFormatString
+    ];
+}
+
+#[test]
+pub fn collects_errors() {
+    let code = "\
+fn f(x: Foo) -> int { return x; }
+fn main() {
+    let a = b + b;
+    let c: int;
+    c += a;
+    f(1);
+    g(c);
+    g(c);
+}
+";
+
+    let ast = parse(TokenStream::new(Lexer::new(code))).unwrap();
+
+    let (hir, errors, _) = sem_check(ast);
+
+    let errors = errors
+        .into_iter()
+        .map(|error| error.kind)
+        .collect::<Vec<_>>();
+
+    // the placeholders of `b`, `Foo` and `g` prevent the repeated errors
+    assert_eq!(
+        errors,
+        [
+            SemErrorKind::UnknownType("Foo"),
+            SemErrorKind::NonExistentVariable("b"),
+            SemErrorKind::UsingUninitedVar("c"),
+            SemErrorKind::NonExistentFunction("g"),
+        ]
+    );
+
+    // `f` is left out
+    assert_eq!(hir.functions.len(), 1);
+}
+
+#[test]
+pub fn warnings() {
+    let code = "\
+const UNUSED: int = 1;
+const USED: int = 2;
+
+fn f(x: int) -> int { return x; }
+fn g() {}
+
+#[allow(dead_code, unused_variables)]
+fn h(y: int) {}
+
+fn main() {
+    let a = USED;
+    let b: int;
+    b = 1;
+    let _c = 3;
+    f(1);
+    if true { return; }
+    while false {}
+    while true { break; printi(2); }
+}
+";
+
+    let ast = parse(TokenStream::new(Lexer::new(code))).unwrap();
+
+    let (_, errors, warnings) = sem_check(ast);
+
+    assert!(errors.is_empty());
+
+    let warnings = warnings
+        .into_iter()
+        .map(|warning| warning.kind)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        warnings,
+        [
+            WarningKind::UnusedConstant("UNUSED"),
+            WarningKind::UnusedFunction("g"),
+            WarningKind::UnusedVariable("a"),
+            WarningKind::UnusedAssignment("b"),
+            WarningKind::UnusedResult("f"),
+            WarningKind::ConstantCondition(true),
+            WarningKind::ConstantCondition(false),
+            WarningKind::UnreachableStatement,
+        ]
+    );
+}
+
+#[test]
+pub fn partial_assignments() {
+    let code = "\
+fn main() {
+    let a = [1, 2, 3];
+    a[0] += 3;
+
+    let b = 1;
+    b += 2;
+
+    let v = vec2(1.0, 2.0);
+    for _i in 0..3 {
+        v:x = 5.0;
+    }
+    println(\"{}\", v);
+
+    let c = 1;
+    c = 2;
+}
+";
+
+    let ast = parse(TokenStream::new(Lexer::new(code))).unwrap();
+
+    let (_, errors, warnings) = sem_check(ast);
+
+    assert!(errors.is_empty());
+
+    let warnings = warnings
+        .into_iter()
+        .map(|warning| warning.kind)
+        .collect::<Vec<_>>();
+
+    assert_eq!(warnings, [WarningKind::UnusedAssignment("c")]);
+}
+
+#[test]
+pub fn evaluated_array_lens() {
+    let code = "\
+const N: int = 3;
+const UNUSED: int = 4;
+const fn twice(n: int) -> int { return n * 2; }
+fn f(_a: [int; twice(N)]) {}
+fn main() { let a = [0; N]; let _b: [int; -1]; f([0; 6]); printi(a[0]); }
+";
+
+    let ast = parse(TokenStream::new(Lexer::new(code))).unwrap();
+
+    // the evaluation is up to the caller, this one knows only two lengths
+    let eval_len = Box::new(|len: &Spanned<ast::Expr>| match &len.0 {
+        ast::Expr::Atom(ast::Atom::Id(id)) if id.0 == "N" => Some(3),
+        ast::Expr::Call(call) if call.id.0 == "twice" => Some(6),
+        _ => Some(-1),
+    });
+
+    let (_, errors, warnings) = sem_check_with_lengths(ast, eval_len);
+
+    let errors = errors
+        .into_iter()
+        .map(|error| error.kind)
+        .collect::<Vec<_>>();
+
+    assert_eq!(errors, [SemErrorKind::InvalidArrayLen]);
+
+    // the names used by the lengths aren't dead code
+    let warnings = warnings
+        .into_iter()
+        .map(|warning| warning.kind)
+        .collect::<Vec<_>>();
+
+    assert_eq!(warnings, [WarningKind::UnusedConstant("UNUSED")]);
+}
+
+#[test]
+pub fn unknown_lint() {
+    semtest![
+        "#[allow(unused)] fn main() {}" => SemErrorKind::UnknownLint("unused")
+    ];
+
+    semtest![
+        "#[inline] fn main() {}" => SemErrorKind::UnknownAttribute("inline")
+    ];
+}
+
+#[test]
+pub fn missing_return() {
+    semtest![
+        "fn f(x: bool) -> int { if x { return 1; } } fn main() {}" => SemErrorKind::MissingReturn
+    ];
+
+    semtest![
+        "fn f(x: bool) -> int { while true { if x { break; } } } fn main() {}" => SemErrorKind::MissingReturn
+    ];
+
+    semtest![
+        "fn f() -> int { 'a: loop { loop { break 'a; } } } fn main() {}" => SemErrorKind::MissingReturn
+    ];
+
+    let code = "\
+fn a(x: bool) -> int { if x { return 1; } else { return 2; } }
+fn b() -> int { while true {} }
+fn c(x: bool) -> int { loop { while x { break; } } }
+fn d(x: int) -> int { match x { 0 => { return 1; } _ => { return 2; } } }
+fn e(x: bool) -> int { do { return 1; } while x; }
+fn main() {}
+";
+
+    let ast = parse(TokenStream::new(Lexer::new(code))).unwrap();
+
+    assert!(sem_check(ast).1.is_empty());
+}
+
+#[test]
+pub fn struct_errors() {
+    let code = "\
+struct A { b: B }
+struct B { a: A }
+struct int { x: real }
+struct C { x: int, x: real }
+struct D { p: Q, t: (int, R) }
+struct E { x: int }
+fn main() { let e = E { x: 1 }; printi(e.x); }
+";
+
+    let ast = parse(TokenStream::new(Lexer::new(code))).unwrap();
+
+    let errors = sem_check(ast)
+        .1
+        .into_iter()
+        .map(|error| error.kind)
+        .collect::<Vec<_>>();
+
+    // each broken struct is reported once, the correct ones are declared
+    assert_eq!(
+        errors,
+        [
+            SemErrorKind::RecursiveStruct("A"),
+            SemErrorKind::BuiltinTypeName("int"),
+            SemErrorKind::DuplicateField("x"),
+            SemErrorKind::UnknownType("Q"),
+            SemErrorKind::UnknownType("R"),
+        ]
+    );
+}
@@ -0,0 +1,182 @@
+use std::fmt;
+
+use smplc_ast as ast;
+use smplc_ast::Span;
+
+/// The warnings don't stop the compilation
+#[derive(Debug)]
+pub struct Warning<'source> {
+    pub kind: WarningKind<'source>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WarningKind<'source> {
+    UnusedVariable(&'source str),
+    UnusedAssignment(&'source str),
+    UnusedFunction(&'source str),
+    UnusedConstant(&'source str),
+    UnusedResult(&'source str),
+    UnreachableStatement,
+    ConstantCondition(bool),
+}
+
+/// The lints can be allowed with `#[allow(...)]` and the `-A` flag
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Lint {
+    UnusedVariables,
+    UnusedAssignments,
+    /// unused functions, constants and statics
+    DeadCode,
+    UnusedResults,
+    UnreachableCode,
+    ConstantConditions,
+}
+
+impl<'source> Warning<'source> {
+    pub fn unused_variable(id: ast::Id<'source>) -> Self {
+        let ast::Spanned(id, span) = id;
+
+        Self {
+            kind: WarningKind::UnusedVariable(id),
+            span,
+        }
+    }
+
+    pub fn unused_assignment(id: ast::Id<'source>) -> Self {
+        let ast::Spanned(id, span) = id;
+
+        Self {
+            kind: WarningKind::UnusedAssignment(id),
+            span,
+        }
+    }
+
+    pub fn unused_function(id: ast::Id<'source>) -> Self {
+        let ast::Spanned(id, span) = id;
+
+        Self {
+            kind: WarningKind::UnusedFunction(id),
+            span,
+        }
+    }
+
+    pub fn unused_constant(id: ast::Id<'source>) -> Self {
+        let ast::Spanned(id, span) = id;
+
+        Self {
+            kind: WarningKind::UnusedConstant(id),
+            span,
+        }
+    }
+
+    pub fn unused_result(id: ast::Id<'source>, span: Span) -> Self {
+        Self {
+            kind: WarningKind::UnusedResult(id.0),
+            span,
+        }
+    }
+
+    pub fn unreachable_statement(span: Span) -> Self {
+        Self {
+            kind: WarningKind::UnreachableStatement,
+            span,
+        }
+    }
+
+    pub fn constant_condition(value: bool, span: Span) -> Self {
+        Self {
+            kind: WarningKind::ConstantCondition(value),
+            span,
+        }
+    }
+}
+
+impl WarningKind<'_> {
+    pub fn lint(&self) -> Lint {
+        match self {
+            Self::UnusedVariable(_) => Lint::UnusedVariables,
+            Self::UnusedAssignment(_) => Lint::UnusedAssignments,
+            Self::UnusedFunction(_) | Self::UnusedConstant(_) => Lint::DeadCode,
+            Self::UnusedResult(_) => Lint::UnusedResults,
+            Self::UnreachableStatement => Lint::UnreachableCode,
+            Self::ConstantCondition(_) => Lint::ConstantConditions,
+        }
+    }
+}
+
+impl Lint {
+    pub const ALL: [Self; 6] = [
+        Self::UnusedVariables,
+        Self::UnusedAssignments,
+        Self::DeadCode,
+        Self::UnusedResults,
+        Self::UnreachableCode,
+        Self::ConstantConditions,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::UnusedVariables => "unused_variables",
+            Self::UnusedAssignments => "unused_assignments",
+            Self::DeadCode => "dead_code",
+            Self::UnusedResults => "unused_results",
+            Self::UnreachableCode => "unreachable_code",
+            Self::ConstantConditions => "constant_conditions",
+        }
+    }
+
+    /// the lint with the name, `warnings` are all the lints
+    pub fn named(name: &str) -> Option<Vec<Self>> {
+        if name == "warnings" {
+            return Some(Self::ALL.to_vec());
+        }
+
+        Self::ALL
+            .into_iter()
+            .find(|lint| lint.name() == name)
+            .map(|lint| vec![lint])
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl fmt::Display for WarningKind<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WarningKind::UnusedVariable(id) => {
+                write!(f, "variable \"{id}\" is never used")
+            }
+
+            WarningKind::UnusedAssignment(id) => {
+                write!(f, "variable \"{id}\" is assigned, but never read")
+            }
+
+            WarningKind::UnusedFunction(id) => {
+                write!(f, "function \"{id}\" is never called")
+            }
+
+            WarningKind::UnusedConstant(id) => {
+                write!(f, "\"{id}\" is never used")
+            }
+
+            WarningKind::UnusedResult(id) => {
+                write!(f, "the result of \"{id}\" is unused")
+            }
+
+            WarningKind::UnreachableStatement => {
+                write!(f, "unreachable statement")
+            }
+
+            WarningKind::ConstantCondition(value) => {
+                write!(f, "condition is always {value}")
+            }
+        }?;
+
+        write!(f, " [{}]", self.lint())
+    }
+}
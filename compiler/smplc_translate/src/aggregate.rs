@@ -0,0 +1,383 @@
+use comet_ir::{ArithmOp, Atom, BinOp, ControlFlow, Id, Phi, RelOp, Sequental, Type, UnOp, Value};
+use smplc_thir as thir;
+use smplc_thir::{Symbols, VecType};
+
+use crate::{
+    expr::{read_var, translate_atom, translate_binary, translate_expr, write_var},
+    idents::BaseIdents,
+    translator::Translator,
+};
+
+pub fn translate_index<'source>(
+    translator: &mut Translator<'source>,
+    idents: &mut BaseIdents,
+    symbols: &Symbols<'source>,
+    lhs: thir::Expr<'source>,
+    index: thir::Expr<'source>,
+    ty: thir::Type,
+    len: usize,
+) -> Id {
+    // a variable is read in place instead of being copied first
+    let aggregate = match lhs {
+        thir::Expr::Atom(thir::Atom::Var(var)) => read_var(translator, idents, var),
+        lhs => translate_expr(lhs, translator, idents, symbols),
+    };
+
+    let ty = Type::from(&ty);
+
+    let offset = translate_offset(translator, idents, symbols, index, ty.slots(), len);
+
+    let dst = idents.next();
+
+    translator.code.push(Sequental::Load {
+        dst,
+        ty,
+        aggregate,
+        offset,
+    });
+
+    dst
+}
+
+pub fn translate_field<'source>(
+    translator: &mut Translator<'source>,
+    idents: &mut BaseIdents,
+    symbols: &Symbols<'source>,
+    dst: Id,
+    lhs: thir::Expr<'source>,
+    ty: thir::Type,
+    index: usize,
+) {
+    let aggregate = match lhs {
+        thir::Expr::Atom(thir::Atom::Var(var)) => read_var(translator, idents, var),
+        lhs => translate_expr(lhs, translator, idents, symbols),
+    };
+
+    let (field_ty, offset) = ty.field(index);
+
+    translator.code.push(Sequental::Load {
+        dst,
+        ty: Type::from(&field_ty),
+        aggregate,
+        offset: Atom::Value(Value::Int(offset as i32)),
+    });
+}
+
+pub fn translate_store<'source>(
+    translator: &mut Translator<'source>,
+    idents: &mut BaseIdents,
+    symbols: &Symbols<'source>,
+    place: thir::Place<'source>,
+    op: Option<thir::BinOp>,
+    rhs: thir::Expr<'source>,
+) {
+    let var_ty = &symbols.variables[place.var].ty;
+
+    let mut elem_ty = var_ty.clone();
+    let mut offset = None;
+
+    for projection in place.projections {
+        let projection_offset = match projection {
+            thir::Projection::Index(index) => {
+                let len = elem_ty.index_bound().unwrap();
+
+                elem_ty = match elem_ty {
+                    thir::Type::Array(elem, _) => *elem,
+                    mat => mat.column().unwrap(),
+                };
+
+                let stride = Type::from(&elem_ty).slots();
+
+                translate_offset(translator, idents, symbols, index, stride, len)
+            }
+
+            thir::Projection::Field { ty, index } => {
+                let (field_ty, offset) = ty.field(index);
+
+                elem_ty = field_ty;
+
+                Atom::Value(Value::Int(offset as i32))
+            }
+        };
+
+        offset = Some(match offset {
+            Some(offset) => add_offsets(translator, idents, offset, projection_offset),
+            None => projection_offset,
+        });
+    }
+
+    let aggregate = read_var(translator, idents, place.var);
+
+    // the old element is needed to update it, the index is evaluated once
+    let elem = match offset {
+        Some(offset) if op.is_some() || place.swizzle.is_some() || place.lane.is_some() => {
+            let dst = idents.next();
+
+            translator.code.push(Sequental::Load {
+                dst,
+                ty: (&elem_ty).into(),
+                aggregate,
+                offset,
+            });
+
+            dst
+        }
+
+        _ => aggregate,
+    };
+
+    let lane = place.lane.map(|lane| {
+        let dims = VecType::try_from(elem_ty.clone()).unwrap().dims();
+        let lane = translate_offset(translator, idents, symbols, lane, 1, dims as usize);
+
+        (dims, lane)
+    });
+
+    let mut value = translate_expr(rhs, translator, idents, symbols);
+
+    if let Some(op) = op {
+        let current = match (place.swizzle, lane) {
+            (Some(swizzle), _) => {
+                let dst = idents.next();
+
+                translator.code.push(Sequental::Unary {
+                    dst,
+                    op: UnOp::Swizzle(swizzle),
+                    operand: Atom::Id(elem),
+                });
+
+                dst
+            }
+
+            (None, Some((dims, lane))) => {
+                let dst = idents.next();
+
+                translator.code.push(Sequental::Binary {
+                    dst,
+                    op: BinOp::Lane(dims),
+                    lhs: Atom::Id(elem),
+                    rhs: lane,
+                });
+
+                dst
+            }
+
+            (None, None) => elem,
+        };
+
+        value = translate_binary(translator, idents, op, current, value);
+    }
+
+    if let Some(swizzle) = place.swizzle {
+        let dims = VecType::try_from(elem_ty.clone()).unwrap().dims();
+        let dst = idents.next();
+
+        translator.code.push(Sequental::Binary {
+            dst,
+            op: BinOp::Blend(dims, swizzle),
+            lhs: Atom::Id(elem),
+            rhs: Atom::Id(value),
+        });
+
+        value = dst;
+    }
+
+    if let Some((dims, lane)) = lane {
+        let dst = idents.next();
+
+        translator.code.push(Sequental::SetLane {
+            dst,
+            dims,
+            vec: Atom::Id(elem),
+            lane,
+            value: Atom::Id(value),
+        });
+
+        value = dst;
+    }
+
+    let Some(offset) = offset else {
+        write_var(translator, idents, place.var, value);
+
+        return;
+    };
+
+    let dst = idents.next();
+
+    translator.code.push(Sequental::Store {
+        dst,
+        ty: var_ty.into(),
+        aggregate,
+        offset,
+        value: (Atom::Id(value), (&elem_ty).into()),
+    });
+
+    write_var(translator, idents, place.var, dst);
+}
+
+/// fills the array by a loop, so the code doesn't grow with the length
+pub fn translate_repeat<'source>(
+    translator: &mut Translator<'source>,
+    idents: &mut BaseIdents,
+    symbols: &Symbols<'source>,
+    value: thir::Expr<'source>,
+    elem_ty: thir::Type,
+    len: usize,
+) -> Id {
+    let value = translate_expr(value, translator, idents, symbols);
+
+    let elem_ty = Type::from(&elem_ty);
+    let ty = Type::Aggregate(elem_ty.slots() * len);
+
+    let empty = idents.next();
+    let start = idents.next();
+
+    translator.code.push(Sequental::Aggregate {
+        dst: empty,
+        ty,
+        elems: vec![],
+    });
+    translator.code.push(Sequental::Assign {
+        dst: start,
+        ty: Type::Int,
+        value: Atom::Value(Value::Int(0)),
+    });
+
+    let array = idents.next();
+    let offset = idents.next();
+    let filled = idents.next();
+    let next = idents.next();
+
+    translator.code.push(Phi {
+        dst: array,
+        branches: vec![empty, filled],
+    });
+    translator.code.push(Phi {
+        dst: offset,
+        branches: vec![start, next],
+    });
+
+    let cond_label = translator.next_label();
+    let body_label = translator.next_label();
+    let end_label = translator.next_label();
+
+    translator.code.label(cond_label);
+
+    translator.code.push(ControlFlow::If {
+        lhs: Atom::Id(offset),
+        op: BinOp::IntRel(RelOp::Lt),
+        rhs: Atom::Value(Value::Int(ty.slots() as i32)),
+        label: body_label,
+    });
+    translator.code.push(ControlFlow::Goto { label: end_label });
+
+    translator.code.label(body_label);
+
+    translator.code.push(Sequental::Store {
+        dst: filled,
+        ty,
+        aggregate: array,
+        offset: Atom::Id(offset),
+        value: (Atom::Id(value), elem_ty),
+    });
+    translator.code.push(Sequental::Binary {
+        dst: next,
+        op: BinOp::Int(ArithmOp::Add),
+        lhs: Atom::Id(offset),
+        rhs: Atom::Value(Value::Int(elem_ty.slots() as i32)),
+    });
+    translator
+        .code
+        .push(ControlFlow::Goto { label: cond_label });
+
+    translator.code.label(end_label);
+
+    array
+}
+
+/// loads the elements of a tuple into the variables
+pub fn translate_destructure<'source>(
+    translator: &mut Translator<'source>,
+    idents: &mut BaseIdents,
+    symbols: &Symbols<'source>,
+    vars: Vec<thir::VarId>,
+    rhs: thir::Expr<'source>,
+) {
+    let aggregate = translate_expr(rhs, translator, idents, symbols);
+
+    let mut offset = 0;
+
+    for var in vars {
+        let ty = Type::from(&symbols.variables[var].ty);
+        let dst = idents.next();
+
+        translator.code.push(Sequental::Load {
+            dst,
+            ty,
+            aggregate,
+            offset: Atom::Value(Value::Int(offset as i32)),
+        });
+
+        write_var(translator, idents, var, dst);
+
+        offset += ty.slots();
+    }
+}
+
+/// translates `index * stride`, the offset is measured in slots
+fn translate_offset<'source>(
+    translator: &mut Translator<'source>,
+    idents: &mut BaseIdents,
+    symbols: &Symbols<'source>,
+    index: thir::Expr<'source>,
+    stride: usize,
+    len: usize,
+) -> Atom {
+    // a literal index is checked by the typecheck
+    if let thir::Expr::Atom(atom @ thir::Atom::Literal(_)) = index {
+        let index = translate_atom(atom, translator, idents);
+
+        if let Atom::Value(Value::Int(index)) = index {
+            return Atom::Value(Value::Int(index * stride as i32));
+        }
+    }
+
+    let index = translate_expr(index, translator, idents, symbols);
+
+    translator.code.push(Sequental::CheckIndex {
+        index: Atom::Id(index),
+        len,
+    });
+
+    if stride == 1 {
+        return Atom::Id(index);
+    }
+
+    let offset = idents.next();
+
+    translator.code.push(Sequental::Binary {
+        dst: offset,
+        op: BinOp::Int(ArithmOp::Mul),
+        lhs: Atom::Id(index),
+        rhs: Atom::Value(Value::Int(stride as i32)),
+    });
+
+    Atom::Id(offset)
+}
+
+fn add_offsets(translator: &mut Translator, idents: &mut BaseIdents, lhs: Atom, rhs: Atom) -> Atom {
+    if let (Atom::Value(Value::Int(lhs)), Atom::Value(Value::Int(rhs))) = (lhs, rhs) {
+        return Atom::Value(Value::Int(lhs + rhs));
+    }
+
+    let offset = idents.next();
+
+    translator.code.push(Sequental::Binary {
+        dst: offset,
+        op: BinOp::Int(ArithmOp::Add),
+        lhs,
+        rhs,
+    });
+
+    Atom::Id(offset)
+}
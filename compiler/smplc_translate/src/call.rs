@@ -1,8 +1,8 @@
-use comet_ir::{Atom, FunId, Id, Sequental};
+use comet_ir::{Atom, Format, Id, Sequental};
 use smplc_thir as thir;
 use smplc_thir::Symbols;
 
-use crate::{expr::translate_expr, idents::BaseIdents, translator::Translator};
+use crate::{expr::translate_expr, fun_id, idents::BaseIdents, translator::Translator};
 
 pub fn translate_call<'source>(
     translator: &mut Translator<'source>,
@@ -10,14 +10,14 @@ pub fn translate_call<'source>(
     symbols: &Symbols<'source>,
     dst: Option<Id>,
     fun: thir::FunId,
-    args: Vec<thir::Expr>,
+    args: Vec<thir::Expr<'source>>,
 ) {
     let fun_data = &symbols.functions[fun];
 
     let args = args
         .into_iter()
         .zip(&fun_data.args_types)
-        .map(|(arg, &ty)| {
+        .map(|(arg, ty)| {
             let arg = translate_expr(arg, translator, idents, symbols);
 
             // FIXME call arg can also be a value
@@ -25,7 +25,26 @@ pub fn translate_call<'source>(
         })
         .collect();
 
-    let fun = FunId::new(fun_data.id.0, fun_data.ret_ty.map(Into::into));
+    let fun = fun_id(symbols, fun);
 
     translator.code.push(Sequental::Call { dst, fun, args })
 }
+
+pub fn translate_print<'source>(
+    translator: &mut Translator<'source>,
+    idents: &mut BaseIdents,
+    symbols: &Symbols<'source>,
+    pieces: Vec<String>,
+    args: Vec<(thir::Expr<'source>, thir::Type)>,
+) {
+    let args = args
+        .into_iter()
+        .map(|(arg, ty)| {
+            let arg = translate_expr(arg, translator, idents, symbols);
+
+            (Atom::Id(arg), Format::from(&ty))
+        })
+        .collect();
+
+    translator.code.push(Sequental::Print { pieces, args })
+}
@@ -1,198 +1,940 @@
 use std::{
     cmp::Ordering,
+    collections::HashMap,
+    mem,
     ops::{Add, Div, Mul, Sub},
 };
 
-use nalgebra::{Vector2, Vector3, Vector4};
+use nalgebra::{
+    Matrix2, Matrix3, Matrix4, Quaternion, Unit, UnitQuaternion, Vector2, Vector3, Vector4,
+};
 use num::Complex;
 
-use comet_ir::Value;
-use smplc_thir::{self as thir, VecOp};
-use smplc_thir::{ArithmOp, NumberType, OrdOp};
-
-use crate::idents::BaseIdents;
-
-pub fn const_eval(expr: thir::Expr, idents: &BaseIdents) -> Value {
-    match expr {
-        thir::Expr::Binary { lhs, op, rhs } => {
-            let lhs = const_eval(*lhs, idents);
-            let rhs = const_eval(*rhs, idents);
-
-            match op {
-                thir::BinOp::Arithm(op, ty) => {
-                    fn arithm<T>(op: ArithmOp, lhs: T, rhs: T) -> T
-                    where
-                        T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
-                    {
-                        match op {
-                            ArithmOp::Add => lhs + rhs,
-                            ArithmOp::Sub => lhs - rhs,
-                            ArithmOp::Mul => lhs * rhs,
-                            ArithmOp::Div => lhs / rhs,
-                        }
-                    }
+use comet_ir::{Swizzle, Value};
+use smplc_thir::{self as thir, Conversion, MatOp, MatType, QuatOp, VarId, VecOp, VecType};
+use smplc_thir::{ArithmOp, IntOp, NumberType, OrdOp};
 
-                    match ty {
-                        NumberType::Complex => {
-                            let lhs = lhs.f32x2();
-                            let rhs = rhs.f32x2();
+use crate::error::ConstEvalErrorKind;
 
-                            let lhs = Complex::new(lhs.x, lhs.y);
-                            let rhs = Complex::new(rhs.x, rhs.y);
+/// statements and loop iterations a constant may take, an endless loop hits the limit
+pub(crate) const MAX_STEPS: usize = 1_000_000;
+/// nested calls of `const fn`s, an endless recursion hits the limit
+pub(crate) const MAX_DEPTH: usize = 128;
 
-                            let res = arithm(op, lhs, rhs);
+/// interrupts the evaluation up to the statement that handles it
+enum Stop<'source> {
+    Break(Option<&'source str>),
+    Continue(Option<&'source str>),
+    Return(Option<Value>),
+    Error(ConstEvalErrorKind<'source>),
+}
 
-                            let res = Vector2::new(res.re, res.im);
+impl<'source> From<ConstEvalErrorKind<'source>> for Stop<'source> {
+    fn from(value: ConstEvalErrorKind<'source>) -> Self {
+        Self::Error(value)
+    }
+}
 
-                            Value::F32x2(res)
-                        }
-                        NumberType::Real => Value::Real(arithm(op, lhs.real(), rhs.real())),
-                        NumberType::Int => Value::Int(arithm(op, lhs.int(), rhs.int())),
-                    }
+type EvalResult<'source, T> = Result<T, Stop<'source>>;
+
+/// Evaluates the values of constants and statics at compile time,
+/// the bodies of the called `const fn`s are interpreted
+pub struct ConstEval<'a, 'source> {
+    symbols: &'a thir::Symbols<'source>,
+    functions: HashMap<thir::FunId, &'a thir::Function<'source>>,
+
+    constants: HashMap<VarId, Value>,
+    /// the variables of the current call
+    frame: HashMap<VarId, Value>,
+
+    steps: usize,
+    depth: usize,
+}
+
+impl<'a, 'source> ConstEval<'a, 'source> {
+    pub fn new(
+        symbols: &'a thir::Symbols<'source>,
+        functions: &'a [thir::Function<'source>],
+    ) -> Self {
+        let functions = functions
+            .iter()
+            .filter(|function| symbols.functions[function.id].is_const)
+            .map(|function| (function.id, function))
+            .collect();
+
+        Self {
+            symbols,
+            functions,
+            constants: HashMap::new(),
+            frame: HashMap::new(),
+            steps: 0,
+            depth: 0,
+        }
+    }
+
+    /// the constant can be used by the values evaluated after
+    pub fn define(&mut self, var: VarId, value: Value) {
+        self.constants.insert(var, value);
+    }
+
+    pub fn eval(
+        &mut self,
+        expr: &thir::Expr<'source>,
+    ) -> Result<Value, ConstEvalErrorKind<'source>> {
+        self.steps = 0;
+        self.depth = 0;
+        self.frame.clear();
+
+        match self.expr(expr) {
+            Ok(value) => Ok(value),
+            Err(Stop::Error(kind)) => Err(kind),
+
+            // `break`, `continue` and `return` don't leave a function
+            Err(_) => unreachable!(),
+        }
+    }
+
+    fn step(&mut self) -> EvalResult<'source, ()> {
+        self.steps += 1;
+
+        if self.steps > MAX_STEPS {
+            return Err(ConstEvalErrorKind::StepLimit.into());
+        }
+
+        Ok(())
+    }
+
+    fn var(&self, var: VarId) -> EvalResult<'source, Value> {
+        self.frame
+            .get(&var)
+            .or_else(|| self.constants.get(&var))
+            .copied()
+            .ok_or_else(|| {
+                let id = self.symbols.variables[var].id.0;
+
+                ConstEvalErrorKind::CyclicConstant(id).into()
+            })
+    }
+
+    fn block(&mut self, block: &thir::Block<'source>) -> EvalResult<'source, ()> {
+        for stmt in &block.statements {
+            self.statement(stmt)?;
+        }
+
+        Ok(())
+    }
+
+    /// runs an iteration of a loop, returns whether the loop is broken
+    fn iteration(
+        &mut self,
+        label: Option<&'source str>,
+        body: &thir::Block<'source>,
+    ) -> EvalResult<'source, bool> {
+        // an empty loop takes steps too
+        self.step()?;
+
+        match self.block(body) {
+            Ok(()) => Ok(false),
+            Err(Stop::Break(target)) if target.is_none() || target == label => Ok(true),
+            Err(Stop::Continue(target)) if target.is_none() || target == label => Ok(false),
+            Err(stop) => Err(stop),
+        }
+    }
+
+    fn cond(&mut self, cond: &thir::Expr<'source>) -> EvalResult<'source, bool> {
+        Ok(self.expr(cond)?.int() != 0)
+    }
+
+    fn statement(&mut self, stmt: &thir::Statement<'source>) -> EvalResult<'source, ()> {
+        self.step()?;
+
+        match stmt {
+            thir::Statement::Expr(expr_statement) => self.expr_statement(expr_statement),
+
+            thir::Statement::If(thir::IfStatement {
+                cond,
+                body,
+                else_body,
+            }) => {
+                if self.cond(cond)? {
+                    self.block(body)
+                } else if let Some(else_body) = else_body {
+                    self.block(else_body)
+                } else {
+                    Ok(())
                 }
-                thir::BinOp::Ord(op, ty) => {
-                    fn rel(op: OrdOp, ordering: Ordering) -> bool {
-                        match op {
-                            OrdOp::Gt => ordering.is_gt(),
-                            OrdOp::Ge => ordering.is_ge(),
-                            OrdOp::Lt => ordering.is_lt(),
-                            OrdOp::Le => ordering.is_le(),
-                        }
-                    }
+            }
 
-                    let ord = match ty {
-                        NumberType::Real => f32::total_cmp(&lhs.real(), &rhs.real()),
-                        NumberType::Int => Ord::cmp(&lhs.int(), &rhs.int()),
+            thir::Statement::Return(thir::ReturnStatement { value, .. }) => {
+                let value = value.as_ref().map(|value| self.expr(value)).transpose()?;
 
-                        NumberType::Complex => unreachable!(),
-                    };
+                Err(Stop::Return(value))
+            }
 
-                    Value::Int(rel(op, ord) as i32)
+            thir::Statement::While(thir::WhileStatement { label, cond, body }) => {
+                while self.cond(cond)? {
+                    if self.iteration(*label, body)? {
+                        break;
+                    }
                 }
-                thir::BinOp::Eq(op, ty) => {
-                    let value = match ty {
-                        thir::LinearType::Number(NumberType::Complex)
-                        | thir::LinearType::Vec(thir::VecType::Vec2) => lhs.f32x2() == rhs.f32x2(),
-                        thir::LinearType::Vec(thir::VecType::Vec3) => lhs.f32x3() == rhs.f32x3(),
-                        thir::LinearType::Vec(thir::VecType::Vec4) => lhs.f32x4() == rhs.f32x4(),
 
-                        thir::LinearType::Number(NumberType::Real) => lhs.real() == rhs.real(),
-                        thir::LinearType::Number(NumberType::Int) => lhs.int() == rhs.int(),
-                    };
+                Ok(())
+            }
 
-                    Value::Int(!(value ^ (op == thir::EqOp::Eq)) as i32)
-                }
-                thir::BinOp::Vec(op, ty) => {
-                    if matches!(op, thir::VecOp::Add | thir::VecOp::Sub) {
-                        fn oper<T>(lhs: T, op: VecOp, rhs: T) -> T
-                        where
-                            T: Add<Output = T> + Sub<Output = T>,
-                        {
-                            match op {
-                                VecOp::Add => lhs + rhs,
-                                VecOp::Sub => lhs - rhs,
-                                _ => unreachable!(),
-                            }
-                        }
+            thir::Statement::DoWhile(thir::DoWhileStatement { label, body, cond }) => {
+                while !self.iteration(*label, body)? && self.cond(cond)? {}
+
+                Ok(())
+            }
+
+            thir::Statement::Loop(thir::LoopStatement { label, body }) => {
+                while !self.iteration(*label, body)? {}
+
+                Ok(())
+            }
+
+            thir::Statement::For(for_statement) => self.for_statement(for_statement),
+
+            thir::Statement::Match(thir::MatchStatement { value, arms }) => {
+                let value = self.expr(value)?.int();
+
+                let arm = arms.iter().find(|arm| {
+                    arm.patterns.iter().any(|pattern| match *pattern {
+                        thir::Pattern::Range(start, end) => (start..=end).contains(&value),
+                        thir::Pattern::Wildcard => true,
+                    })
+                });
+
+                // the match is exhaustive
+                self.block(&arm.unwrap().body)
+            }
+
+            thir::Statement::Break(label) => Err(Stop::Break(*label)),
+            thir::Statement::Continue(label) => Err(Stop::Continue(*label)),
+        }
+    }
+
+    fn for_statement(&mut self, stmt: &thir::ForStatement<'source>) -> EvalResult<'source, ()> {
+        let start = self.expr(&stmt.start)?;
+        let end = self.expr(&stmt.end)?;
+
+        let step = match &stmt.step {
+            Some(step) => self.expr(step)?,
+            None => Value::Int(1),
+        };
+
+        // a negative step counts down, a zero step runs no iterations
+        let direction = match step {
+            Value::Real(step) => step.partial_cmp(&0.).unwrap_or(Ordering::Equal),
+            step => step.int().cmp(&0),
+        };
+
+        if direction.is_eq() {
+            return Ok(());
+        }
 
-                        return match ty {
-                            thir::VecType::Vec2 => Value::F32x2(oper(lhs.f32x2(), op, rhs.f32x2())),
-                            thir::VecType::Vec3 => Value::F32x3(oper(lhs.f32x3(), op, rhs.f32x3())),
-                            thir::VecType::Vec4 => Value::F32x4(oper(lhs.f32x4(), op, rhs.f32x4())),
-                        };
+        self.frame.insert(stmt.var, start);
+
+        loop {
+            let ordering = match (self.var(stmt.var)?, end) {
+                (Value::Real(var), Value::Real(end)) => f32::total_cmp(&var, &end),
+                (var, end) => Ord::cmp(&var.int(), &end.int()),
+            };
+
+            if !(ordering == direction.reverse() || stmt.inclusive && ordering.is_eq()) {
+                break;
+            }
+
+            // `continue` still increments the counter
+            if self.iteration(stmt.label, &stmt.body)? {
+                break;
+            }
+
+            let next = match (self.var(stmt.var)?, step) {
+                (Value::Real(var), Value::Real(step)) => Value::Real(var + step),
+                (var, step) => Value::Int(var.int().wrapping_add(step.int())),
+            };
+
+            self.frame.insert(stmt.var, next);
+        }
+
+        Ok(())
+    }
+
+    fn expr_statement(&mut self, stmt: &thir::ExprStatement<'source>) -> EvalResult<'source, ()> {
+        match stmt {
+            thir::ExprStatement::Assign { var, rhs } => {
+                let value = self.expr(rhs)?;
+
+                self.frame.insert(*var, value);
+            }
+
+            thir::ExprStatement::Store { place, op, rhs } => {
+                let var = self.var(place.var)?;
+
+                // only a column of a matrix can be assigned
+                let column = match place.projections.as_slice() {
+                    [] => None,
+
+                    [thir::Projection::Index(index)] if is_mat(var) => {
+                        Some(self.expr(index)?.int())
                     }
 
-                    let (scalar, vec) = match op {
-                        thir::VecOp::LeftMul => (lhs.real(), rhs),
-                        thir::VecOp::RightMul => (rhs.real(), lhs),
-                        thir::VecOp::Div => (lhs.real(), rhs),
+                    _ => return Err(ConstEvalErrorKind::Unsupported("arrays and structs").into()),
+                };
 
-                        _ => unreachable!(),
-                    };
+                let elem = match column {
+                    Some(index) => column_of(var, index)?,
+                    None => var,
+                };
+
+                let lane = match &place.lane {
+                    Some(lane) => Some(self.expr(lane)?.int()),
+                    None => None,
+                };
 
-                    fn oper<V>(scalar: f32, op: VecOp, vec: V) -> V
-                    where
-                        V: Mul<f32, Output = V>,
-                        V: Div<f32, Output = V>,
-                    {
-                        match op {
-                            VecOp::LeftMul | VecOp::RightMul => vec * scalar,
-                            VecOp::Div => vec / scalar,
-                            _ => unreachable!(),
+                let mut value = self.expr(rhs)?;
+
+                if let Some(op) = *op {
+                    let current = match (&place.swizzle, lane) {
+                        (Some(swizzle), _) => swizzled(elem, swizzle),
+                        (None, Some(lane)) => {
+                            binary(thir::BinOp::Lane(vec_type(elem)), elem, Value::Int(lane))?
                         }
-                    }
+                        (None, None) => elem,
+                    };
 
-                    match ty {
-                        thir::VecType::Vec2 => Value::F32x2(oper(scalar, op, vec.f32x2())),
-                        thir::VecType::Vec3 => Value::F32x3(oper(scalar, op, vec.f32x3())),
-                        thir::VecType::Vec4 => Value::F32x4(oper(scalar, op, vec.f32x4())),
-                    }
+                    value = binary(op, current, value)?;
+                }
+
+                if let Some(swizzle) = &place.swizzle {
+                    value = blend(elem, swizzle, value);
                 }
-                thir::BinOp::Or => Value::Int((lhs.int() != 0 || rhs.int() != 0) as _),
-                thir::BinOp::And => Value::Int((lhs.int() != 0 && rhs.int() != 0) as _),
+
+                if let Some(lane) = lane {
+                    value = with_lane(elem, lane, value)?;
+                }
+
+                if let Some(index) = column {
+                    value = with_column(var, index, value);
+                }
+
+                self.frame.insert(place.var, value);
+            }
+
+            thir::ExprStatement::Destructure { .. } => {
+                return Err(ConstEvalErrorKind::Unsupported("tuples").into());
+            }
+
+            thir::ExprStatement::Expr(expr) => {
+                self.expr(expr)?;
             }
         }
-        thir::Expr::Unary { op, rhs } => {
-            let rhs = const_eval(*rhs, idents);
 
-            match op {
-                thir::UnOp::Neg(thir::LinearType::Number(NumberType::Complex))
-                | thir::UnOp::Neg(thir::LinearType::Vec(thir::VecType::Vec2)) => {
-                    Value::F32x2(-rhs.f32x2())
+        Ok(())
+    }
+
+    fn block_expr(&mut self, block: &thir::BlockExpr<'source>) -> EvalResult<'source, Value> {
+        for stmt in &block.statements {
+            self.statement(stmt)?;
+        }
+
+        // a block without a value has stopped at its diverging statement
+        match &block.value {
+            Some(value) => self.expr(value),
+            None => unreachable!(),
+        }
+    }
+
+    fn expr(&mut self, expr: &thir::Expr<'source>) -> EvalResult<'source, Value> {
+        let value = match expr {
+            // the rhs of logical operators is evaluated only if it's needed
+            thir::Expr::Binary {
+                lhs,
+                op: op @ (thir::BinOp::And | thir::BinOp::Or),
+                rhs,
+            } => {
+                let lhs = self.cond(lhs)?;
+
+                let value = match op {
+                    thir::BinOp::And => lhs && self.cond(rhs)?,
+                    _ => lhs || self.cond(rhs)?,
+                };
+
+                Value::Int(value as i32)
+            }
+            thir::Expr::Binary { lhs, op, rhs } => {
+                let lhs = self.expr(lhs)?;
+                let rhs = self.expr(rhs)?;
+
+                binary(*op, lhs, rhs)?
+            }
+            thir::Expr::Unary { op, rhs } => unary(*op, self.expr(rhs)?),
+            thir::Expr::Swizzle { lhs, swizzle } => swizzled(self.expr(lhs)?, swizzle),
+            thir::Expr::If {
+                cond,
+                body,
+                else_body,
+            } => {
+                if self.cond(cond)? {
+                    self.block_expr(body)?
+                } else {
+                    self.block_expr(else_body)?
                 }
-                thir::UnOp::Neg(thir::LinearType::Vec(thir::VecType::Vec3)) => {
-                    Value::F32x3(-rhs.f32x3())
+            }
+            thir::Expr::Block(block) => self.block_expr(block)?,
+            thir::Expr::Len(len) => Value::Int(*len as i32),
+            thir::Expr::Index { lhs, index, .. } => {
+                let lhs = self.expr(lhs)?;
+
+                if !is_mat(lhs) {
+                    return Err(ConstEvalErrorKind::Unsupported("arrays").into());
                 }
-                thir::UnOp::Neg(thir::LinearType::Vec(thir::VecType::Vec4)) => {
-                    Value::F32x4(-rhs.f32x4())
+
+                let index = self.expr(index)?.int();
+
+                column_of(lhs, index)?
+            }
+            thir::Expr::Array { .. } | thir::Expr::ArrayRepeat { .. } => {
+                return Err(ConstEvalErrorKind::Unsupported("arrays").into());
+            }
+            thir::Expr::Field { .. } | thir::Expr::Struct { .. } => {
+                return Err(ConstEvalErrorKind::Unsupported("structs").into());
+            }
+            thir::Expr::Tuple(_) => return Err(ConstEvalErrorKind::Unsupported("tuples").into()),
+            thir::Expr::Call { fun, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.expr(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                self.call(*fun, args)?
+            }
+            thir::Expr::Print { .. } => {
+                return Err(ConstEvalErrorKind::NonConstCall("print").into());
+            }
+            thir::Expr::Atom(atom) => match atom {
+                &thir::Atom::Var(var) => self.var(var)?,
+                thir::Atom::Literal(literal) => match literal.ty {
+                    thir::LiteralType::Complex => Value::F32x2(Vector2::new(
+                        0.0,
+                        parse_int::parse(&literal.value[0..literal.value.len() - 1]).unwrap(),
+                    )),
+                    thir::LiteralType::Real => {
+                        Value::Real(parse_int::parse(literal.value).unwrap())
+                    }
+                    thir::LiteralType::Int => Value::Int(parse_int::parse(literal.value).unwrap()),
+                    thir::LiteralType::Bool => {
+                        Value::Int(if literal.value == "true" { 1 } else { 0 })
+                    }
+                    thir::LiteralType::String => unreachable!(),
+                },
+            },
+        };
+
+        Ok(value)
+    }
+
+    fn call(&mut self, fun: thir::FunId, args: Vec<Value>) -> EvalResult<'source, Value> {
+        let Some(function) = self.functions.get(&fun).copied() else {
+            let name = self.symbols.functions[fun].id.0;
+
+            return std_call(name, &args).ok_or(ConstEvalErrorKind::NonConstCall(name).into());
+        };
+
+        if self.depth == MAX_DEPTH {
+            return Err(ConstEvalErrorKind::RecursionLimit.into());
+        }
+
+        let frame = function.args.iter().copied().zip(args).collect();
+        let caller = mem::replace(&mut self.frame, frame);
+
+        self.depth += 1;
+        let result = self.block(&function.body);
+        self.depth -= 1;
+
+        self.frame = caller;
+
+        match result {
+            // the result of a function without a return value is never used
+            Ok(()) | Err(Stop::Return(None)) => Ok(Value::Int(0)),
+            Err(Stop::Return(Some(value))) => Ok(value),
+            Err(stop) => Err(stop),
+        }
+    }
+}
+
+fn binary<'source>(
+    op: thir::BinOp,
+    lhs: Value,
+    rhs: Value,
+) -> Result<Value, ConstEvalErrorKind<'source>> {
+    let value = match op {
+        thir::BinOp::Arithm(op, ty) => {
+            fn arithm<T>(op: ArithmOp, lhs: T, rhs: T) -> T
+            where
+                T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+            {
+                match op {
+                    ArithmOp::Add => lhs + rhs,
+                    ArithmOp::Sub => lhs - rhs,
+                    ArithmOp::Mul => lhs * rhs,
+                    ArithmOp::Div => lhs / rhs,
                 }
+            }
 
-                thir::UnOp::Neg(thir::LinearType::Number(NumberType::Real)) => {
-                    Value::Real(-rhs.real())
+            match ty {
+                NumberType::Complex => {
+                    complex_value(arithm(op, complex_operand(lhs), complex_operand(rhs)))
                 }
-                thir::UnOp::Neg(thir::LinearType::Number(NumberType::Int)) => {
-                    Value::Int(-rhs.int())
+                NumberType::Real => Value::Real(arithm(op, lhs.real(), rhs.real())),
+                // ints wrap around like they do at runtime
+                NumberType::Int => {
+                    let (lhs, rhs) = (lhs.int(), rhs.int());
+
+                    Value::Int(match op {
+                        ArithmOp::Add => lhs.wrapping_add(rhs),
+                        ArithmOp::Sub => lhs.wrapping_sub(rhs),
+                        ArithmOp::Mul => lhs.wrapping_mul(rhs),
+                        ArithmOp::Div if rhs == 0 => {
+                            return Err(ConstEvalErrorKind::DivisionByZero)
+                        }
+                        ArithmOp::Div => lhs.wrapping_div(rhs),
+                    })
                 }
-                thir::UnOp::Not => Value::Int(!rhs.int()),
             }
         }
-        thir::Expr::Swizzle { lhs, swizzle } => {
-            let lhs = const_eval(*lhs, idents);
+        thir::BinOp::Int(op) => {
+            let (lhs, rhs) = (lhs.int(), rhs.int());
+
+            let res = match op {
+                IntOp::Rem if rhs == 0 => return Err(ConstEvalErrorKind::DivisionByZero),
+                IntOp::Rem => lhs.wrapping_rem(rhs),
+                IntOp::And => lhs & rhs,
+                IntOp::Or => lhs | rhs,
+                IntOp::Xor => lhs ^ rhs,
+                // x86 masks the shift count the same way
+                IntOp::Shl => lhs.wrapping_shl(rhs as u32),
+                IntOp::Shr => lhs.wrapping_shr(rhs as u32),
+            };
+
+            Value::Int(res)
+        }
+        thir::BinOp::Ord(op, ty) => {
+            fn rel(op: OrdOp, ordering: Ordering) -> bool {
+                match op {
+                    OrdOp::Gt => ordering.is_gt(),
+                    OrdOp::Ge => ordering.is_ge(),
+                    OrdOp::Lt => ordering.is_lt(),
+                    OrdOp::Le => ordering.is_le(),
+                }
+            }
 
-            let source_vec = match &lhs {
-                Value::F32x2(matrix) => matrix.as_slice(),
-                Value::F32x3(matrix) => matrix.as_slice(),
-                Value::F32x4(matrix) => matrix.as_slice(),
+            let ord = match ty {
+                NumberType::Real => f32::total_cmp(&lhs.real(), &rhs.real()),
+                NumberType::Int => Ord::cmp(&lhs.int(), &rhs.int()),
 
-                _ => unreachable!(),
+                NumberType::Complex => unreachable!(),
             };
 
-            let mut new_vec = [0.; 4];
+            Value::Int(rel(op, ord) as i32)
+        }
+        thir::BinOp::Eq(op, ty) => {
+            let value = match ty {
+                thir::LinearType::Number(NumberType::Complex) => {
+                    complex_operand(lhs) == complex_operand(rhs)
+                }
+                thir::LinearType::Vec(thir::VecType::Vec2) => lhs.f32x2() == rhs.f32x2(),
+                thir::LinearType::Vec(thir::VecType::Vec3) => lhs.f32x3() == rhs.f32x3(),
+                thir::LinearType::Vec(thir::VecType::Vec4) => lhs.f32x4() == rhs.f32x4(),
 
-            for (n, &comp) in swizzle.as_slice().into_iter().enumerate() {
-                new_vec[n] = source_vec[comp as usize];
+                thir::LinearType::Number(NumberType::Real) => lhs.real() == rhs.real(),
+                thir::LinearType::Number(NumberType::Int) => lhs.int() == rhs.int(),
+            };
+
+            Value::Int(!(value ^ (op == thir::EqOp::Eq)) as i32)
+        }
+        thir::BinOp::Vec(op, ty) => {
+            if matches!(op, thir::VecOp::Add | thir::VecOp::Sub) {
+                fn oper<T>(lhs: T, op: VecOp, rhs: T) -> T
+                where
+                    T: Add<Output = T> + Sub<Output = T>,
+                {
+                    match op {
+                        VecOp::Add => lhs + rhs,
+                        VecOp::Sub => lhs - rhs,
+                        _ => unreachable!(),
+                    }
+                }
+
+                return Ok(match ty {
+                    thir::VecType::Vec2 => Value::F32x2(oper(lhs.f32x2(), op, rhs.f32x2())),
+                    thir::VecType::Vec3 => Value::F32x3(oper(lhs.f32x3(), op, rhs.f32x3())),
+                    thir::VecType::Vec4 => Value::F32x4(oper(lhs.f32x4(), op, rhs.f32x4())),
+                });
             }
 
-            match swizzle.as_slice().len() {
-                1 => Value::Real(new_vec[0]),
-                2 => Value::F32x2(Vector2::new(new_vec[0], new_vec[1])),
-                3 => Value::F32x3(Vector3::new(new_vec[0], new_vec[1], new_vec[2])),
-                4 => Value::F32x4(Vector4::new(new_vec[0], new_vec[1], new_vec[2], new_vec[3])),
+            let (scalar, vec) = match op {
+                thir::VecOp::LeftMul => (lhs.real(), rhs),
+                thir::VecOp::RightMul => (rhs.real(), lhs),
+                thir::VecOp::Div => (lhs.real(), rhs),
 
                 _ => unreachable!(),
+            };
+
+            fn oper<V>(scalar: f32, op: VecOp, vec: V) -> V
+            where
+                V: Mul<f32, Output = V>,
+                V: Div<f32, Output = V>,
+            {
+                match op {
+                    VecOp::LeftMul | VecOp::RightMul => vec * scalar,
+                    VecOp::Div => vec / scalar,
+                    _ => unreachable!(),
+                }
+            }
+
+            match ty {
+                thir::VecType::Vec2 => Value::F32x2(oper(scalar, op, vec.f32x2())),
+                thir::VecType::Vec3 => Value::F32x3(oper(scalar, op, vec.f32x3())),
+                thir::VecType::Vec4 => Value::F32x4(oper(scalar, op, vec.f32x4())),
+            }
+        }
+        thir::BinOp::Mat(MatOp::MulVec, ty) => vec_value(mat4(lhs) * vec4(rhs), ty.column()),
+        thir::BinOp::Mat(op, ty) => {
+            let res = match op {
+                MatOp::Add => mat4(lhs) + mat4(rhs),
+                MatOp::Sub => mat4(lhs) - mat4(rhs),
+                MatOp::LeftMul => mat4(rhs) * lhs.real(),
+                MatOp::RightMul => mat4(lhs) * rhs.real(),
+                MatOp::Div => mat4(lhs) / rhs.real(),
+                MatOp::MulMat => mat4(lhs) * mat4(rhs),
+
+                MatOp::MulVec => unreachable!(),
+            };
+
+            mat_value(res, ty)
+        }
+        thir::BinOp::Lane(ty) => {
+            let len = ty.dims() as usize;
+            let index = rhs.int();
+
+            if !(0..len as i32).contains(&index) {
+                return Err(ConstEvalErrorKind::IndexOutOfBounds { index, len });
             }
+
+            Value::Real(vec4(lhs)[index as usize])
         }
-        thir::Expr::Call { .. } => panic!("there's no const fn lol"),
-        thir::Expr::Atom(atom) => match atom {
-            thir::Atom::Var(var) => idents.constants[&idents.get(var)],
-            thir::Atom::Literal(literal) => match literal.ty {
-                thir::LiteralType::Complex => {
-                    Value::F32x2(Vector2::new(0.0, parse_int::parse(literal.value).unwrap()))
+        thir::BinOp::Quat(op) => {
+            let quat = |value: Value| Quaternion::from(value.f32x4());
+
+            let res = match op {
+                QuatOp::Add => quat(lhs) + quat(rhs),
+                QuatOp::Sub => quat(lhs) - quat(rhs),
+                QuatOp::LeftMul => quat(rhs) * lhs.real(),
+                QuatOp::RightMul => quat(lhs) * rhs.real(),
+                QuatOp::Div => quat(lhs) / rhs.real(),
+                QuatOp::MulQuat => quat(lhs) * quat(rhs),
+                QuatOp::DivQuat => {
+                    let inverse = quat(rhs).try_inverse();
+
+                    quat(lhs) * inverse.unwrap_or(Quaternion::from(Vector4::repeat(f32::NAN)))
                 }
-                thir::LiteralType::Real => Value::Real(parse_int::parse(literal.value).unwrap()),
-                thir::LiteralType::Int => Value::Int(parse_int::parse(literal.value).unwrap()),
-                thir::LiteralType::Bool => Value::Int(if literal.value == "true" { 1 } else { 0 }),
-            },
+            };
+
+            Value::F32x4(res.coords)
+        }
+        thir::BinOp::Or => Value::Int((lhs.int() != 0 || rhs.int() != 0) as _),
+        thir::BinOp::And => Value::Int((lhs.int() != 0 && rhs.int() != 0) as _),
+    };
+
+    Ok(value)
+}
+
+fn unary(op: thir::UnOp, rhs: Value) -> Value {
+    match op {
+        thir::UnOp::Neg(thir::LinearType::Number(NumberType::Complex))
+        | thir::UnOp::Neg(thir::LinearType::Vec(thir::VecType::Vec2)) => Value::F32x2(-rhs.f32x2()),
+        thir::UnOp::Neg(thir::LinearType::Vec(thir::VecType::Vec3)) => Value::F32x3(-rhs.f32x3()),
+        thir::UnOp::Neg(thir::LinearType::Vec(thir::VecType::Vec4)) => Value::F32x4(-rhs.f32x4()),
+
+        thir::UnOp::Neg(thir::LinearType::Number(NumberType::Real)) => Value::Real(-rhs.real()),
+        thir::UnOp::Neg(thir::LinearType::Number(NumberType::Int)) => {
+            Value::Int(rhs.int().wrapping_neg())
+        }
+        thir::UnOp::Not => Value::Int((rhs.int() == 0) as i32),
+        thir::UnOp::BitNot => Value::Int(!rhs.int()),
+        thir::UnOp::Convert(conversion) => match conversion {
+            Conversion::IntToReal => Value::Real(rhs.int() as f32),
+            Conversion::RealToInt => Value::Int(rhs.real() as i32),
+            Conversion::IntToComplex => Value::F32x2(Vector2::new(rhs.int() as f32, 0.)),
+            Conversion::RealToComplex => Value::F32x2(Vector2::new(rhs.real(), 0.)),
+            Conversion::QuatToVec4 | Conversion::Vec4ToQuat => rhs,
+            // `vec4` fills the missing components with zeros
+            Conversion::Resize(_, ty) => vec_value(vec4(rhs), ty),
         },
+        thir::UnOp::Transpose(ty) => mat_value(mat4(rhs).transpose(), ty),
+        thir::UnOp::Determinant(_) => Value::Real(mat4(rhs).determinant()),
+        thir::UnOp::Inverse(ty) => {
+            let inverse = mat4(rhs).try_inverse();
+
+            mat_value(inverse.unwrap_or(Matrix4::repeat(f32::NAN)), ty)
+        }
+    }
+}
+
+fn swizzled(value: Value, swizzle: &Swizzle) -> Value {
+    let source_vec = vec4(value);
+
+    let mut new_vec = [0.; 4];
+
+    for (n, &comp) in swizzle.as_slice().iter().enumerate() {
+        new_vec[n] = source_vec[comp as usize];
+    }
+
+    match swizzle.as_slice().len() {
+        1 => Value::Real(new_vec[0]),
+        2 => Value::F32x2(Vector2::new(new_vec[0], new_vec[1])),
+        3 => Value::F32x3(Vector3::new(new_vec[0], new_vec[1], new_vec[2])),
+        4 => Value::F32x4(Vector4::new(new_vec[0], new_vec[1], new_vec[2], new_vec[3])),
+
+        _ => unreachable!(),
+    }
+}
+
+/// replaces the swizzled components of `vec` with `value`
+fn blend(vec: Value, swizzle: &Swizzle, value: Value) -> Value {
+    let mut result = vec4(vec);
+
+    let value = match value {
+        Value::Real(x) => Vector4::new(x, 0., 0., 0.),
+        value => vec4(value),
+    };
+
+    for (n, &comp) in swizzle.as_slice().iter().enumerate() {
+        result[comp as usize] = value[n];
+    }
+
+    match vec {
+        Value::F32x2(_) => vec_value(result, VecType::Vec2),
+        Value::F32x3(_) => vec_value(result, VecType::Vec3),
+        _ => vec_value(result, VecType::Vec4),
+    }
+}
+
+fn with_lane<'source>(
+    vec: Value,
+    index: i32,
+    value: Value,
+) -> Result<Value, ConstEvalErrorKind<'source>> {
+    let ty = vec_type(vec);
+    let len = ty.dims() as usize;
+
+    if !(0..len as i32).contains(&index) {
+        return Err(ConstEvalErrorKind::IndexOutOfBounds { index, len });
+    }
+
+    let mut result = vec4(vec);
+
+    result[index as usize] = value.real();
+
+    Ok(vec_value(result, ty))
+}
+
+fn is_mat(value: Value) -> bool {
+    matches!(
+        value,
+        Value::F32x2x2(_) | Value::F32x3x3(_) | Value::F32x4x4(_)
+    )
+}
+
+fn vec_type(value: Value) -> VecType {
+    match value {
+        Value::F32x2(_) => VecType::Vec2,
+        Value::F32x3(_) => VecType::Vec3,
+        Value::F32x4(_) => VecType::Vec4,
+
+        _ => unreachable!(),
+    }
+}
+
+fn mat_type(value: Value) -> MatType {
+    match value {
+        Value::F32x2x2(_) => MatType::Mat2,
+        Value::F32x3x3(_) => MatType::Mat3,
+        Value::F32x4x4(_) => MatType::Mat4,
+
+        _ => unreachable!(),
+    }
+}
+
+fn column_of<'source>(mat: Value, index: i32) -> Result<Value, ConstEvalErrorKind<'source>> {
+    let ty = mat_type(mat);
+    let len = ty.dims() as usize;
+
+    if !(0..len as i32).contains(&index) {
+        return Err(ConstEvalErrorKind::IndexOutOfBounds { index, len });
+    }
+
+    let column = mat4(mat).column(index as usize).clone_owned();
+
+    Ok(vec_value(column, ty.column()))
+}
+
+fn with_column(mat: Value, index: i32, column: Value) -> Value {
+    let ty = mat_type(mat);
+    let mut result = mat4(mat);
+
+    result.set_column(index as usize, &vec4(column));
+
+    mat_value(result, ty)
+}
+
+/// folds a call of the math, complex and quaternion functions
+/// and the vector and matrix constructors of the std
+fn std_call(name: &str, args: &[Value]) -> Option<Value> {
+    let value = match (name, args) {
+        ("sqrt", &[Value::Real(x)]) => Value::Real(x.sqrt()),
+        ("sin", &[Value::Real(x)]) => Value::Real(x.sin()),
+        ("cos", &[Value::Real(x)]) => Value::Real(x.cos()),
+        ("tan", &[Value::Real(x)]) => Value::Real(x.tan()),
+        ("atan2", &[Value::Real(y), Value::Real(x)]) => Value::Real(y.atan2(x)),
+        ("exp", &[Value::Real(x)]) => Value::Real(x.exp()),
+        ("ln", &[Value::Real(x)]) => Value::Real(x.ln()),
+        ("pow", &[Value::Real(x), Value::Real(y)]) => Value::Real(x.powf(y)),
+        ("floor", &[Value::Real(x)]) => Value::Real(x.floor()),
+        ("ceil", &[Value::Real(x)]) => Value::Real(x.ceil()),
+
+        ("abs", &[Value::Real(x)]) => Value::Real(x.abs()),
+        ("abs", &[Value::Int(x)]) => Value::Int(x.wrapping_abs()),
+
+        ("min", &[Value::Real(a), Value::Real(b)]) => Value::Real(a.min(b)),
+        ("min", &[Value::Int(a), Value::Int(b)]) => Value::Int(a.min(b)),
+        ("max", &[Value::Real(a), Value::Real(b)]) => Value::Real(a.max(b)),
+        ("max", &[Value::Int(a), Value::Int(b)]) => Value::Int(a.max(b)),
+
+        ("vec2", &[Value::Real(x), Value::Real(y)]) => Value::F32x2(Vector2::new(x, y)),
+        ("vec3", &[Value::Real(x), Value::Real(y), Value::Real(z)]) => {
+            Value::F32x3(Vector3::new(x, y, z))
+        }
+        ("vec4", &[Value::Real(x), Value::Real(y), Value::Real(z), Value::Real(w)]) => {
+            Value::F32x4(Vector4::new(x, y, z, w))
+        }
+
+        ("mat2", &[Value::F32x2(a), Value::F32x2(b)]) => {
+            Value::F32x2x2(Matrix2::from_columns(&[a, b]))
+        }
+        ("mat3", &[Value::F32x3(a), Value::F32x3(b), Value::F32x3(c)]) => {
+            Value::F32x3x3(Matrix3::from_columns(&[a, b, c]))
+        }
+        ("mat4", &[Value::F32x4(a), Value::F32x4(b), Value::F32x4(c), Value::F32x4(d)]) => {
+            Value::F32x4x4(Matrix4::from_columns(&[a, b, c, d]))
+        }
+
+        ("re", &[Value::F32x2(z)]) => Value::Real(z.x),
+        ("im", &[Value::F32x2(z)]) => Value::Real(z.y),
+        ("abs", &[Value::F32x2(z)]) => Value::Real(complex(z).norm()),
+        ("arg", &[Value::F32x2(z)]) => Value::Real(complex(z).arg()),
+        ("conj", &[Value::F32x2(z)]) => complex_value(complex(z).conj()),
+        ("cexp", &[Value::F32x2(z)]) => complex_value(complex(z).exp()),
+        ("clog", &[Value::F32x2(z)]) => complex_value(complex(z).ln()),
+        ("csqrt", &[Value::F32x2(z)]) => complex_value(complex(z).sqrt()),
+        ("polar", &[Value::Real(r), Value::Real(theta)]) => {
+            complex_value(Complex::from_polar(r, theta))
+        }
+        ("cpow", &[Value::F32x2(z), Value::Int(n)]) => complex_value(complex(z).powi(n)),
+
+        // `Quaternion::new` takes the real part first too
+        ("quat", &[Value::Real(w), Value::Real(x), Value::Real(y), Value::Real(z)]) => {
+            Value::F32x4(Quaternion::new(w, x, y, z).coords)
+        }
+        ("conjugate", &[Value::F32x4(q)]) => Value::F32x4(Quaternion::from(q).conjugate().coords),
+        ("normalize", &[Value::F32x4(q)]) => Value::F32x4(Quaternion::from(q).normalize().coords),
+        ("quat_from_axis_angle", &[Value::F32x3(axis), Value::Real(angle)]) => {
+            let q = UnitQuaternion::from_axis_angle(&Unit::new_normalize(axis), angle);
+
+            Value::F32x4(q.coords)
+        }
+        // the quaternions aren't normalized, like they aren't at runtime
+        ("rotate", &[Value::F32x4(q), Value::F32x3(v)]) => {
+            Value::F32x3(UnitQuaternion::new_unchecked(Quaternion::from(q)) * v)
+        }
+        ("slerp", &[Value::F32x4(a), Value::F32x4(b), Value::Real(t)]) => {
+            let a = UnitQuaternion::new_unchecked(Quaternion::from(a));
+            let b = UnitQuaternion::new_unchecked(Quaternion::from(b));
+
+            // the epsilon of `slerp` of std
+            Value::F32x4(a.try_slerp(&b, t, 1e-6).unwrap_or(a).coords)
+        }
+
+        // unlike `clamp` of std, it doesn't panic if `lo > hi`
+        ("clamp", &[Value::Real(x), Value::Real(lo), Value::Real(hi)]) => {
+            Value::Real(x.max(lo).min(hi))
+        }
+        ("clamp", &[Value::Int(x), Value::Int(lo), Value::Int(hi)]) => {
+            Value::Int(x.max(lo).min(hi))
+        }
+
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+fn complex(value: Vector2<f32>) -> Complex<f32> {
+    Complex::new(value.x, value.y)
+}
+
+fn complex_value(value: Complex<f32>) -> Value {
+    Value::F32x2(Vector2::new(value.re, value.im))
+}
+
+/// a real operand of the complex arithmetic is `(r, 0)`, as it is at runtime
+fn complex_operand(value: Value) -> Complex<f32> {
+    match value {
+        Value::Real(re) => Complex::new(re, 0.0),
+        value => complex(value.f32x2()),
+    }
+}
+
+/// puts a matrix into the top left corner of the identity 4x4 matrix,
+/// it keeps the determinant and the inverse of the original one
+fn mat4(value: Value) -> Matrix4<f32> {
+    let mut mat = Matrix4::identity();
+
+    match value {
+        Value::F32x2x2(m) => mat.fixed_view_mut::<2, 2>(0, 0).copy_from(&m),
+        Value::F32x3x3(m) => mat.fixed_view_mut::<3, 3>(0, 0).copy_from(&m),
+        Value::F32x4x4(m) => mat = m,
+
+        _ => unreachable!(),
+    }
+
+    mat
+}
+
+fn mat_value(mat: Matrix4<f32>, ty: MatType) -> Value {
+    match ty {
+        MatType::Mat2 => Value::F32x2x2(mat.fixed_view::<2, 2>(0, 0).clone_owned()),
+        MatType::Mat3 => Value::F32x3x3(mat.fixed_view::<3, 3>(0, 0).clone_owned()),
+        MatType::Mat4 => Value::F32x4x4(mat),
+    }
+}
+
+fn vec4(value: Value) -> Vector4<f32> {
+    match value {
+        Value::F32x2(v) => Vector4::new(v.x, v.y, 0., 0.),
+        Value::F32x3(v) => Vector4::new(v.x, v.y, v.z, 0.),
+        Value::F32x4(v) => v,
+
+        _ => unreachable!(),
+    }
+}
+
+fn vec_value(vec: Vector4<f32>, ty: VecType) -> Value {
+    match ty {
+        VecType::Vec2 => Value::F32x2(vec.xy()),
+        VecType::Vec3 => Value::F32x3(vec.xyz()),
+        VecType::Vec4 => Value::F32x4(vec),
     }
 }
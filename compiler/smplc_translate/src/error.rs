@@ -0,0 +1,59 @@
+use std::fmt;
+
+use smplc_thir::Span;
+
+use crate::const_eval::{MAX_DEPTH, MAX_STEPS};
+
+/// an error of the evaluation of a constant or a static,
+/// `span` points to its value
+pub struct ConstEvalError<'source> {
+    pub kind: ConstEvalErrorKind<'source>,
+    pub span: Span,
+}
+
+pub enum ConstEvalErrorKind<'source> {
+    NonConstCall(&'source str),
+    CyclicConstant(&'source str),
+    StepLimit,
+    RecursionLimit,
+    DivisionByZero,
+    IndexOutOfBounds { index: i32, len: usize },
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for ConstEvalErrorKind<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstEvalErrorKind::NonConstCall(name) => {
+                write!(
+                    f,
+                    "function \"{name}\" can't be called at compile time, it isn't a const fn"
+                )
+            }
+
+            ConstEvalErrorKind::CyclicConstant(id) => {
+                write!(f, "constant \"{id}\" is used before its value is evaluated")
+            }
+
+            ConstEvalErrorKind::StepLimit => {
+                write!(f, "constant evaluation takes more than {MAX_STEPS} steps")
+            }
+
+            ConstEvalErrorKind::RecursionLimit => {
+                write!(f, "constant evaluation nests more than {MAX_DEPTH} calls")
+            }
+
+            ConstEvalErrorKind::DivisionByZero => {
+                write!(f, "division by zero in constant evaluation")
+            }
+
+            ConstEvalErrorKind::IndexOutOfBounds { index, len } => {
+                write!(f, "index {index} is out of bounds of length {len}")
+            }
+
+            ConstEvalErrorKind::Unsupported(what) => {
+                write!(f, "{what} can't be evaluated at compile time")
+            }
+        }
+    }
+}
@@ -1,15 +1,21 @@
 use nalgebra::Vector2;
 
-use comet_ir::{Atom, ControlFlow, Id, Sequental, Type, UnOp, Value};
-use smplc_thir::Symbols;
-use smplc_thir::{self as thir, VecOp};
+use comet_ir::{Atom, ControlFlow, Id, Phi, Sequental, Type, UnOp, Value};
+use smplc_thir::{self as thir, MatOp, VecOp};
+use smplc_thir::{Symbols, VarId};
 
 use crate::{
-    call::translate_call, idents::BaseIdents, logic::translate_logic, translator::Translator,
+    aggregate::{translate_field, translate_index, translate_repeat},
+    call::translate_call,
+    idents::BaseIdents,
+    logic::translate_logic,
+    statement::join_branches,
+    translator::Translator,
+    Translate,
 };
 
 pub fn translate_expr<'source>(
-    expr: thir::Expr,
+    expr: thir::Expr<'source>,
     translator: &mut Translator<'source>,
     idents: &mut BaseIdents,
     symbols: &Symbols<'source>,
@@ -52,30 +58,37 @@ pub fn translate_expr<'source>(
         }
 
         thir::Expr::Binary { lhs, op, rhs } => {
-            let mut lhs = translate_expr(*lhs, translator, idents, symbols);
-            let mut rhs = translate_expr(*rhs, translator, idents, symbols);
+            // a literal lane is checked by the typecheck
+            let lane_len = match (op, &*rhs) {
+                (_, thir::Expr::Atom(thir::Atom::Literal(_))) => None,
+                (thir::BinOp::Lane(ty), _) => Some(ty.dims() as usize),
+                _ => None,
+            };
 
-            let result = idents.next();
+            let lhs = translate_expr(*lhs, translator, idents, symbols);
+            let rhs = translate_expr(*rhs, translator, idents, symbols);
 
-            if let thir::BinOp::Vec(VecOp::LeftMul, _) = op {
-                std::mem::swap(&mut lhs, &mut rhs);
+            if let Some(len) = lane_len {
+                translator.code.push(Sequental::CheckIndex {
+                    index: Atom::Id(rhs),
+                    len,
+                });
             }
 
-            translator.code.push(Sequental::Binary {
-                dst: result,
-                op: op.into(),
-                lhs: Atom::Id(lhs),
-                rhs: Atom::Id(rhs),
-            });
-
-            result
+            translate_binary(translator, idents, op, lhs, rhs)
         }
 
-        thir::Expr::Unary {
-            op: thir::UnOp::Neg(ty),
-            rhs,
-        } => {
-            let ty = ty.into();
+        thir::Expr::Unary { op, rhs } => {
+            let op = match op {
+                thir::UnOp::Neg(ty) => UnOp::Neg(ty.into()),
+                thir::UnOp::BitNot => UnOp::BitNot,
+                thir::UnOp::Convert(conversion) => UnOp::Convert(conversion.into()),
+                thir::UnOp::Transpose(ty) => UnOp::Transpose(ty.dims()),
+                thir::UnOp::Determinant(ty) => UnOp::Determinant(ty.dims()),
+                thir::UnOp::Inverse(ty) => UnOp::Inverse(ty.dims()),
+
+                thir::UnOp::Not => unreachable!(),
+            };
 
             let rhs = translate_expr(*rhs, translator, idents, symbols);
 
@@ -83,7 +96,7 @@ pub fn translate_expr<'source>(
 
             translator.code.push(Sequental::Unary {
                 dst: result,
-                op: UnOp::Neg(ty),
+                op,
                 operand: Atom::Id(rhs),
             });
 
@@ -104,6 +117,152 @@ pub fn translate_expr<'source>(
             result
         }
 
+        thir::Expr::Index {
+            lhs,
+            index,
+            ty,
+            len,
+        } => translate_index(translator, idents, symbols, *lhs, *index, ty, len),
+
+        thir::Expr::Field { lhs, ty, index } => {
+            let result = idents.next();
+
+            translate_field(translator, idents, symbols, result, *lhs, ty, index);
+
+            result
+        }
+
+        thir::Expr::If {
+            cond,
+            body,
+            else_body,
+        } => {
+            let true_label = translator.next_label();
+            let false_label = translator.next_label();
+            let end_label = translator.next_label();
+
+            translate_logic(*cond, translator, idents, symbols, true_label, false_label);
+
+            let mut then_idents = BaseIdents::with_parent(idents);
+            let mut else_idents = BaseIdents::with_parent(idents);
+
+            translator.code.label(true_label);
+            let then_value = translate_block(body, translator, &mut then_idents, symbols);
+
+            translator.code.push(ControlFlow::Goto { label: end_label });
+
+            translator.code.label(false_label);
+            let else_value = translate_block(else_body, translator, &mut else_idents, symbols);
+
+            translator.code.label(end_label);
+
+            join_branches(
+                translator,
+                idents,
+                vec![then_idents.variables, else_idents.variables],
+            );
+
+            // a diverging branch doesn't pass a value, the typecheck ensures that one of them does
+            match (then_value, else_value) {
+                (Some(then_value), Some(else_value)) => {
+                    let result = idents.next();
+
+                    translator.code.push(Phi {
+                        dst: result,
+                        branches: vec![then_value, else_value],
+                    });
+
+                    result
+                }
+
+                (Some(value), None) | (None, Some(value)) => value,
+
+                (None, None) => unreachable!(),
+            }
+        }
+
+        thir::Expr::Block(block) => translate_block(block, translator, idents, symbols).unwrap(),
+
+        thir::Expr::Struct { ty, fields } => {
+            let elems = fields
+                .into_iter()
+                .zip(ty.fields.iter())
+                .map(|(value, field)| {
+                    let value = translate_expr(value, translator, idents, symbols);
+
+                    (Atom::Id(value), Type::from(&field.ty))
+                })
+                .collect();
+
+            let result = idents.next();
+
+            translator.code.push(Sequental::Aggregate {
+                dst: result,
+                ty: Type::from(thir::Type::Struct(ty)),
+                elems,
+            });
+
+            result
+        }
+
+        thir::Expr::Tuple(elems) => {
+            let elems = elems
+                .into_iter()
+                .map(|(elem, ty)| {
+                    let elem = translate_expr(elem, translator, idents, symbols);
+
+                    (Atom::Id(elem), Type::from(&ty))
+                })
+                .collect::<Vec<_>>();
+
+            let result = idents.next();
+
+            translator.code.push(Sequental::Aggregate {
+                dst: result,
+                ty: Type::Aggregate(elems.iter().map(|(_, ty)| ty.slots()).sum()),
+                elems,
+            });
+
+            result
+        }
+
+        thir::Expr::Array { elems, ty } => {
+            let elems = elems
+                .into_iter()
+                .map(|elem| translate_expr(elem, translator, idents, symbols))
+                .map(|elem| (Atom::Id(elem), Type::from(&ty)))
+                .collect::<Vec<_>>();
+
+            let result = idents.next();
+
+            translator.code.push(Sequental::Aggregate {
+                dst: result,
+                ty: Type::Aggregate(Type::from(&ty).slots() * elems.len()),
+                elems,
+            });
+
+            result
+        }
+
+        thir::Expr::ArrayRepeat { value, len, ty } => {
+            translate_repeat(translator, idents, symbols, *value, ty, len)
+        }
+
+        thir::Expr::Len(len) => {
+            let result = idents.next();
+
+            // the lengths are limited by the semcheck
+            let len = i32::try_from(len).unwrap();
+
+            translator.code.push(Sequental::Assign {
+                dst: result,
+                ty: Type::Int,
+                value: Atom::Value(Value::Int(len)),
+            });
+
+            result
+        }
+
         thir::Expr::Call { fun, args } => {
             let result = idents.next();
 
@@ -112,13 +271,15 @@ pub fn translate_expr<'source>(
             result
         }
 
+        thir::Expr::Print { .. } => unreachable!(),
+
         thir::Expr::Atom(atom) => {
-            let value = translate_atom(atom, idents);
+            let value = translate_atom(atom, translator, idents);
 
             let result = idents.next();
 
             let ty = match atom {
-                thir::Atom::Var(id) => symbols.variables[id].ty.into(),
+                thir::Atom::Var(id) => (&symbols.variables[id].ty).into(),
                 thir::Atom::Literal(literal) => thir::Type::from(literal.ty).into(),
             };
 
@@ -133,9 +294,80 @@ pub fn translate_expr<'source>(
     }
 }
 
-pub fn translate_atom(atom: thir::Atom, idents: &mut BaseIdents) -> Atom {
+fn translate_block<'source>(
+    block: thir::BlockExpr<'source>,
+    translator: &mut Translator<'source>,
+    idents: &mut BaseIdents,
+    symbols: &Symbols<'source>,
+) -> Option<Id> {
+    for statement in block.statements {
+        statement.translate(translator, idents, symbols);
+    }
+
+    block
+        .value
+        .map(|value| translate_expr(*value, translator, idents, symbols))
+}
+
+/// the operands are already translated, logical operations are translated with jumps
+pub fn translate_binary(
+    translator: &mut Translator,
+    idents: &mut BaseIdents,
+    op: thir::BinOp,
+    mut lhs: Id,
+    mut rhs: Id,
+) -> Id {
+    let result = idents.next();
+
+    if let thir::BinOp::Vec(VecOp::LeftMul, _) | thir::BinOp::Mat(MatOp::LeftMul, _) = op {
+        std::mem::swap(&mut lhs, &mut rhs);
+    }
+
+    translator.code.push(Sequental::Binary {
+        dst: result,
+        op: op.into(),
+        lhs: Atom::Id(lhs),
+        rhs: Atom::Id(rhs),
+    });
+
+    result
+}
+
+/// statics are loaded on every read, since any call could have changed them
+pub fn read_var(translator: &mut Translator, idents: &mut BaseIdents, var: VarId) -> Id {
+    let Some(&(global, ty)) = translator.statics.get(&var) else {
+        return idents.get(var);
+    };
+
+    let dst = idents.next();
+
+    translator
+        .code
+        .push(Sequental::LoadGlobal { dst, ty, global });
+
+    dst
+}
+
+pub fn write_var(translator: &mut Translator, idents: &mut BaseIdents, var: VarId, id: Id) {
+    let Some(&(global, ty)) = translator.statics.get(&var) else {
+        idents.set(var, id);
+
+        return;
+    };
+
+    translator.code.push(Sequental::StoreGlobal {
+        global,
+        value: (Atom::Id(id), ty),
+    });
+}
+
+pub fn translate_atom(
+    atom: thir::Atom,
+    translator: &mut Translator,
+    idents: &mut BaseIdents,
+) -> Atom {
     match atom {
-        thir::Atom::Var(var) => Atom::Id(idents.get(var)),
+        thir::Atom::Var(var) => Atom::Id(read_var(translator, idents, var)),
         thir::Atom::Literal(literal) => Atom::Value(match literal.ty {
             thir::LiteralType::Complex => Value::F32x2(Vector2::new(
                 0.0,
@@ -144,6 +376,7 @@ pub fn translate_atom(atom: thir::Atom, idents: &mut BaseIdents) -> Atom {
             thir::LiteralType::Real => Value::Real(parse_int::parse(literal.value).unwrap()),
             thir::LiteralType::Int => Value::Int(parse_int::parse(literal.value).unwrap()),
             thir::LiteralType::Bool => Value::Int(if literal.value == "true" { 1 } else { 0 }),
+            thir::LiteralType::String => unreachable!(),
         }),
     }
 }
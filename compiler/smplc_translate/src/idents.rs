@@ -42,7 +42,7 @@ impl<'p> BaseIdents<'p> {
     }
 
     pub fn next(&self) -> Id {
-        if let Some(parent) = self.parent.as_deref() {
+        if let Some(parent) = self.parent {
             parent.next()
         } else {
             let id = Id::new(self.counter.get());
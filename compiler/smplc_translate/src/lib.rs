@@ -1,3 +1,6 @@
+pub mod error;
+
+mod aggregate;
 mod call;
 mod const_eval;
 mod expr;
@@ -6,37 +9,66 @@ mod logic;
 mod statement;
 mod translator;
 
-use std::mem;
+#[cfg(test)]
+mod tests;
+
+use std::{collections::BTreeMap, mem};
 
-use comet_ir::{FunId, FunctionBody, LIR};
-use smplc_thir::{FunData, Symbols, THIR};
+use comet_ir::{FunId, FunctionBody, Global, Value, LIR};
+use smplc_thir as thir;
+use smplc_thir::{Symbols, Type, THIR};
 
-use const_eval::const_eval;
+use const_eval::ConstEval;
+use error::ConstEvalError;
 use idents::BaseIdents;
 use translator::Translator;
 
-pub fn translate<'source>(thir: THIR<'source>) -> LIR<'source> {
+pub fn translate<'source>(thir: THIR<'source>) -> Result<LIR<'source>, ConstEvalError<'source>> {
     let THIR {
         symbols,
         functions,
         constants,
+        statics,
     } = thir;
 
     let mut translator = Translator::default();
     let mut idents = BaseIdents::default();
 
-    for constant in constants {
-        let value = const_eval(constant.value, &idents);
+    let mut const_eval = ConstEval::new(&symbols, &functions);
 
-        let id = idents.next();
+    for (var, value) in eval_constants(&mut const_eval, &constants)? {
+        let id = idents.add(var);
 
         idents.constants.insert(id, value);
     }
 
+    let mut globals = BTreeMap::new();
+
+    for static_ in statics {
+        let value = const_eval
+            .eval(&static_.value)
+            .map_err(|kind| ConstEvalError {
+                kind,
+                span: static_.span,
+            })?;
+
+        let global = Global::new(globals.len());
+
+        globals.insert(global, value);
+
+        translator
+            .statics
+            .insert(static_.id, (global, (&static_.ty).into()));
+    }
+
     let bodies = functions
         .into_iter()
         .map(|fun| {
-            let args = fun.args.into_iter().map(|var| idents.add(var)).collect();
+            let args = fun
+                .args
+                .into_iter()
+                .map(|var| (idents.add(var), (&symbols.variables[var].ty).into()))
+                .collect();
 
             fun.body.translate(&mut translator, &mut idents, &symbols);
 
@@ -45,17 +77,50 @@ pub fn translate<'source>(thir: THIR<'source>) -> LIR<'source> {
                 code: mem::take(&mut translator.code),
             };
 
-            let id = fun_id(&symbols.functions[fun.id]);
+            let id = fun_id(&symbols, fun.id);
 
             (id, function)
         })
         .collect();
 
-    LIR {
+    Ok(LIR {
         bodies,
         constants: idents.constants,
+        globals,
         labels: translator.labels,
-    }
+    })
+}
+
+/// the values of the constants in the order they are declared,
+/// the functions and the statics aren't translated
+pub fn constant_values<'source>(
+    thir: &THIR<'source>,
+) -> Result<Vec<(thir::VarId, Value)>, ConstEvalError<'source>> {
+    let mut const_eval = ConstEval::new(&thir.symbols, &thir.functions);
+
+    eval_constants(&mut const_eval, &thir.constants)
+}
+
+/// a constant can use the ones declared before it
+fn eval_constants<'source>(
+    const_eval: &mut ConstEval<'_, 'source>,
+    constants: &[thir::Constant<'source>],
+) -> Result<Vec<(thir::VarId, Value)>, ConstEvalError<'source>> {
+    constants
+        .iter()
+        .map(|constant| {
+            let value = const_eval
+                .eval(&constant.value)
+                .map_err(|kind| ConstEvalError {
+                    kind,
+                    span: constant.span,
+                })?;
+
+            const_eval.define(constant.id, value);
+
+            Ok((constant.id, value))
+        })
+        .collect()
 }
 
 trait Translate<'source> {
@@ -67,6 +132,45 @@ trait Translate<'source> {
     );
 }
 
-pub fn fun_id<'source>(data: &FunData<'source>) -> FunId<'source> {
-    FunId::new(data.id.0, data.ret_ty.map(Into::into))
+/// overloaded functions are mangled as `name@arg@arg`, arrays as `elem#len`
+pub fn fun_id<'source>(symbols: &Symbols<'source>, fun: thir::FunId) -> FunId<'source> {
+    let data = &symbols.functions[fun];
+    let ret_ty = data.ret_ty.as_ref().map(Into::into);
+
+    // the std functions keep the symbols they're defined with in the std,
+    // a user overload of a std name is renamed instead
+    let overloaded = symbols.functions.iter().any(|(other, other_data)| {
+        other != fun && other_data.id.0 == data.id.0 && (other_data.is_std() || !data.is_std())
+    });
+
+    if !overloaded {
+        return FunId::new(data.id.0, ret_ty);
+    }
+
+    let name = data
+        .args_types
+        .iter()
+        .fold(data.id.0.to_owned(), |name, ty| {
+            format!("{name}@{}", mangle(ty))
+        });
+
+    FunId::new(name, ret_ty)
+}
+
+/// the name of the type in a symbol, it's a valid label of the assembler:
+/// `[int; 3]` is `int#3`, `(int, [real; 2])` is `T2.int.real#2~`
+fn mangle(ty: &Type) -> String {
+    match ty {
+        Type::Array(elem, len) => format!("{}#{len}", mangle(elem)),
+        Type::Tuple(elems) => {
+            let name = format!("T{}", elems.len());
+
+            let name = elems
+                .iter()
+                .fold(name, |name, elem| format!("{name}.{}", mangle(elem)));
+
+            format!("{name}~")
+        }
+        ty => ty.to_string(),
+    }
 }
@@ -10,7 +10,7 @@ use crate::{
 };
 
 pub fn translate_logic<'source>(
-    expr: thir::Expr,
+    expr: thir::Expr<'source>,
     translator: &mut Translator<'source>,
     idents: &mut BaseIdents,
     symbols: &Symbols<'source>,
@@ -90,8 +90,26 @@ pub fn translate_logic<'source>(
                 .push(ControlFlow::Goto { label: false_label });
         }
 
+        expr @ (thir::Expr::Index { .. }
+        | thir::Expr::Field { .. }
+        | thir::Expr::If { .. }
+        | thir::Expr::Block(_)) => {
+            let result = translate_expr(expr, translator, idents, symbols);
+
+            translator.code.push(ControlFlow::If {
+                op: BinOp::IntRel(RelOp::Eq),
+                lhs: Atom::Id(result),
+                rhs: Atom::Value(Value::Int(1)),
+                label: true_label,
+            });
+
+            translator
+                .code
+                .push(ControlFlow::Goto { label: false_label });
+        }
+
         thir::Expr::Atom(atom) => {
-            let value = translate_atom(atom, idents);
+            let value = translate_atom(atom, translator, idents);
 
             translator.code.push(ControlFlow::If {
                 op: BinOp::IntRel(RelOp::Eq),
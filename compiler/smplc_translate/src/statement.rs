@@ -1,15 +1,21 @@
 use std::{
+    cmp::Ordering,
     collections::{HashMap, HashSet},
     mem,
 };
 
-use comet_ir::{Atom, ControlFlow, Id, Phi};
-use smplc_thir::{self as thir, Block};
+use comet_ir::{ArithmOp, Atom, BinOp, ControlFlow, Id, Label, Phi, RelOp, Sequental, Value};
+use smplc_thir as thir;
 use smplc_thir::{Symbols, VarId};
 
 use crate::{
-    call::translate_call, expr::translate_expr, idents::BaseIdents, logic::translate_logic,
-    translator::Translator, Translate,
+    aggregate::{translate_destructure, translate_store},
+    call::{translate_call, translate_print},
+    expr::{translate_expr, write_var},
+    idents::BaseIdents,
+    logic::translate_logic,
+    translator::Translator,
+    Translate,
 };
 
 impl<'source> Translate<'source> for thir::Statement<'source> {
@@ -32,13 +38,25 @@ impl<'source> Translate<'source> for thir::Statement<'source> {
             thir::Statement::While(while_statement) => {
                 while_statement.translate(translator, idents, symbols);
             }
-            thir::Statement::Break => {
-                let (_, end_label) = translator.loop_labels();
+            thir::Statement::DoWhile(do_while_statement) => {
+                do_while_statement.translate(translator, idents, symbols);
+            }
+            thir::Statement::Loop(loop_statement) => {
+                loop_statement.translate(translator, idents, symbols);
+            }
+            thir::Statement::For(for_statement) => {
+                for_statement.translate(translator, idents, symbols);
+            }
+            thir::Statement::Match(match_statement) => {
+                match_statement.translate(translator, idents, symbols);
+            }
+            thir::Statement::Break(label) => {
+                let (_, end_label) = translator.loop_labels(label);
 
                 translator.code.push(ControlFlow::Goto { label: end_label });
             }
-            thir::Statement::Continue => {
-                let (start_label, _) = translator.loop_labels();
+            thir::Statement::Continue(label) => {
+                let (start_label, _) = translator.loop_labels(label);
 
                 translator
                     .code
@@ -59,13 +77,25 @@ impl<'source> Translate<'source> for thir::ExprStatement<'source> {
             thir::ExprStatement::Assign { var, rhs } => {
                 let result_id = translate_expr(rhs, translator, idents, symbols);
 
-                idents.set(var, result_id);
+                write_var(translator, idents, var, result_id);
+            }
+
+            thir::ExprStatement::Store { place, op, rhs } => {
+                translate_store(translator, idents, symbols, place, op, rhs);
+            }
+
+            thir::ExprStatement::Destructure { vars, rhs } => {
+                translate_destructure(translator, idents, symbols, vars, rhs);
             }
 
             thir::ExprStatement::Expr(thir::Expr::Call { fun, args }) => {
                 translate_call(translator, idents, symbols, None, fun, args);
             }
 
+            thir::ExprStatement::Expr(thir::Expr::Print { pieces, args }) => {
+                translate_print(translator, idents, symbols, pieces, args);
+            }
+
             _ => {}
         }
     }
@@ -106,43 +136,11 @@ impl<'source> Translate<'source> for thir::IfStatement<'source> {
 
             translator.code.label(end_label);
 
-            let mut updated = HashSet::new();
-
-            let then_idents = then_idents.variables;
-            let else_idents = else_idents.variables;
-
-            for &var in then_idents.keys().chain(else_idents.keys()) {
-                if updated.contains(&var) {
-                    continue;
-                }
-
-                updated.insert(var);
-
-                match (
-                    idents.try_get(var),
-                    then_idents.get(&var),
-                    else_idents.get(&var),
-                ) {
-                    (None, Some(&id), None) | (None, None, Some(&id)) => {
-                        idents.set(var, id);
-                    }
-
-                    (Some(a), Some(&b), None)
-                    | (Some(a), None, Some(&b))
-                    | (_, Some(&a), Some(&b)) => {
-                        let new_id = idents.next();
-
-                        translator.code.push(Phi {
-                            dst: new_id,
-                            branches: vec![a, b],
-                        });
-
-                        idents.set(var, new_id);
-                    }
-
-                    _ => {}
-                }
-            }
+            join_branches(
+                translator,
+                idents,
+                vec![then_idents.variables, else_idents.variables],
+            );
         } else {
             let true_label = translator.next_label();
 
@@ -178,6 +176,176 @@ impl<'source> Translate<'source> for thir::IfStatement<'source> {
     }
 }
 
+/// merges the variables updated in the branches of an `if` or a `match`,
+/// the code must be at the label where the branches meet
+pub fn join_branches(
+    translator: &mut Translator,
+    idents: &mut BaseIdents,
+    branches: Vec<HashMap<VarId, Id>>,
+) {
+    let mut updated = HashSet::new();
+
+    for &var in branches.iter().flat_map(HashMap::keys) {
+        if updated.contains(&var) {
+            continue;
+        }
+
+        updated.insert(var);
+
+        let prev = idents.try_get(var);
+
+        // a branch that doesn't update the variable passes the previous value
+        let ids: Vec<Id> = branches
+            .iter()
+            .filter_map(|branch| branch.get(&var).copied().or(prev))
+            .collect();
+
+        if let [id] = ids[..] {
+            idents.set(var, id);
+        } else {
+            let new_id = idents.next();
+
+            translator.code.push(Phi {
+                dst: new_id,
+                branches: ids,
+            });
+
+            idents.set(var, new_id);
+        }
+    }
+}
+
+impl<'source> Translate<'source> for thir::MatchStatement<'source> {
+    fn translate(
+        self,
+        translator: &mut Translator<'source>,
+        idents: &mut BaseIdents,
+        symbols: &Symbols<'source>,
+    ) {
+        let value = translate_expr(self.value, translator, idents, symbols);
+
+        let end_label = translator.next_label();
+
+        let arm_labels: Vec<Label> = self.arms.iter().map(|_| translator.next_label()).collect();
+
+        let default = self
+            .arms
+            .iter()
+            .zip(&arm_labels)
+            .find(|(arm, _)| arm.patterns.contains(&thir::Pattern::Wildcard))
+            .map_or(end_label, |(_, &label)| label);
+
+        let mut ranges: Vec<(i32, i32, Label)> = self
+            .arms
+            .iter()
+            .zip(&arm_labels)
+            .flat_map(|(arm, &label)| {
+                arm.patterns
+                    .iter()
+                    .filter_map(move |pattern| match *pattern {
+                        thir::Pattern::Range(start, end) => Some((start, end, label)),
+                        thir::Pattern::Wildcard => None,
+                    })
+            })
+            .collect();
+
+        ranges.sort_by_key(|&(start, ..)| start);
+
+        translate_switch(translator, value, &ranges, default);
+
+        let arms_count = self.arms.len();
+        let mut branches = Vec::new();
+
+        for (n, (arm, label)) in self.arms.into_iter().zip(arm_labels).enumerate() {
+            let mut arm_idents = BaseIdents::with_parent(idents);
+
+            translator.code.label(label);
+            arm.body.translate(translator, &mut arm_idents, symbols);
+
+            if n + 1 < arms_count {
+                translator.code.push(ControlFlow::Goto { label: end_label });
+            }
+
+            branches.push(arm_idents.variables);
+        }
+
+        translator.code.label(end_label);
+
+        join_branches(translator, idents, branches);
+    }
+}
+
+/// Goes to the label of the range containing `value` or to `default`.
+/// A jump table is used for enough values with few gaps between them,
+/// otherwise the ranges are compared one by one
+fn translate_switch(
+    translator: &mut Translator,
+    value: Id,
+    ranges: &[(i32, i32, Label)],
+    default: Label,
+) {
+    const MIN_TABLE_RANGES: usize = 3;
+    const MAX_TABLE_LEN: i64 = 256;
+
+    if let (Some(&(min, ..)), Some(&(.., max, _))) = (ranges.first(), ranges.last()) {
+        let len = max as i64 - min as i64 + 1;
+
+        let covered: i64 = ranges
+            .iter()
+            .map(|&(start, end, _)| end as i64 - start as i64 + 1)
+            .sum();
+
+        if ranges.len() >= MIN_TABLE_RANGES && len <= MAX_TABLE_LEN && covered * 2 >= len {
+            let targets = (min..=max)
+                .map(|value| {
+                    ranges
+                        .iter()
+                        .find(|&&(start, end, _)| (start..=end).contains(&value))
+                        .map_or(default, |&(.., label)| label)
+                })
+                .collect();
+
+            translator.code.push(ControlFlow::Switch {
+                value: Atom::Id(value),
+                min,
+                targets,
+                default,
+            });
+
+            return;
+        }
+    }
+
+    for &(start, end, label) in ranges {
+        if start == end {
+            translator.code.push(ControlFlow::If {
+                lhs: Atom::Id(value),
+                op: BinOp::IntRel(RelOp::Eq),
+                rhs: Atom::Value(Value::Int(start)),
+                label,
+            });
+
+            continue;
+        }
+
+        // the ranges are sorted, so the value below this one is below the next ones too
+        translator.code.push(ControlFlow::If {
+            lhs: Atom::Id(value),
+            op: BinOp::IntRel(RelOp::Lt),
+            rhs: Atom::Value(Value::Int(start)),
+            label: default,
+        });
+        translator.code.push(ControlFlow::If {
+            lhs: Atom::Id(value),
+            op: BinOp::IntRel(RelOp::Le),
+            rhs: Atom::Value(Value::Int(end)),
+            label,
+        });
+    }
+
+    translator.code.push(ControlFlow::Goto { label: default });
+}
+
 impl<'source> Translate<'source> for thir::ReturnStatement<'source> {
     fn translate(
         self,
@@ -188,7 +356,12 @@ impl<'source> Translate<'source> for thir::ReturnStatement<'source> {
         let value = self
             .value
             .map(|expr| Atom::Id(translate_expr(expr, translator, idents, symbols)))
-            .map(|value| (symbols.functions[self.fun].ret_ty.unwrap().into(), value));
+            .map(|value| {
+                (
+                    symbols.functions[self.fun].ret_ty.as_ref().unwrap().into(),
+                    value,
+                )
+            });
 
         translator.code.push(ControlFlow::Return { value });
     }
@@ -204,9 +377,9 @@ impl<'source> Translate<'source> for thir::WhileStatement<'source> {
         let prev_code = mem::take(&mut translator.code);
 
         let mut phis = HashMap::default();
-        consider_phis(&self.body, idents, &mut phis);
+        consider_phis(&self.body.statements, idents, &mut phis);
 
-        let (start_label, end_label) = translator.join_loop();
+        let (start_label, end_label) = translator.join_loop(self.label);
 
         let body_idents = {
             let mut idents = BaseIdents::with_parent(idents);
@@ -258,38 +431,453 @@ impl<'source> Translate<'source> for thir::WhileStatement<'source> {
     }
 }
 
-fn consider_phis(block: &Block, idents: &mut BaseIdents, phis: &mut HashMap<VarId, Id>) {
-    for stmt in &block.statements {
+impl<'source> Translate<'source> for thir::DoWhileStatement<'source> {
+    fn translate(
+        self,
+        translator: &mut Translator<'source>,
+        idents: &mut BaseIdents,
+        symbols: &Symbols<'source>,
+    ) {
+        let prev_code = mem::take(&mut translator.code);
+
+        let mut phis = HashMap::default();
+        consider_phis(&self.body.statements, idents, &mut phis);
+        consider_expr_phis(&self.cond, idents, &mut phis);
+
+        // `continue` jumps to the condition
+        let (cond_label, end_label) = translator.join_loop(self.label);
+
+        let body_start = translator.next_label();
+
+        let body_idents = {
+            let mut idents = BaseIdents::with_parent(idents);
+
+            for (&var, &id) in &phis {
+                idents.set(var, id);
+            }
+
+            self.body.translate(translator, &mut idents, symbols);
+
+            translator.code.label(cond_label);
+
+            translate_logic(
+                self.cond,
+                translator,
+                &mut idents,
+                symbols,
+                body_start,
+                end_label,
+            );
+
+            idents
+        };
+
+        let body = mem::replace(&mut translator.code, prev_code);
+
+        for (&var, &id) in &phis {
+            translator.code.push(Phi {
+                dst: id,
+                branches: vec![idents.get(var), body_idents.get(var)],
+            });
+        }
+
+        let body_idents = body_idents.variables;
+
+        for (var, id) in phis {
+            idents.set(var, id);
+        }
+
+        // the variables initialized in the body can be used after the loop
+        for (var, id) in body_idents {
+            if idents.try_get(var).is_none() {
+                idents.set(var, id);
+            }
+        }
+
+        translator.code.label(body_start);
+        translator.code.append(body);
+        translator.code.label(end_label);
+
+        translator.exit_loop();
+    }
+}
+
+impl<'source> Translate<'source> for thir::LoopStatement<'source> {
+    fn translate(
+        self,
+        translator: &mut Translator<'source>,
+        idents: &mut BaseIdents,
+        symbols: &Symbols<'source>,
+    ) {
+        let prev_code = mem::take(&mut translator.code);
+
+        let mut phis = HashMap::default();
+        consider_phis(&self.body.statements, idents, &mut phis);
+
+        let (start_label, end_label) = translator.join_loop(self.label);
+
+        let body_idents = {
+            let mut idents = BaseIdents::with_parent(idents);
+
+            for (&var, &id) in &phis {
+                idents.set(var, id);
+            }
+
+            self.body.translate(translator, &mut idents, symbols);
+
+            idents
+        };
+
+        let body = mem::replace(&mut translator.code, prev_code);
+
+        for (&var, &id) in &phis {
+            translator.code.push(Phi {
+                dst: id,
+                branches: vec![idents.get(var), body_idents.get(var)],
+            });
+        }
+
+        for (var, id) in phis {
+            idents.set(var, id);
+        }
+
+        translator.code.label(start_label);
+        translator.code.append(body);
+        translator
+            .code
+            .push(ControlFlow::Goto { label: start_label });
+        translator.code.label(end_label);
+
+        translator.exit_loop();
+    }
+}
+
+impl<'source> Translate<'source> for thir::ForStatement<'source> {
+    fn translate(
+        self,
+        translator: &mut Translator<'source>,
+        idents: &mut BaseIdents,
+        symbols: &Symbols<'source>,
+    ) {
+        let ty = &symbols.variables[self.var].ty;
+
+        let start = translate_expr(self.start, translator, idents, symbols);
+        let end = translate_expr(self.end, translator, idents, symbols);
+
+        // the direction of a literal step is known, otherwise it's checked at runtime
+        let sign = match &self.step {
+            Some(step) => literal_sign(step),
+            None => Some(Ordering::Greater),
+        };
+
+        let step = match self.step {
+            Some(step) => Atom::Id(translate_expr(step, translator, idents, symbols)),
+            None => Atom::Value(Value::Int(1)),
+        };
+
+        idents.set(self.var, start);
+
+        let prev_code = mem::take(&mut translator.code);
+
+        let mut phis = HashMap::default();
+        phis.insert(self.var, idents.next());
+        consider_phis(&self.body.statements, idents, &mut phis);
+
+        // `continue` jumps to the step label, so the counter is always incremented
+        let (step_label, end_label) = translator.join_loop(self.label);
+
+        let body_idents = {
+            let mut idents = BaseIdents::with_parent(idents);
+
+            for (&var, &id) in &phis {
+                idents.set(var, id);
+            }
+
+            self.body.translate(translator, &mut idents, symbols);
+
+            translator.code.label(step_label);
+
+            let next = idents.next();
+
+            translator.code.push(Sequental::Binary {
+                dst: next,
+                op: match ty {
+                    thir::Type::Real => BinOp::Real(ArithmOp::Add),
+                    _ => BinOp::Int(ArithmOp::Add),
+                },
+                lhs: Atom::Id(idents.get(self.var)),
+                rhs: step,
+            });
+
+            idents.set(self.var, next);
+
+            idents
+        };
+
+        let body = mem::replace(&mut translator.code, prev_code);
+
+        for (&var, &id) in &phis {
+            translator.code.push(Phi {
+                dst: id,
+                branches: vec![idents.get(var), body_idents.get(var)],
+            });
+        }
+
+        for (var, id) in phis {
+            idents.set(var, id);
+        }
+
+        let cond_label = translator.next_label();
+        let body_start = translator.next_label();
+
+        let rel = |op| match ty {
+            thir::Type::Real => BinOp::RealRel(op),
+            _ => BinOp::IntRel(op),
+        };
+
+        let zero = match ty {
+            thir::Type::Real => Value::Real(0.),
+            _ => Value::Int(0),
+        };
+
+        // a positive step counts up to `end`, a negative one counts down to it
+        let (up, down) = match self.inclusive {
+            false => (rel(RelOp::Lt), rel(RelOp::Gt)),
+            true => (rel(RelOp::Le), rel(RelOp::Ge)),
+        };
+
+        let var = Atom::Id(idents.get(self.var));
+
+        translator.code.label(cond_label);
+
+        match sign {
+            Some(Ordering::Greater) => {
+                translator.code.push(ControlFlow::If {
+                    lhs: var,
+                    op: up,
+                    rhs: Atom::Id(end),
+                    label: body_start,
+                });
+            }
+
+            Some(Ordering::Less) => {
+                translator.code.push(ControlFlow::If {
+                    lhs: var,
+                    op: down,
+                    rhs: Atom::Id(end),
+                    label: body_start,
+                });
+            }
+
+            // a zero step runs no iterations
+            _ => {
+                let up_label = translator.next_label();
+                let down_label = translator.next_label();
+
+                translator.code.push(ControlFlow::If {
+                    lhs: step,
+                    op: rel(RelOp::Gt),
+                    rhs: Atom::Value(zero),
+                    label: up_label,
+                });
+                translator.code.push(ControlFlow::If {
+                    lhs: step,
+                    op: rel(RelOp::Lt),
+                    rhs: Atom::Value(zero),
+                    label: down_label,
+                });
+                translator.code.push(ControlFlow::Goto { label: end_label });
+
+                translator.code.label(up_label);
+                translator.code.push(ControlFlow::If {
+                    lhs: var,
+                    op: up,
+                    rhs: Atom::Id(end),
+                    label: body_start,
+                });
+                translator.code.push(ControlFlow::Goto { label: end_label });
+
+                translator.code.label(down_label);
+                translator.code.push(ControlFlow::If {
+                    lhs: var,
+                    op: down,
+                    rhs: Atom::Id(end),
+                    label: body_start,
+                });
+            }
+        }
+
+        translator.code.push(ControlFlow::Goto { label: end_label });
+
+        translator.code.label(body_start);
+        translator.code.append(body);
+        translator
+            .code
+            .push(ControlFlow::Goto { label: cond_label });
+        translator.code.label(end_label);
+
+        translator.exit_loop();
+    }
+}
+
+/// the sign of a literal, possibly negated
+fn literal_sign(expr: &thir::Expr) -> Option<Ordering> {
+    match expr {
+        thir::Expr::Unary {
+            op: thir::UnOp::Neg(_),
+            rhs,
+        } => literal_sign(rhs).map(Ordering::reverse),
+
+        thir::Expr::Atom(thir::Atom::Literal(literal)) => match literal.ty {
+            thir::LiteralType::Int => Some(parse_int::parse::<i32>(literal.value).ok()?.cmp(&0)),
+            thir::LiteralType::Real => parse_int::parse::<f32>(literal.value)
+                .ok()?
+                .partial_cmp(&0.),
+            _ => None,
+        },
+
+        _ => None,
+    }
+}
+
+fn consider_phis(
+    statements: &[thir::Statement],
+    idents: &mut BaseIdents,
+    phis: &mut HashMap<VarId, Id>,
+) {
+    for stmt in statements {
         match stmt {
-            &thir::Statement::Expr(thir::ExprStatement::Assign { var, .. }) => {
-                if phis.contains_key(&var) {
+            thir::Statement::Expr(
+                thir::ExprStatement::Assign { var, rhs }
+                | thir::ExprStatement::Store {
+                    place: thir::Place { var, .. },
+                    rhs,
+                    ..
+                },
+            ) => {
+                consider_expr_phis(rhs, idents, phis);
+
+                if phis.contains_key(var) {
                     continue;
                 }
 
-                if idents.try_get(var).is_some() {
+                if idents.try_get(*var).is_some() {
                     let id = idents.next();
-                    phis.insert(var, id);
+                    phis.insert(*var, id);
                 }
             }
 
+            thir::Statement::Expr(
+                thir::ExprStatement::Destructure { rhs: expr, .. }
+                | thir::ExprStatement::Expr(expr),
+            ) => {
+                consider_expr_phis(expr, idents, phis);
+            }
+
             thir::Statement::If(thir::IfStatement {
-                body, else_body, ..
+                cond,
+                body,
+                else_body,
             }) => {
-                consider_phis(body, idents, phis);
+                consider_expr_phis(cond, idents, phis);
+                consider_phis(&body.statements, idents, phis);
 
                 if let Some(else_body) = else_body {
-                    consider_phis(else_body, idents, phis);
+                    consider_phis(&else_body.statements, idents, phis);
                 }
             }
 
-            thir::Statement::While(thir::WhileStatement { body, .. }) => {
-                consider_phis(body, idents, phis);
+            thir::Statement::Return(thir::ReturnStatement {
+                value: Some(value), ..
+            }) => {
+                consider_expr_phis(value, idents, phis);
+            }
+
+            thir::Statement::While(thir::WhileStatement { body, .. })
+            | thir::Statement::Loop(thir::LoopStatement { body, .. })
+            | thir::Statement::For(thir::ForStatement { body, .. }) => {
+                consider_phis(&body.statements, idents, phis);
+            }
+
+            thir::Statement::DoWhile(thir::DoWhileStatement { body, cond, .. }) => {
+                consider_phis(&body.statements, idents, phis);
+                consider_expr_phis(cond, idents, phis);
+            }
+
+            thir::Statement::Match(thir::MatchStatement { value, arms }) => {
+                consider_expr_phis(value, idents, phis);
+
+                for arm in arms {
+                    consider_phis(&arm.body.statements, idents, phis);
+                }
             }
             _ => (),
         }
     }
 }
 
+fn consider_block_phis(
+    block: &thir::BlockExpr,
+    idents: &mut BaseIdents,
+    phis: &mut HashMap<VarId, Id>,
+) {
+    consider_phis(&block.statements, idents, phis);
+
+    if let Some(value) = &block.value {
+        consider_expr_phis(value, idents, phis);
+    }
+}
+
+/// variables can be assigned in the blocks of if-expressions and block expressions
+fn consider_expr_phis(expr: &thir::Expr, idents: &mut BaseIdents, phis: &mut HashMap<VarId, Id>) {
+    match expr {
+        thir::Expr::If {
+            cond,
+            body,
+            else_body,
+        } => {
+            consider_expr_phis(cond, idents, phis);
+
+            for block in [body, else_body] {
+                consider_block_phis(block, idents, phis);
+            }
+        }
+
+        thir::Expr::Block(block) => consider_block_phis(block, idents, phis),
+
+        thir::Expr::Binary { lhs, rhs, .. }
+        | thir::Expr::Index {
+            lhs, index: rhs, ..
+        } => {
+            consider_expr_phis(lhs, idents, phis);
+            consider_expr_phis(rhs, idents, phis);
+        }
+
+        thir::Expr::Unary { rhs, .. }
+        | thir::Expr::Swizzle { lhs: rhs, .. }
+        | thir::Expr::Field { lhs: rhs, .. }
+        | thir::Expr::ArrayRepeat { value: rhs, .. } => {
+            consider_expr_phis(rhs, idents, phis);
+        }
+
+        thir::Expr::Struct { fields: elems, .. }
+        | thir::Expr::Array { elems, .. }
+        | thir::Expr::Call { args: elems, .. } => {
+            for elem in elems {
+                consider_expr_phis(elem, idents, phis);
+            }
+        }
+
+        thir::Expr::Tuple(args) | thir::Expr::Print { args, .. } => {
+            for (arg, _) in args {
+                consider_expr_phis(arg, idents, phis);
+            }
+        }
+
+        thir::Expr::Len(_) | thir::Expr::Atom(_) => {}
+    }
+}
+
 impl<'source> Translate<'source> for thir::Block<'source> {
     fn translate(
         self,
@@ -0,0 +1,403 @@
+use smplc_lexer::Lexer;
+use smplc_parse::{parse, TokenStream};
+use smplc_semcheck::sem_check;
+use smplc_typecheck::typecheck;
+
+use crate::translate;
+
+/// the IR of a correct program or the message of the error of a constant evaluation
+fn translated(code: &str) -> Result<String, String> {
+    let token_stream = TokenStream::new(Lexer::new(code));
+    let ast = parse(token_stream).unwrap();
+
+    let (hir, errors, _) = sem_check(ast);
+    assert!(errors.is_empty(), "{errors:?}");
+
+    let Ok(thir) = typecheck(hir) else {
+        panic!("type errors");
+    };
+
+    translate(thir)
+        .map(|lir| lir.to_string())
+        .map_err(|err| err.kind.to_string())
+}
+
+/// the lines of the IR without the indentation
+fn lines_of(code: &str) -> Vec<&str> {
+    code.lines().map(str::trim).collect()
+}
+
+/// the index of the first line which contains `pattern`
+fn find(lines: &[&str], pattern: &str) -> usize {
+    lines
+        .iter()
+        .position(|line| line.contains(pattern))
+        .unwrap_or_else(|| panic!("no `{pattern}` in {lines:#?}"))
+}
+
+/// the label of the block of the line
+fn label_of<'a>(lines: &[&'a str], index: usize) -> &'a str {
+    let label = lines[..index].iter().rev().find(|line| line.ends_with(':'));
+    label.unwrap().trim_end_matches(':')
+}
+
+/// the label the line jumps to
+fn target(line: &str) -> &str {
+    line.rsplit(' ').next().unwrap()
+}
+
+/// the counter of the loop and its value after the step
+fn loop_counter<'a>(lines: &[&'a str]) -> (&'a str, &'a str) {
+    let (counter, args) = lines[find(lines, " = phi ")].split_once(" = phi ").unwrap();
+    (counter, args.split_once(", ").unwrap().1)
+}
+
+#[test]
+pub fn for_step() {
+    // a literal step gives the direction at compile time
+    let code = translated("fn main() { for i in 10..0 step -2 { printi(i); } }").unwrap();
+    let lines = lines_of(&code);
+    let (counter, next) = loop_counter(&lines);
+
+    let cond = find(&lines, &format!("if int.> {counter}, "));
+    assert_eq!(
+        target(lines[cond]),
+        label_of(&lines, find(&lines, "call printi"))
+    );
+    assert!(lines[cond + 1].starts_with("goto "));
+    assert!(!code.contains("int.<"));
+
+    let step = find(&lines, &format!("{next} = int.+ {counter}, "));
+    assert_eq!(lines[step + 1], format!("goto {}", label_of(&lines, cond)));
+
+    // otherwise it's checked before each iteration, a zero step exits
+    let code = translated(
+        "fn f(s: real) { for x in 0.0..=1.0 step s { printr(x); } } fn main() { f(0.1); }",
+    )
+    .unwrap();
+    let lines = lines_of(&code);
+    let (counter, next) = loop_counter(&lines);
+    let body = label_of(&lines, find(&lines, "call printr"));
+
+    let up = find(&lines, "if real.> ");
+    let down = find(&lines, "if real.< ");
+    let s = lines[up].split(' ').nth(2).unwrap().trim_end_matches(',');
+    assert_eq!(
+        lines[up],
+        format!("if real.> {s}, 0 goto {}", target(lines[up]))
+    );
+    assert_eq!(
+        lines[down],
+        format!("if real.< {s}, 0 goto {}", target(lines[down]))
+    );
+    assert_eq!(down, up + 1);
+    assert!(lines[down + 1].starts_with("goto "));
+
+    let up_cond = find(&lines, &format!("if real.<= {counter}, "));
+    let down_cond = find(&lines, &format!("if real.>= {counter}, "));
+    assert_eq!(label_of(&lines, up_cond), target(lines[up]));
+    assert_eq!(label_of(&lines, down_cond), target(lines[down]));
+    assert_eq!(target(lines[up_cond]), body);
+    assert_eq!(target(lines[down_cond]), body);
+
+    let step = find(&lines, &format!("{next} = real.+ {counter}, {s}"));
+    assert_eq!(lines[step + 1], format!("goto {}", label_of(&lines, up)));
+
+    let source = "
+const fn count(s: int) -> int {
+    let n = 0;
+    for i in 10..0 step s { n = n + 1; }
+    return n;
+}
+
+static DOWN: int = count(-3);
+static ZERO: int = count(0);
+static UP: int = count(3);
+
+fn main() { printi(DOWN + ZERO + UP); }
+";
+    let code = translated(source).unwrap();
+    assert!(code.contains("global @0 = 4\n"));
+    assert!(code.contains("global @1 = 0\n"));
+    assert!(code.contains("global @2 = 0\n"));
+}
+
+#[test]
+pub fn index_check() {
+    // a literal index is checked at compile time
+    let code = translated("fn main() { let a = [1, 2, 3]; a[1] = 5; printi(a[2]); }").unwrap();
+    assert!(!code.contains("check_index"));
+
+    let code = translated(
+        "fn f(i: int) { let a = [1, 2, 3]; a[i] = 5; printi(a[i + 1]); } fn main() { f(0); }",
+    )
+    .unwrap();
+    assert_eq!(code.matches("check_index").count(), 2);
+    assert!(code.contains("check_index $5, 3"));
+}
+
+#[test]
+pub fn array_repeat() {
+    // the array is filled by a loop instead of one element per slot
+    let code =
+        translated("fn main() { let a = [vec2(1.0, 2.0); 60000]; printvec2(a[2]); }").unwrap();
+    assert!(code.contains("= agg60000 {}"));
+    assert!(code.contains("if int.< $6, 60000 goto L1"));
+    assert!(code.contains("$7 = store agg60000 $5, $6, f32x2 $0\n\t$8 = int.+ $6, 1"));
+    assert_eq!(code.matches("f32x2 $0").count(), 1);
+}
+
+#[test]
+pub fn lane_check() {
+    // a lane of a vector and of a column of a matrix
+    let source = "
+fn f(i: int, j: int) {
+    let v = vec3(1.0, 2.0, 3.0);
+    let m = mat2(vec2(1.0, 0.0), vec2(0.0, 1.0));
+    printr(v[i] + v[1] + m[i][j]);
+}
+fn main() { f(0, 1); }
+";
+    let code = translated(source).unwrap();
+    assert_eq!(code.matches("check_index").count(), 3);
+    assert!(code.contains("check_index $14, 3\n\t$15 = f32x3.lane $13, $14"));
+    assert!(code.contains("check_index $22, 2\n\t$23 = f32x2.lane $21, $22"));
+}
+
+#[test]
+pub fn const_matrix() {
+    let source = "
+const M: mat2 = mat2(vec2(1.0, 2.0), vec2(3.0, 4.0));
+
+const fn set(m: mat2, i: int, x: real) -> mat2 {
+    m[i][1] = x;
+    m[0][0] += 10.0;
+    return m;
+}
+
+static V: vec2 = M * vec2(1.0, 1.0);
+static N: mat2 = M * M;
+static S: mat2 = set(M, 1, 7.0);
+
+fn main() { printvec2(V); printmat2(N + S); }
+";
+    let code = translated(source).unwrap();
+    assert!(code.contains("global @0 = (4, 6)\n"));
+    assert!(code.contains("global @1 = [(7, 10), (15, 22)]\n"));
+    assert!(code.contains("global @2 = [(11, 2), (3, 7)]\n"));
+
+    // a constant is indexed in place
+    let source = "
+const M: mat2 = mat2(vec2(1.0, 2.0), vec2(3.0, 4.0));
+fn main() { let i = readi(); printvec2(M[0] + M[i]); }
+";
+    let code = translated(source).unwrap();
+    assert!(code.contains("load f32x2 $0, 0"));
+    assert!(code.contains("check_index $3, 2\n\t$4 = load f32x2 $0, $3"));
+
+    // the column is loaded, updated and stored back
+    let code = translated("fn f(m: mat2, i: int, j: int) { m[i][j] += 5.0; printmat2(m); } fn main() { f(mat2(vec2(1.0, 2.0), vec2(3.0, 4.0)), 0, 1); }").unwrap();
+    assert!(code.contains("check_index $3, 2\n\t$4 = load f32x2 $0, $3"));
+    assert!(code.contains("check_index $5, 2"));
+    assert!(code.contains("$7 = f32x2.lane $4, $5"));
+    assert!(code.contains("$9 = f32x2.set_lane $4, $5, $8"));
+    assert!(code.contains("store f32x2x2 $0, $3, f32x2 $9"));
+}
+
+#[test]
+pub fn std_overload() {
+    // the std functions keep their symbols, the user overloads are renamed
+    let source = "
+fn sqrt(x: int) -> int { return x; }
+fn re(v: vec2) -> real { return v:x; }
+fn abs(v: vec2) -> vec2 { return v; }
+
+fn main() {
+    printr(sqrt(2.0) + re(2.0 + 1.0i));
+    printi(sqrt(4) + abs(-1));
+    printr(re(abs(vec2(1.0, 2.0))));
+}
+";
+    let code = translated(source).unwrap();
+    assert!(code.contains("call sqrt real"));
+    assert!(code.contains("call sqrt@int int"));
+    assert!(code.contains("call re f32x2"));
+    assert!(code.contains("call re@vec2 f32x2"));
+    assert!(code.contains("call abs@int int"));
+    assert!(code.contains("call abs@vec2 f32x2"));
+    assert!(code.contains("fn sqrt@int(int $0)"));
+    assert!(!code.contains("fn sqrt("));
+}
+
+#[test]
+pub fn tuple_overload() {
+    let source = "
+fn f(t: (int, real)) -> int { return t.0; }
+fn f(t: [(int, [real; 2]); 1]) -> int { return t[0].0; }
+fn f(x: int) -> int { return x; }
+
+fn main() { printi(f((1, 2.0)) + f([(1, [2.0, 3.0])]) + f(3)); }
+";
+    let code = translated(source).unwrap();
+    assert!(code.contains("fn f@T2.int.real~("));
+    assert!(code.contains("fn f@T2.int.real#2~#1("));
+    assert!(code.contains("call f@T2.int.real~ "));
+    assert!(code.contains("call f@T2.int.real#2~#1 "));
+    assert!(code.contains("call f@int "));
+}
+
+#[test]
+pub fn const_fn_aggregates() {
+    assert_eq!(
+        translated(
+            "const fn f() -> int { let t = (1, 2); return t.0; } const N: int = f(); fn main() { printi(N); }"
+        ).unwrap_err(),
+        "tuples can't be evaluated at compile time"
+    );
+
+    assert_eq!(
+        translated(
+            "const fn f() -> int { let a = [1, 2]; return a[0]; } const N: int = f(); fn main() { printi(N); }"
+        ).unwrap_err(),
+        "arrays can't be evaluated at compile time"
+    );
+}
+
+#[test]
+pub fn aggregate_statics() {
+    // the initializer of a static is evaluated at compile time
+    assert_eq!(
+        translated("static A: [int; 3] = [1, 2, 3]; fn main() { printi(A[0]); }").unwrap_err(),
+        "arrays can't be evaluated at compile time"
+    );
+
+    assert_eq!(
+        translated(
+            "struct P { x: int, y: real } static S: P = P { x: 1, y: 2.0 }; fn main() { printi(S.x); }"
+        ).unwrap_err(),
+        "structs can't be evaluated at compile time"
+    );
+
+    assert_eq!(
+        translated("static T: (int, real) = (1, 2.0); fn main() { printi(T.0); }").unwrap_err(),
+        "tuples can't be evaluated at compile time"
+    );
+}
+
+#[test]
+pub fn diverging_branches() {
+    // the value of the if-expression comes only from the branch that doesn't diverge
+    let source = "
+fn f(c: bool) -> int {
+    let x = if c { return 1; } else { 2 };
+    return x;
+}
+
+fn main() { printi(f(true)); }
+";
+    let code = translated(source).unwrap();
+    let lines = lines_of(&code);
+    let (value, _) = lines[find(&lines, " = int 2")].split_once(" = ").unwrap();
+    assert_eq!(code.matches("returnint").count(), 2);
+    assert!(code.contains(&format!(" = int {value}\n")));
+    assert!(!code.contains("phi"));
+}
+
+#[test]
+pub fn read_calls() {
+    let source = "
+fn main() {
+    let n = readi();
+    let x = readr() * 2.0;
+    let z = readc();
+    let v = readvec3();
+    printi(n);
+    printr(x);
+    printc(z);
+    printvec3(v);
+}
+";
+    let code = translated(source).unwrap();
+    assert!(code.contains(" = call readi \n"));
+    assert!(code.contains(" = call readr \n"));
+    assert!(code.contains(" = call readc \n"));
+    assert!(code.contains(" = call readvec3 \n"));
+    assert!(code.contains("call printc f32x2 "));
+    assert!(code.contains("call printvec3 f32x3 "));
+}
+
+#[test]
+pub fn quat_calls() {
+    let source = "
+static P: quat = quat(1.0, 2.0, 3.0, 4.0) * conjugate(quat(1.0, 2.0, 3.0, 4.0));
+static N: quat = normalize(quat(0.0, 0.0, 0.0, 2.0));
+static R: vec3 = rotate(quat(0.0, 0.0, 0.0, 1.0), vec3(1.0, 2.0, 3.0));
+
+fn main() { printvec4(P as vec4 + N as vec4); printvec3(R); }
+";
+    let code = translated(source).unwrap();
+    // the vector part goes first in the lanes, the real part is the last one
+    assert!(code.contains("global @0 = (0, 0, 0, 30)\n"));
+    assert!(code.contains("global @1 = (0, 0, 1, 0)\n"));
+    assert!(code.contains("global @2 = (-1, -2, 3)\n"));
+
+    let source = "
+fn main() {
+    let v = readvec3();
+    let q = quat_from_axis_angle(v, readr());
+    let p = q * conjugate(q);
+    printvec3(rotate(normalize(p), v));
+    printvec4(slerp(q, p, 0.5) as vec4);
+}
+";
+    let code = translated(source).unwrap();
+    let lines = lines_of(&code);
+    assert!(lines[find(&lines, "call quat_from_axis_angle f32x3 ")].contains(", real "));
+    assert!(code.contains("call conjugate f32x4 "));
+    assert!(code.contains("f32x4.quat_mul "));
+    assert!(code.contains("call normalize f32x4 "));
+    assert!(lines[find(&lines, "call rotate f32x4 ")].contains(", f32x3 "));
+    assert!(lines[find(&lines, "call slerp f32x4 ")].contains(", f32x4 "));
+}
+
+#[test]
+pub fn complex_calls() {
+    let source = "
+static A: real = re(3.0 + 4.0i) + im(3.0 + 4.0i) + abs(3.0 + 4.0i);
+static C: complex = conj(3.0 + 4.0i) * cpow(1.0i, 2);
+static S: complex = csqrt(-4.0 as complex) + polar(2.0, 0.0);
+
+fn main() { printr(A); printc(C + S); }
+";
+    let code = translated(source).unwrap();
+    assert!(code.contains("global @0 = 12\n"));
+    assert!(code.contains("global @1 = (-3, 4)\n"));
+    assert!(code.contains("global @2 = (2, 2)\n"));
+
+    let source = "
+fn main() {
+    let z = readc();
+    let w = polar(abs(z) + arg(z), re(z));
+    printc(cexp(conj(w)) / csqrt(z) + clog(z));
+    printc(cpow(z, readi()));
+}
+";
+    let code = translated(source).unwrap();
+    let lines = lines_of(&code);
+    assert!(code.contains("call abs@complex f32x2 "));
+    assert!(code.contains("call arg f32x2 "));
+    assert!(lines[find(&lines, "call polar real ")].contains(", real "));
+    assert!(code.contains("call conj f32x2 "));
+    assert!(code.contains("call cexp f32x2 "));
+    assert!(code.contains("f32x2.complex_div "));
+    assert!(lines[find(&lines, "call cpow f32x2 ")].contains(", int "));
+
+    // a real argument of a complex parameter is converted, the variable stays real
+    let code = translated("fn main() { let r = readr(); printc(csqrt(r)); printr(r); }").unwrap();
+    let lines = lines_of(&code);
+    let (arg, _) = lines[find(&lines, "real.as f32x2 ")]
+        .split_once(" = ")
+        .unwrap();
+    assert!(code.contains(&format!("call csqrt f32x2 {arg}\n")));
+    assert!(code.contains("call printr real "));
+}
@@ -1,6 +1,7 @@
 use std::collections::{HashMap, VecDeque};
 
-use comet_ir::{Code, Label};
+use comet_ir::{Code, Global, Label, Type};
+use smplc_thir::VarId;
 
 #[derive(Default)]
 pub struct Translator<'source> {
@@ -8,11 +9,15 @@ pub struct Translator<'source> {
     pub labels: HashMap<Label, String>,
     labels_count: usize,
 
-    loops: VecDeque<(Label, Label)>,
+    /// statics aren't tracked by idents, they're read and written through their globals
+    pub statics: HashMap<VarId, (Global, Type)>,
+
+    /// the labels of the loops and their continue and break labels
+    loops: VecDeque<(Option<&'source str>, Label, Label)>,
     loops_count: usize,
 }
 
-impl Translator<'_> {
+impl<'source> Translator<'source> {
     pub fn next_label(&mut self) -> Label {
         let label = Label::new(self.labels_count);
 
@@ -23,21 +28,27 @@ impl Translator<'_> {
         label
     }
 
-    pub fn join_loop(&mut self) -> (Label, Label) {
+    pub fn join_loop(&mut self, label: Option<&'source str>) -> (Label, Label) {
         self.loops_count += 1;
 
         let labels = (self.next_label(), self.next_label());
 
-        self.loops.push_back(labels);
+        self.loops.push_back((label, labels.0, labels.1));
 
-        self.loop_labels()
+        labels
     }
 
     pub fn exit_loop(&mut self) {
         self.loops.pop_back();
     }
 
-    pub fn loop_labels(&mut self) -> (Label, Label) {
-        self.loops.back().copied().unwrap()
+    /// the innermost loop with the label or just the innermost loop
+    pub fn loop_labels(&mut self, label: Option<&'source str>) -> (Label, Label) {
+        self.loops
+            .iter()
+            .rev()
+            .find(|(loop_label, ..)| label.is_none() || *loop_label == label)
+            .map(|&(_, continue_label, break_label)| (continue_label, break_label))
+            .unwrap()
     }
 }
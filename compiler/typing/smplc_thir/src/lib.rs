@@ -1,10 +1,12 @@
 mod ops;
 
+use std::rc::Rc;
+
 use smplc_ast::{self as ast, Swizzle};
 use smplc_hir::SymbolsTable;
 
-pub use smplc_ast::LiteralType;
-pub use smplc_hir::{Atom, FunData, FunId, Literal, Type, VarId};
+pub use smplc_ast::{LiteralType, Span};
+pub use smplc_hir::{Atom, FunData, FunId, Literal, Pattern, StructField, StructType, Type, VarId};
 
 pub use ops::*;
 
@@ -13,6 +15,7 @@ pub struct THIR<'source> {
 
     pub functions: Vec<Function<'source>>,
     pub constants: Vec<Constant<'source>>,
+    pub statics: Vec<Static<'source>>,
 }
 
 pub struct Symbols<'source> {
@@ -35,26 +38,77 @@ pub struct Constant<'source> {
     pub id: VarId,
     pub ty: Type,
     pub value: Expr<'source>,
+    /// the span of the value, the errors of its evaluation point there
+    pub span: Span,
+}
+
+pub struct Static<'source> {
+    pub id: VarId,
+    pub ty: Type,
+    pub value: Expr<'source>,
+    pub span: Span,
 }
 
 pub struct Block<'source> {
     pub statements: Vec<Statement<'source>>,
 }
 
+/// `value` is `None` if the block diverges
+pub struct BlockExpr<'source> {
+    pub statements: Vec<Statement<'source>>,
+    pub value: Option<Box<Expr<'source>>>,
+}
+
 pub enum Statement<'source> {
     Expr(ExprStatement<'source>),
     If(IfStatement<'source>),
     Return(ReturnStatement<'source>),
     While(WhileStatement<'source>),
-    Break,
-    Continue,
+    DoWhile(DoWhileStatement<'source>),
+    Loop(LoopStatement<'source>),
+    For(ForStatement<'source>),
+    Match(MatchStatement<'source>),
+    /// the label is set for `break 'outer;`
+    Break(Option<&'source str>),
+    Continue(Option<&'source str>),
 }
 
 pub enum ExprStatement<'source> {
-    Assign { var: VarId, rhs: Expr<'source> },
+    Assign {
+        var: VarId,
+        rhs: Expr<'source>,
+    },
+    /// `op` is set for compound assignments like `a[i] += b`
+    Store {
+        place: Place<'source>,
+        op: Option<BinOp>,
+        rhs: Expr<'source>,
+    },
+    /// `let (a, b) = rhs;`
+    Destructure {
+        vars: Vec<VarId>,
+        rhs: Expr<'source>,
+    },
     Expr(Expr<'source>),
 }
 
+pub struct Place<'source> {
+    pub var: VarId,
+    pub projections: Vec<Projection<'source>>,
+    /// the index of the assigned component of a vector
+    pub lane: Option<Expr<'source>>,
+    pub swizzle: Option<Swizzle>,
+}
+
+pub enum Projection<'source> {
+    Index(Expr<'source>),
+    /// `index` is the position of the field in `ty`, a struct or a tuple
+    Field {
+        ty: Type,
+        index: usize,
+    },
+}
+
 pub struct IfStatement<'source> {
     pub cond: Expr<'source>,
     pub body: Block<'source>,
@@ -67,7 +121,39 @@ pub struct ReturnStatement<'source> {
 }
 
 pub struct WhileStatement<'source> {
+    pub label: Option<&'source str>,
+    pub cond: Expr<'source>,
+    pub body: Block<'source>,
+}
+
+pub struct DoWhileStatement<'source> {
+    pub label: Option<&'source str>,
+    pub body: Block<'source>,
     pub cond: Expr<'source>,
+}
+
+pub struct LoopStatement<'source> {
+    pub label: Option<&'source str>,
+    pub body: Block<'source>,
+}
+
+pub struct ForStatement<'source> {
+    pub label: Option<&'source str>,
+    pub var: VarId,
+    pub start: Expr<'source>,
+    pub end: Expr<'source>,
+    pub inclusive: bool,
+    pub step: Option<Expr<'source>>,
+    pub body: Block<'source>,
+}
+
+pub struct MatchStatement<'source> {
+    pub value: Expr<'source>,
+    pub arms: Vec<MatchArm<'source>>,
+}
+
+pub struct MatchArm<'source> {
+    pub patterns: Vec<Pattern>,
     pub body: Block<'source>,
 }
 
@@ -85,9 +171,51 @@ pub enum Expr<'source> {
         lhs: Box<Self>,
         swizzle: Swizzle,
     },
+    /// `ty` is the type of the element, `len` is the count of the elements
+    Index {
+        lhs: Box<Self>,
+        index: Box<Self>,
+        ty: Type,
+        len: usize,
+    },
+    /// `index` is the position of the field in `ty`, a struct or a tuple
+    Field {
+        lhs: Box<Self>,
+        ty: Type,
+        index: usize,
+    },
+    If {
+        cond: Box<Self>,
+        body: BlockExpr<'source>,
+        else_body: BlockExpr<'source>,
+    },
+    Block(BlockExpr<'source>),
+    Struct {
+        ty: Rc<StructType>,
+        fields: Vec<Self>,
+    },
+    Tuple(Vec<(Self, Type)>),
+    /// `ty` is the type of the elements
+    Array {
+        elems: Vec<Self>,
+        ty: Type,
+    },
+    ArrayRepeat {
+        value: Box<Self>,
+        len: usize,
+        ty: Type,
+    },
+    /// the length is known at compile time
+    Len(usize),
     Call {
         fun: FunId,
         args: Vec<Self>,
     },
+    /// `pieces` is the text around the arguments,
+    /// it has one more element than `args`
+    Print {
+        pieces: Vec<String>,
+        args: Vec<(Self, Type)>,
+    },
     Atom(Atom<'source>),
 }
@@ -7,7 +7,13 @@ mod display;
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum BinOp {
     Arithm(ArithmOp, NumberType),
+    /// the operations that are defined only for `int`
+    Int(IntOp),
     Vec(VecOp, VecType),
+    Mat(MatOp, MatType),
+    Quat(QuatOp),
+    /// Vec x Int, a component of the vector
+    Lane(VecType),
     Ord(OrdOp, NumberType),
     Eq(EqOp, LinearType),
     Or,
@@ -20,11 +26,17 @@ impl BinOp {
             BinOp::Arithm(_, NumberType::Int) => lir::Type::Int,
             BinOp::Arithm(_, NumberType::Real) => lir::Type::Real,
             BinOp::Arithm(_, NumberType::Complex) => lir::Type::F32x2,
+            BinOp::Int(_) => lir::Type::Int,
 
             BinOp::Vec(_, VecType::Vec2) => lir::Type::F32x2,
             BinOp::Vec(_, VecType::Vec3) => lir::Type::F32x4,
             BinOp::Vec(_, VecType::Vec4) => lir::Type::F32x3,
 
+            BinOp::Mat(MatOp::MulVec, ty) => ty.dims().ty(),
+            BinOp::Mat(_, ty) => ty.dims().mat_ty(),
+            BinOp::Quat(_) => lir::Type::F32x4,
+            BinOp::Lane(_) => lir::Type::Real,
+
             BinOp::Ord(_, _) | BinOp::Eq(_, _) | BinOp::Or | BinOp::And => lir::Type::Int,
         }
     }
@@ -34,6 +46,27 @@ impl BinOp {
 pub enum UnOp {
     Neg(LinearType),
     Not,
+    /// bitwise not of an `int`
+    BitNot,
+    Convert(Conversion),
+    Transpose(MatType),
+    Determinant(MatType),
+    Inverse(MatType),
+}
+
+/// the conversions of `as`
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Conversion {
+    IntToReal,
+    /// truncates the fractional part
+    RealToInt,
+    IntToComplex,
+    RealToComplex,
+    /// keeps the lanes, `w` is the real part
+    QuatToVec4,
+    Vec4ToQuat,
+    /// drops the extra components or fills the missing ones with zeros
+    Resize(VecType, VecType),
 }
 
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -50,6 +83,16 @@ pub enum ArithmOp {
     Div,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum IntOp {
+    Rem,
+    And,
+    Or,
+    Xor,
+    Shl,
+    Shr,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum NumberType {
     Complex,
@@ -85,6 +128,61 @@ impl VecType {
     }
 }
 
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum MatOp {
+    Add,
+    Sub,
+    /// Scalar x Mat
+    LeftMul,
+    /// Mat x Scalar
+    RightMul,
+    Div,
+    /// Mat x Vec
+    MulVec,
+    /// Mat x Mat
+    MulMat,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum MatType {
+    Mat2,
+    Mat3,
+    Mat4,
+}
+
+impl MatType {
+    pub fn dims(self) -> Dims {
+        match self {
+            MatType::Mat2 => Dims::X2,
+            MatType::Mat3 => Dims::X3,
+            MatType::Mat4 => Dims::X4,
+        }
+    }
+
+    pub fn column(self) -> VecType {
+        match self {
+            MatType::Mat2 => VecType::Vec2,
+            MatType::Mat3 => VecType::Vec3,
+            MatType::Mat4 => VecType::Vec4,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum QuatOp {
+    Add,
+    Sub,
+    /// Scalar x Quat
+    LeftMul,
+    /// Quat x Scalar
+    RightMul,
+    Div,
+    /// Quat x Quat, the Hamilton product
+    MulQuat,
+    /// Quat x inverse(Quat)
+    DivQuat,
+}
+
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum EqOp {
     Eq,
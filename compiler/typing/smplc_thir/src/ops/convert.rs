@@ -1,13 +1,17 @@
 use comet_ir as lir;
 use smplc_hir::{self as hir, Type};
 
-use super::{ArithmOp, BinOp, EqOp, LinearType, NumberType, OrdOp, VecOp, VecType};
+use super::{
+    ArithmOp, BinOp, Conversion, EqOp, IntOp, LinearType, MatOp, MatType, NumberType, OrdOp,
+    QuatOp, VecOp, VecType,
+};
 
 impl From<BinOp> for lir::BinOp {
     fn from(value: BinOp) -> Self {
         match value {
             BinOp::Arithm(op, NumberType::Int) => lir::BinOp::Int(op.into()),
             BinOp::Arithm(op, NumberType::Real) => lir::BinOp::Real(op.into()),
+            BinOp::Int(op) => lir::BinOp::IntOp(op.into()),
 
             BinOp::Arithm(ArithmOp::Mul, NumberType::Complex) => lir::BinOp::ComplexMul,
             BinOp::Arithm(ArithmOp::Div, NumberType::Complex) => lir::BinOp::ComplexDiv,
@@ -31,6 +35,32 @@ impl From<BinOp> for lir::BinOp {
                 lir::BinOp::F32s(ty.dims(), op)
             }
 
+            BinOp::Mat(op, ty) => {
+                let op = match op {
+                    MatOp::Add => lir::MatOp::Add,
+                    MatOp::Sub => lir::MatOp::Sub,
+                    MatOp::LeftMul | MatOp::RightMul => lir::MatOp::ScalarMul,
+                    MatOp::Div => lir::MatOp::ScalarDiv,
+                    MatOp::MulVec => lir::MatOp::MulVec,
+                    MatOp::MulMat => lir::MatOp::MulMat,
+                };
+
+                lir::BinOp::Mat(ty.dims(), op)
+            }
+
+            BinOp::Quat(op) => match op {
+                QuatOp::Add => lir::BinOp::F32s(lir::Dims::X4, lir::F32sOp::Add),
+                QuatOp::Sub => lir::BinOp::F32s(lir::Dims::X4, lir::F32sOp::Sub),
+                QuatOp::LeftMul | QuatOp::RightMul => {
+                    lir::BinOp::F32s(lir::Dims::X4, lir::F32sOp::ScalarMul)
+                }
+                QuatOp::Div => lir::BinOp::F32s(lir::Dims::X4, lir::F32sOp::ScalarDiv),
+                QuatOp::MulQuat => lir::BinOp::QuatMul,
+                QuatOp::DivQuat => lir::BinOp::QuatDiv,
+            },
+
+            BinOp::Lane(ty) => lir::BinOp::Lane(ty.dims()),
+
             BinOp::Eq(op, LinearType::Number(NumberType::Complex)) => match op {
                 EqOp::Eq => lir::BinOp::F32sRel(lir::Dims::X2, lir::EqOp::Eq),
                 EqOp::Ne => lir::BinOp::F32sRel(lir::Dims::X2, lir::EqOp::Ne),
@@ -74,15 +104,15 @@ impl From<BinOp> for lir::BinOp {
     }
 }
 
-impl Into<lir::Type> for LinearType {
-    fn into(self) -> lir::Type {
-        Type::from(self).into()
+impl From<LinearType> for lir::Type {
+    fn from(val: LinearType) -> Self {
+        Type::from(val).into()
     }
 }
 
-impl Into<lir::ArithmOp> for ArithmOp {
-    fn into(self) -> lir::ArithmOp {
-        match self {
+impl From<ArithmOp> for lir::ArithmOp {
+    fn from(val: ArithmOp) -> Self {
+        match val {
             ArithmOp::Add => lir::ArithmOp::Add,
             ArithmOp::Sub => lir::ArithmOp::Sub,
             ArithmOp::Mul => lir::ArithmOp::Mul,
@@ -91,6 +121,35 @@ impl Into<lir::ArithmOp> for ArithmOp {
     }
 }
 
+impl From<IntOp> for lir::IntOp {
+    fn from(val: IntOp) -> Self {
+        match val {
+            IntOp::Rem => lir::IntOp::Rem,
+            IntOp::And => lir::IntOp::And,
+            IntOp::Or => lir::IntOp::Or,
+            IntOp::Xor => lir::IntOp::Xor,
+            IntOp::Shl => lir::IntOp::Shl,
+            IntOp::Shr => lir::IntOp::Shr,
+        }
+    }
+}
+
+impl From<Conversion> for lir::Conversion {
+    fn from(val: Conversion) -> Self {
+        match val {
+            Conversion::IntToReal => lir::Conversion::IntToReal,
+            Conversion::RealToInt => lir::Conversion::RealToInt,
+            Conversion::IntToComplex => lir::Conversion::IntToComplex,
+            Conversion::RealToComplex => lir::Conversion::RealToComplex,
+            // the lanes are copied as they are
+            Conversion::QuatToVec4 | Conversion::Vec4ToQuat => {
+                lir::Conversion::Resize(lir::Dims::X4, lir::Dims::X4)
+            }
+            Conversion::Resize(from, to) => lir::Conversion::Resize(from.dims(), to.dims()),
+        }
+    }
+}
+
 impl TryFrom<hir::BinOp> for ArithmOp {
     type Error = ();
 
@@ -106,6 +165,23 @@ impl TryFrom<hir::BinOp> for ArithmOp {
     }
 }
 
+impl TryFrom<hir::BinOp> for IntOp {
+    type Error = ();
+
+    fn try_from(value: hir::BinOp) -> Result<Self, Self::Error> {
+        match value {
+            hir::BinOp::Rem => Ok(Self::Rem),
+            hir::BinOp::BitAnd => Ok(Self::And),
+            hir::BinOp::BitOr => Ok(Self::Or),
+            hir::BinOp::BitXor => Ok(Self::Xor),
+            hir::BinOp::Shl => Ok(Self::Shl),
+            hir::BinOp::Shr => Ok(Self::Shr),
+
+            _ => Err(()),
+        }
+    }
+}
+
 impl TryFrom<hir::BinOp> for EqOp {
     type Error = ();
 
@@ -153,6 +229,16 @@ impl From<VecType> for hir::Type {
     }
 }
 
+impl From<MatType> for hir::Type {
+    fn from(value: MatType) -> Self {
+        match value {
+            MatType::Mat2 => Self::Mat2,
+            MatType::Mat3 => Self::Mat3,
+            MatType::Mat4 => Self::Mat4,
+        }
+    }
+}
+
 impl From<LinearType> for hir::Type {
     fn from(value: LinearType) -> Self {
         match value {
@@ -190,13 +276,27 @@ impl TryFrom<hir::Type> for VecType {
     }
 }
 
+impl TryFrom<hir::Type> for MatType {
+    type Error = ();
+
+    fn try_from(value: hir::Type) -> Result<Self, Self::Error> {
+        match value {
+            hir::Type::Mat2 => Ok(Self::Mat2),
+            hir::Type::Mat3 => Ok(Self::Mat3),
+            hir::Type::Mat4 => Ok(Self::Mat4),
+
+            _ => Err(()),
+        }
+    }
+}
+
 impl TryFrom<hir::Type> for LinearType {
     type Error = ();
 
     fn try_from(value: hir::Type) -> Result<Self, Self::Error> {
         Result::or(
-            VecType::try_from(value).map(|ty| Self::Vec(ty)),
-            NumberType::try_from(value).map(|ty| Self::Number(ty)),
+            VecType::try_from(value.clone()).map(Self::Vec),
+            NumberType::try_from(value).map(Self::Number),
         )
     }
 }
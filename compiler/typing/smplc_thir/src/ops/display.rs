@@ -2,7 +2,7 @@ use std::fmt;
 
 use smplc_hir as hir;
 
-use super::{ArithmOp, EqOp, LinearType, NumberType, OrdOp, RelOp};
+use super::{ArithmOp, EqOp, IntOp, LinearType, NumberType, OrdOp, RelOp};
 
 impl fmt::Display for ArithmOp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -15,6 +15,19 @@ impl fmt::Display for ArithmOp {
     }
 }
 
+impl fmt::Display for IntOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntOp::Rem => write!(f, "%"),
+            IntOp::And => write!(f, "&&&"),
+            IntOp::Or => write!(f, "|||"),
+            IntOp::Xor => write!(f, "^^^"),
+            IntOp::Shl => write!(f, "<<"),
+            IntOp::Shr => write!(f, ">>"),
+        }
+    }
+}
+
 impl fmt::Display for EqOp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
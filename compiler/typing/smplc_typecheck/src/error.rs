@@ -1,6 +1,7 @@
 use std::fmt;
 
-use smplc_ast::Span;
+use smplc_ast::{BinOp, Id, Span, Spanned};
+use smplc_hir::Type;
 
 use crate::type_var::TypeVar;
 
@@ -9,15 +10,106 @@ pub struct TypeError<'source> {
     pub span: Span,
 }
 
-impl TypeError<'_> {
+impl<'source> TypeError<'source> {
     pub fn mismatched_types(required: TypeVar, got: TypeVar, span: Span) -> Self {
         Self {
             kind: TypeErrorKind::MismatchedTypes { required, got },
             span,
         }
     }
+
+    pub fn not_an_array(got: TypeVar, span: Span) -> Self {
+        Self {
+            kind: TypeErrorKind::NotAnArray(got),
+            span,
+        }
+    }
+
+    pub fn not_a_matrix(got: TypeVar, span: Span) -> Self {
+        Self {
+            kind: TypeErrorKind::NotAMatrix(got),
+            span,
+        }
+    }
+
+    pub fn not_printable(got: TypeVar, span: Span) -> Self {
+        Self {
+            kind: TypeErrorKind::NotPrintable(got),
+            span,
+        }
+    }
+
+    pub fn no_matching_overload(id: Id<'source>, args: Vec<TypeVar>) -> Self {
+        let Spanned(fun_id, span) = id;
+
+        Self {
+            kind: TypeErrorKind::NoMatchingOverload { fun_id, args },
+            span,
+        }
+    }
+
+    pub fn ambiguous_call(id: Id<'source>, args: Vec<TypeVar>) -> Self {
+        let Spanned(fun_id, span) = id;
+
+        Self {
+            kind: TypeErrorKind::AmbiguousCall { fun_id, args },
+            span,
+        }
+    }
+
+    pub fn invalid_conversion(from: TypeVar, to: Type, span: Span) -> Self {
+        Self {
+            kind: TypeErrorKind::InvalidConversion { from, to },
+            span,
+        }
+    }
+
+    pub fn invalid_destructure(ty: TypeVar, vars: usize, span: Span) -> Self {
+        Self {
+            kind: TypeErrorKind::InvalidDestructure { ty, vars },
+            span,
+        }
+    }
+
+    pub fn not_a_struct(got: TypeVar, span: Span) -> Self {
+        Self {
+            kind: TypeErrorKind::NotAStruct(got),
+            span,
+        }
+    }
+
+    pub fn missing_return_value(ty: Type, span: Span) -> Self {
+        Self {
+            kind: TypeErrorKind::MissingReturnValue(ty),
+            span,
+        }
+    }
+
+    pub fn no_value(span: Span) -> Self {
+        Self {
+            kind: TypeErrorKind::NoValue,
+            span,
+        }
+    }
+
+    pub fn invalid_operands(op: BinOp, lhs: TypeVar, rhs: TypeVar, span: Span) -> Self {
+        Self {
+            kind: TypeErrorKind::InvalidOperands { op, lhs, rhs },
+            span,
+        }
+    }
+
+    pub fn non_existent_field(ty: Type, field: Id<'source>) -> Self {
+        let Spanned(field, span) = field;
+
+        Self {
+            kind: TypeErrorKind::NonExistentField { ty, field },
+            span,
+        }
+    }
 }
 
+#[derive(Debug, PartialEq)]
 pub enum TypeErrorKind<'source> {
     CouldNotInfer {
         var_id: &'source str,
@@ -27,6 +119,45 @@ pub enum TypeErrorKind<'source> {
         required: TypeVar,
         got: TypeVar,
     },
+    RealRangeWithoutStep,
+    ZeroStep,
+    NotAnArray(TypeVar),
+    IndexOutOfBounds {
+        index: i64,
+        len: usize,
+    },
+    AmbiguousArrayElement(TypeVar),
+    AmbiguousTupleElement(TypeVar),
+    NotAMatrix(TypeVar),
+    NotAStruct(TypeVar),
+    NotPrintable(TypeVar),
+    NoMatchingOverload {
+        fun_id: &'source str,
+        args: Vec<TypeVar>,
+    },
+    AmbiguousCall {
+        fun_id: &'source str,
+        args: Vec<TypeVar>,
+    },
+    NonExistentField {
+        ty: Type,
+        field: &'source str,
+    },
+    InvalidConversion {
+        from: TypeVar,
+        to: Type,
+    },
+    InvalidDestructure {
+        ty: TypeVar,
+        vars: usize,
+    },
+    MissingReturnValue(Type),
+    NoValue,
+    InvalidOperands {
+        op: BinOp,
+        lhs: TypeVar,
+        rhs: TypeVar,
+    },
 }
 
 impl fmt::Display for TypeErrorKind<'_> {
@@ -39,8 +170,91 @@ impl fmt::Display for TypeErrorKind<'_> {
             TypeErrorKind::MismatchedTypes { required, got } => {
                 write!(f, "mismatched types: required {required}, got {got}")
             }
+
+            TypeErrorKind::RealRangeWithoutStep => {
+                write!(f, "a range over real numbers requires a step")
+            }
+
+            TypeErrorKind::ZeroStep => {
+                write!(f, "the step of a range can't be zero")
+            }
+
+            TypeErrorKind::NotAnArray(got) => {
+                write!(f, "expected an array, got {got}")
+            }
+
+            TypeErrorKind::IndexOutOfBounds { index, len } => {
+                write!(f, "index {index} is out of bounds of length {len}")
+            }
+
+            TypeErrorKind::AmbiguousArrayElement(type_var) => {
+                write!(f, "couldn't infer the type of the array elements. Inferred type variable: {type_var}")
+            }
+
+            TypeErrorKind::AmbiguousTupleElement(type_var) => {
+                write!(f, "couldn't infer the type of the tuple element. Inferred type variable: {type_var}")
+            }
+
+            TypeErrorKind::NotAMatrix(got) => {
+                write!(f, "expected a matrix, got {got}")
+            }
+
+            TypeErrorKind::NotAStruct(got) => {
+                write!(f, "expected a struct or a tuple, got {got}")
+            }
+
+            TypeErrorKind::NotPrintable(got) => {
+                write!(f, "a value of type {got} can't be printed")
+            }
+
+            TypeErrorKind::NoMatchingOverload { fun_id, args } => {
+                write!(f, "no overload of \"{fun_id}\" takes ({})", list(args))
+            }
+
+            TypeErrorKind::AmbiguousCall { fun_id, args } => {
+                write!(
+                    f,
+                    "several overloads of \"{fun_id}\" take ({}), specify the arguments types",
+                    list(args)
+                )
+            }
+
+            TypeErrorKind::NonExistentField { ty, field } => {
+                write!(f, "\"{ty}\" has no field \"{field}\"")
+            }
+
+            TypeErrorKind::InvalidConversion { from, to } => {
+                write!(f, "{from} can't be converted to {to}")
+            }
+
+            TypeErrorKind::InvalidDestructure { ty, vars } => {
+                write!(f, "{ty} can't be destructured into {vars} variables")
+            }
+
+            TypeErrorKind::MissingReturnValue(ty) => {
+                write!(f, "the function returns {ty}, `return` requires a value")
+            }
+
+            TypeErrorKind::NoValue => {
+                write!(
+                    f,
+                    "the expression has no value, all of its branches diverge"
+                )
+            }
+
+            TypeErrorKind::InvalidOperands { op, lhs, rhs } => {
+                write!(f, "operator `{op}` can't be applied to {lhs} and {rhs}")
+            }
         }
     }
 }
 
+fn list(types: &[TypeVar]) -> String {
+    types
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 pub type TypeResult<'source, T> = Result<T, TypeError<'source>>;
@@ -1,7 +1,7 @@
 mod expr;
 mod statement;
 
-use std::{collections::HashMap, ops::Index};
+use std::collections::HashMap;
 
 use smplc_ast::Spanned;
 use smplc_hir as hir;
@@ -11,6 +11,7 @@ use smplc_thir::{FunId, Symbols, VarData, VarId};
 use crate::{
     error::{TypeError, TypeErrorKind, TypeResult},
     type_var::TypeVar,
+    typed::Calls,
 };
 
 use self::expr::InferenceResult;
@@ -20,13 +21,13 @@ pub use self::expr::infer_expr;
 pub trait TypeInfer<'source> {
     fn infer(
         &self,
-        inferrer: &mut TypeInferrer,
+        inferrer: &mut TypeInferrer<'source>,
         symbols: &hir::Symbols<'source>,
     ) -> TypeResult<'source, ()>;
 }
 
 #[derive(Default)]
-pub struct TypeInferrer {
+pub struct TypeInferrer<'source> {
     pub vars: HashMap<VarId, SetId>,
     pub sets: HashMap<SetId, TypeVar>,
 
@@ -34,14 +35,27 @@ pub struct TypeInferrer {
 
     pub current_fn: Option<FunId>,
 
+    pub calls: Calls,
+
     relations: Vec<Relation>,
+
+    /// the errors the inference goes on after
+    errors: Vec<TypeError<'source>>,
+
+    /// an expression refers to a function the semcheck has reported,
+    /// so the program isn't typed even without type errors
+    pub broken: bool,
 }
 
-impl TypeInferrer {
+impl<'source> TypeInferrer<'source> {
+    pub fn error(&mut self, error: TypeError<'source>) {
+        self.errors.push(error);
+    }
+
     pub fn set_var_ty(&mut self, var: VarId, ty: TypeVar) -> Result<SetId, (TypeVar, TypeVar)> {
         match self.vars.get(&var) {
             Some(&set) => {
-                let ty = TypeVar::max(self.sets[&set], ty)?;
+                let ty = TypeVar::max(self.sets[&set].clone(), ty)?;
 
                 self.sets.insert(set, ty);
 
@@ -60,22 +74,22 @@ impl TypeInferrer {
     }
 
     pub fn set_set_ty(&mut self, set: SetId, ty: TypeVar) -> Result<TypeVar, (TypeVar, TypeVar)> {
-        let new_ty = TypeVar::max(self.sets[&set], ty)?;
+        let new_ty = TypeVar::max(self.sets[&set].clone(), ty)?;
 
-        self.sets.insert(set, new_ty);
+        self.sets.insert(set, new_ty.clone());
 
         Ok(new_ty)
     }
 
-    pub fn assume_inference<'source>(
+    pub fn assume_inference(
         &mut self,
-        inference: InferenceResult,
+        inference: &InferenceResult,
         ty: TypeVar,
     ) -> TypeResult<'source, TypeVar> {
         if let Some(set) = inference.set {
             self.set_set_ty(set, ty)
         } else {
-            TypeVar::max(inference.ty, ty)
+            TypeVar::max(inference.ty.clone(), ty)
         }
         .map_err(|(got, required)| TypeError::mismatched_types(required, got, inference.span))
     }
@@ -101,13 +115,13 @@ impl TypeInferrer {
             return Ok(a);
         }
 
-        let new_ty = TypeVar::max(self.sets[&a], self.sets[&b])?;
+        let new_ty = TypeVar::max(self.sets[&a].clone(), self.sets[&b].clone())?;
 
         self.set_set_ty(a, new_ty)?;
 
         // self.sets.remove(&b).unwrap();
 
-        for (_, set) in &mut self.vars {
+        for set in self.vars.values_mut() {
             if set == &b {
                 *set = a
             }
@@ -122,15 +136,22 @@ impl TypeInferrer {
         SetId(self.sets_counter)
     }
 
-    pub fn solve_relations<'source>(&mut self) -> TypeResult<'source, ()> {
+    /// the relation of a type error operand is skipped, it's already reported
+    pub fn solve_relations(&mut self) {
         for relation in std::mem::take(&mut self.relations) {
-            match relation {
+            let (Relation::Mul(lhs, rhs) | Relation::Div(lhs, rhs)) = &relation;
+
+            if self.sets[&lhs.0] == TypeVar::Error || self.sets[&rhs.0] == TypeVar::Error {
+                continue;
+            }
+
+            let result = match relation {
                 Relation::Mul(lhs, rhs) => {
                     let lhs_set = lhs.0;
-                    let lhs_ty = self.sets[&lhs_set];
+                    let lhs_ty = self.sets[&lhs_set].clone();
 
                     let rhs_set = rhs.0;
-                    let rhs_ty = self.sets[&rhs_set];
+                    let rhs_ty = self.sets[&rhs_set].clone();
 
                     if lhs_ty.is_vec() {
                         self.set_set_ty(rhs_set, Type::Real.into())
@@ -149,14 +170,14 @@ impl TypeInferrer {
                     }
                     .map_err(|((got, required), span)| {
                         TypeError::mismatched_types(required, got, span)
-                    })?;
+                    })
                 }
                 Relation::Div(lhs, rhs) => {
                     let lhs_set = lhs.0;
-                    let lhs_ty = self.sets[&lhs_set];
+                    let lhs_ty = self.sets[&lhs_set].clone();
 
                     let rhs_set = rhs.0;
-                    let rhs_ty = self.sets[&rhs_set];
+                    let rhs_ty = self.sets[&rhs_set].clone();
 
                     if lhs_ty.is_vec() {
                         self.set_set_ty(rhs_set, Type::Real.into())
@@ -171,40 +192,82 @@ impl TypeInferrer {
                     }
                     .map_err(|((got, required), span)| {
                         TypeError::mismatched_types(required, got, span)
-                    })?;
+                    })
                 }
+            };
+
+            if let Err(err) = result {
+                self.error(err);
             }
         }
-
-        Ok(())
     }
 
-    pub fn infer(mut self, symbols: hir::Symbols) -> Result<Symbols, Vec<TypeError>> {
-        self.solve_relations().map_err(|err| vec![err])?;
+    pub fn infer(
+        mut self,
+        symbols: hir::Symbols<'source>,
+    ) -> Result<Symbols<'source>, Vec<TypeError<'source>>> {
+        self.solve_relations();
 
-        let no_all_infered = self
-            .sets
+        // a variable without a set is never used or its declaration is dropped by the semcheck
+        let unused = symbols
+            .variables
             .iter()
-            .any(|(_, ty)| !matches!(ty, TypeVar::Type(_)));
+            .filter(|(var, _)| !self.vars.contains_key(var))
+            .map(|(var, data)| {
+                let ty = match &data.ty {
+                    _ if data.poisoned => TypeVar::Error,
+                    Some(ty) => TypeVar::Type(ty.clone()),
+                    None => TypeVar::Unknown,
+                };
+
+                (var, ty)
+            })
+            .collect::<Vec<_>>();
 
-        if no_all_infered {
-            Err(self
-                .vars
-                .into_iter()
-                .map(|(var, set)| (var, self.sets[&set]))
-                .filter(|(_, ty)| !matches!(ty, TypeVar::Type(_)))
-                .map(|(var, type_var)| {
-                    let var = symbols.variables[var].id;
-
-                    TypeError {
-                        kind: TypeErrorKind::CouldNotInfer {
-                            var_id: var.0,
-                            type_var,
-                        },
-                        span: var.span(),
-                    }
-                })
-                .collect())
+        for (var, ty) in unused {
+            self.set_var_ty(var, ty).unwrap();
+        }
+
+        // an unknown type after another error is likely caused by it,
+        // the error types come from the semantic errors
+        let has_errors =
+            !self.errors.is_empty() || self.sets.values().any(|ty| *ty == TypeVar::Error);
+
+        // the variables of a type error are already reported
+        let not_inferred = self
+            .vars
+            .iter()
+            .map(|(&var, set)| (var, self.sets[set].clone()))
+            .filter(|(var, ty)| match ty {
+                TypeVar::Type(_) | TypeVar::Error => false,
+                TypeVar::Unknown | TypeVar::None => {
+                    !has_errors && !symbols.variables[*var].poisoned
+                }
+                _ => true,
+            })
+            .map(|(var, type_var)| {
+                let var = symbols.variables[var].id;
+
+                TypeError {
+                    kind: TypeErrorKind::CouldNotInfer {
+                        var_id: var.0,
+                        type_var,
+                    },
+                    span: var.span(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.errors.extend(not_inferred);
+
+        // the error types without type errors come from the semantic errors
+        let has_error_types = self.sets.values().any(|ty| *ty == TypeVar::Error);
+
+        if !self.errors.is_empty() || has_error_types || self.broken {
+            self.errors
+                .sort_by_key(|error| (error.span.file().0, error.span.start().index()));
+
+            Err(self.errors)
         } else {
             let variables = symbols
                 .variables
@@ -227,7 +290,7 @@ impl TypeInferrer {
     }
 
     fn ty(&self, var: VarId) -> Type {
-        let TypeVar::Type(ty) = self.sets[&self.vars[&var]] else {
+        let TypeVar::Type(ty) = self.sets[&self.vars[&var]].clone() else {
             unreachable!()
         };
 
@@ -240,22 +303,5 @@ pub enum Relation {
     Div(Spanned<SetId>, Spanned<SetId>),
 }
 
-pub struct TypesInfo {
-    vars: HashMap<VarId, SetId>,
-    sets: HashMap<SetId, TypeVar>,
-}
-
-impl Index<VarId> for TypesInfo {
-    type Output = Type;
-
-    fn index(&self, index: VarId) -> &Self::Output {
-        let TypeVar::Type(ty) = &self.sets[&self.vars[&index]] else {
-            unreachable!()
-        };
-
-        ty
-    }
-}
-
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SetId(usize);
@@ -1,210 +1,397 @@
-use smplc_ast::{Component, MakeSpanned, Span, Spanned};
+use std::rc::Rc;
+
+use smplc_ast::{Component, Id, LiteralType, MakeSpanned, Span, Spanned, Swizzle};
 use smplc_hir as hir;
-use smplc_hir::Type;
+use smplc_hir::{FunId, Type, VarId};
 
 use crate::{
-    error::{TypeError, TypeResult},
+    error::{TypeError, TypeErrorKind, TypeResult},
     infer::Relation,
 };
 
-use super::{SetId, TypeInferrer, TypeVar};
+use super::{SetId, TypeInfer, TypeInferrer, TypeVar};
 
+/// Infers the type of the expression, an expression with a type error
+/// is reported and gets the error type, so the inference goes on
 pub fn infer_expr<'source>(
     expr: &Spanned<hir::Expr<'source>>,
-    inferrer: &mut TypeInferrer,
+    inferrer: &mut TypeInferrer<'source>,
+    symbols: &hir::Symbols<'source>,
+) -> InferenceResult {
+    try_infer_expr(expr, inferrer, symbols).unwrap_or_else(|err| {
+        inferrer.error(err);
+
+        InferenceResult::error(expr.span())
+    })
+}
+
+fn try_infer_expr<'source>(
+    expr: &Spanned<hir::Expr<'source>>,
+    inferrer: &mut TypeInferrer<'source>,
     symbols: &hir::Symbols<'source>,
 ) -> TypeResult<'source, InferenceResult> {
     match &expr.0 {
         hir::Expr::Binary { lhs, op, rhs } => {
-            let lhs_inference = infer_expr(&lhs, inferrer, symbols)?;
-            let rhs_inference = infer_expr(&rhs, inferrer, symbols)?;
+            let lhs_inference = infer_expr(lhs, inferrer, symbols);
+            let rhs_inference = infer_expr(rhs, inferrer, symbols);
 
-            let (ret_ty, set) = match (lhs_inference.ty, op, rhs_inference.ty) {
-                (_, hir::BinOp::Add | hir::BinOp::Sub, _) => {
-                    let lhs_ty = inferrer.assume_inference(lhs_inference, TypeVar::Linear)?;
-                    let rhs_ty = inferrer.assume_inference(rhs_inference, TypeVar::Linear)?;
+            infer_binary(inferrer, *op, lhs_inference, rhs_inference, expr.span())
+        }
 
-                    let operation_ty =
-                        TypeVar::max(lhs_ty, rhs_ty).map_err(|(required, got)| {
-                            TypeError::mismatched_types(required, got, rhs.span())
-                        })?;
+        hir::Expr::Unary {
+            op: hir::UnOp::BitNot,
+            rhs,
+        } => {
+            let inference = infer_expr(rhs, inferrer, symbols);
 
-                    let set = inferrer
-                        .try_unite(lhs_inference.set, rhs_inference.set)
-                        .unwrap();
+            let ty = inferrer.assume_inference(&inference, Type::Int.into())?;
 
-                    (operation_ty, set)
-                }
-
-                (lhs_ty, hir::BinOp::Mul | hir::BinOp::Div, rhs_ty)
-                    if lhs_ty.is_number() && rhs_ty.is_number() =>
-                {
-                    let operation_ty =
-                        TypeVar::max(lhs_ty, rhs_ty).map_err(|(required, got)| {
-                            TypeError::mismatched_types(required, got, rhs.span())
-                        })?;
+            Ok(InferenceResult {
+                set: inference.set,
+                ty,
+                span: expr.span(),
+            })
+        }
 
-                    let set = inferrer
-                        .try_unite(lhs_inference.set, rhs_inference.set)
-                        .unwrap();
+        hir::Expr::Unary { op, rhs } => {
+            let InferenceResult {
+                set,
+                ty: operand_ty,
+                ..
+            } = infer_expr(rhs, inferrer, symbols);
 
-                    (operation_ty, set)
-                }
+            let min_ty = match op {
+                hir::UnOp::Not => TypeVar::Type(Type::Bool),
+                hir::UnOp::Neg => TypeVar::Linear,
+                hir::UnOp::BitNot => unreachable!(),
+            };
 
-                (_, hir::BinOp::Mul, rhs_ty) if rhs_ty.is_vec() => {
-                    inferrer.assume_inference(lhs_inference, Type::Real.into())?;
+            let ret_ty = TypeVar::max(min_ty, operand_ty).map_err(|(required, got)| {
+                TypeError::mismatched_types(required, got, rhs.span())
+            })?;
 
-                    (rhs_ty, rhs_inference.set)
-                }
+            Ok(InferenceResult {
+                set,
+                ty: ret_ty,
+                span: expr.span(),
+            })
+        }
 
-                (lhs_ty, hir::BinOp::Mul | hir::BinOp::Div, _) if lhs_ty.is_vec() => {
-                    inferrer.assume_inference(rhs_inference, Type::Real.into())?;
+        hir::Expr::Swizzle { lhs, swizzle } => {
+            let inference = infer_expr(lhs, inferrer, symbols);
 
-                    (lhs_ty, lhs_inference.set)
-                }
+            let (ty, ret_ty) = swizzle_tys(swizzle);
 
-                (_, hir::BinOp::Mul, _) => {
-                    inferrer.assume_inference(lhs_inference, TypeVar::Linear)?;
-                    inferrer.assume_inference(rhs_inference, TypeVar::Linear)?;
+            inferrer.assume_inference(&inference, ty)?;
 
-                    if let Some((a, b)) = lhs_inference.set.zip(rhs_inference.set) {
-                        inferrer
-                            .connect(Relation::Mul(a.spanned(lhs.span()), b.spanned(rhs.span())));
-                    }
+            Ok(InferenceResult {
+                set: None,
+                ty: ret_ty.into(),
+                span: expr.span(),
+            })
+        }
 
-                    (TypeVar::Linear, None)
-                }
+        hir::Expr::Index { lhs, index } => {
+            let lhs_inference = infer_expr(lhs, inferrer, symbols);
 
-                (_, hir::BinOp::Div, _) => {
-                    inferrer.assume_inference(lhs_inference, TypeVar::Linear)?;
-                    inferrer.assume_inference(rhs_inference, TypeVar::Number)?;
+            let index_inference = infer_expr(index, inferrer, symbols);
+            inferrer.assume_inference(&index_inference, Type::Int.into())?;
 
-                    if let Some((a, b)) = lhs_inference.set.zip(rhs_inference.set) {
-                        inferrer
-                            .connect(Relation::Div(a.spanned(lhs.span()), b.spanned(rhs.span())));
-                    }
+            if lhs_inference.ty == TypeVar::Error {
+                return Ok(InferenceResult::error(expr.span()));
+            }
 
-                    (TypeVar::Linear, None)
-                }
+            let elem_ty = index_ty(&lhs_inference.ty, lhs_inference.span)?;
 
-                (_, op, _) if op.is_ord() => {
-                    inferrer.assume_inference(lhs_inference, TypeVar::Scalar)?;
-                    inferrer.assume_inference(rhs_inference, TypeVar::Scalar)?;
+            check_index(index, &lhs_inference.ty)?;
 
-                    inferrer
-                        .try_unite(lhs_inference.set, rhs_inference.set)
-                        .map_err(|(got, required)| {
-                            TypeError::mismatched_types(required, got, rhs_inference.span)
-                        })?;
+            Ok(InferenceResult {
+                set: None,
+                ty: elem_ty.into(),
+                span: expr.span(),
+            })
+        }
 
-                    (Type::Bool.into(), None)
-                }
+        hir::Expr::Field { lhs, field } => {
+            let lhs_inference = infer_expr(lhs, inferrer, symbols);
 
-                (_, op, _) if op.is_eq() => {
-                    inferrer.assume_inference(lhs_inference, TypeVar::Linear)?;
-                    inferrer.assume_inference(rhs_inference, TypeVar::Linear)?;
+            if lhs_inference.ty == TypeVar::Error {
+                return Ok(InferenceResult::error(expr.span()));
+            }
 
-                    inferrer
-                        .try_unite(lhs_inference.set, rhs_inference.set)
-                        .map_err(|(got, required)| {
-                            TypeError::mismatched_types(required, got, rhs_inference.span)
-                        })?;
+            let (ty, index) = aggregate_field(&lhs_inference.ty, *field, lhs_inference.span)?;
 
-                    (Type::Bool.into(), None)
-                }
+            Ok(InferenceResult {
+                set: None,
+                ty: ty.field(index).0.into(),
+                span: expr.span(),
+            })
+        }
 
-                (_, op, _) if op.is_logic() => {
-                    inferrer.assume_inference(lhs_inference, Type::Bool.into())?;
-                    inferrer.assume_inference(rhs_inference, Type::Bool.into())?;
+        hir::Expr::As { expr: value, ty } => {
+            let inference = infer_expr(value, inferrer, symbols);
 
-                    let set = inferrer
-                        .try_unite(lhs_inference.set, rhs_inference.set)
-                        .unwrap();
+            let invalid =
+                || TypeError::invalid_conversion(inference.ty.clone(), ty.clone(), expr.span());
 
-                    (Type::Bool.into(), set)
+            // a quaternion keeps its lanes as a `vec4`
+            if inference.ty.is_quat() {
+                if !matches!(ty, Type::Quat | Type::Vec4) {
+                    return Err(invalid());
                 }
 
-                _ => todo!("error"),
+                return Ok(InferenceResult {
+                    set: None,
+                    ty: ty.clone().into(),
+                    span: expr.span(),
+                });
+            }
+
+            // the types that can be converted to `ty`
+            let from = match ty {
+                Type::Int | Type::Real => TypeVar::Scalar,
+                Type::Complex => TypeVar::Number,
+                Type::Vec2 | Type::Vec3 | Type::Vec4 => TypeVar::Vec,
+                Type::Quat => Type::Vec4.into(),
+
+                _ => return Err(invalid()),
             };
 
+            let from = inferrer
+                .assume_inference(&inference, from)
+                .map_err(|_| invalid())?;
+
+            // complex numbers can't be converted to real or int
+            if from == TypeVar::Type(Type::Complex) && ty != &Type::Complex {
+                return Err(invalid());
+            }
+
             Ok(InferenceResult {
-                set,
-                ty: ret_ty,
+                set: None,
+                ty: ty.clone().into(),
                 span: expr.span(),
             })
         }
 
-        hir::Expr::Unary { op, rhs } => {
-            let InferenceResult {
-                set,
-                ty: operand_ty,
-                ..
-            } = infer_expr(&rhs, inferrer, symbols)?;
+        hir::Expr::If {
+            cond,
+            body,
+            else_body,
+        } => {
+            let cond_inference = infer_expr(cond, inferrer, symbols);
+            inferrer.assume_inference(&cond_inference, Type::Bool.into())?;
 
-            let min_ty = match op {
-                hir::UnOp::Not => TypeVar::Type(Type::Bool),
-                hir::UnOp::Neg => TypeVar::Linear,
-            };
+            if body.value.is_none() && else_body.value.is_none() {
+                return Err(TypeError::no_value(expr.span()));
+            }
 
-            let ret_ty = TypeVar::max(min_ty, operand_ty).map_err(|(required, got)| {
-                TypeError::mismatched_types(required, got, rhs.span())
-            })?;
+            let mut ty = TypeVar::Unknown;
+            let mut set = None;
+
+            for block in [body, else_body] {
+                let inference = infer_block(block, expr.span(), inferrer, symbols)?;
+
+                ty = inferrer.assume_inference(&inference, ty)?;
+
+                set = inferrer
+                    .try_unite(set, inference.set)
+                    .map_err(|(got, required)| {
+                        TypeError::mismatched_types(required, got, inference.span)
+                    })?;
+            }
 
             Ok(InferenceResult {
                 set,
-                ty: ret_ty,
+                ty,
                 span: expr.span(),
             })
         }
 
-        hir::Expr::Swizzle { lhs, swizzle } => {
-            let inference = infer_expr(&lhs, inferrer, symbols)?;
+        hir::Expr::Block(block) => {
+            if block.value.is_none() {
+                return Err(TypeError::no_value(expr.span()));
+            }
 
-            let ret_ty = match swizzle.as_slice().len() {
-                1 => Type::Real,
-                2 => Type::Vec2,
-                3 => Type::Vec3,
-                4 => Type::Vec4,
+            let inference = infer_block(block, expr.span(), inferrer, symbols)?;
 
-                _ => unreachable!(),
-            };
+            Ok(InferenceResult {
+                span: expr.span(),
+                ..inference
+            })
+        }
+
+        hir::Expr::Struct { ty, fields } => {
+            for (value, field) in fields.iter().zip(&ty.fields) {
+                let inference = infer_expr(value, inferrer, symbols);
+
+                inferrer.assume_inference(&inference, field.ty.clone().into())?;
+            }
+
+            Ok(InferenceResult {
+                set: None,
+                ty: Type::Struct(Rc::clone(ty)).into(),
+                span: expr.span(),
+            })
+        }
+
+        hir::Expr::Tuple(elems) => {
+            let elems = elems
+                .iter()
+                .map(|elem| {
+                    let inference = infer_expr(elem, inferrer, symbols);
 
-            let max_component = swizzle.as_slice().into_iter().max().unwrap();
+                    match inference.ty {
+                        TypeVar::Type(ty) => Ok(Some(ty)),
+                        TypeVar::Error => Ok(None),
 
-            let ty = match max_component {
-                Component::X | Component::Y => TypeVar::Vec,
-                Component::Z => TypeVar::Vec34,
-                Component::W => Type::Vec4.into(),
+                        type_var => Err(TypeError {
+                            kind: TypeErrorKind::AmbiguousTupleElement(type_var),
+                            span: inference.span,
+                        }),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            // the tuple with a type error element has the error type itself
+            let ty = match elems.into_iter().collect::<Option<_>>() {
+                Some(elems) => Type::Tuple(elems).into(),
+                None => TypeVar::Error,
             };
 
-            inferrer.assume_inference(inference, ty)?;
+            Ok(InferenceResult {
+                set: None,
+                ty,
+                span: expr.span(),
+            })
+        }
+
+        hir::Expr::Array(elems) => {
+            let mut ty = TypeVar::Unknown;
+            let mut set = None;
+
+            for elem in elems {
+                let inference = infer_expr(elem, inferrer, symbols);
+
+                ty = inferrer.assume_inference(&inference, ty)?;
+
+                set = inferrer
+                    .try_unite(set, inference.set)
+                    .map_err(|(got, required)| {
+                        TypeError::mismatched_types(required, got, elem.span())
+                    })?;
+            }
+
+            array_inference(ty, elems.len(), expr.span())
+        }
+
+        hir::Expr::ArrayRepeat { value, len } => {
+            let inference = infer_expr(value, inferrer, symbols);
+
+            array_inference(inference.ty, *len, expr.span())
+        }
+
+        hir::Expr::Len(arg) => {
+            let inference = infer_expr(arg, inferrer, symbols);
+
+            if inference.ty != TypeVar::Error {
+                array_ty(&inference.ty, inference.span)?;
+            }
 
             Ok(InferenceResult {
                 set: None,
-                ty: ret_ty.into(),
+                ty: Type::Int.into(),
+                span: expr.span(),
+            })
+        }
+
+        hir::Expr::Intrinsic { intrinsic, arg } => {
+            let inference = infer_expr(arg, inferrer, symbols);
+
+            if inference.ty == TypeVar::Error {
+                return Ok(InferenceResult::error(expr.span()));
+            }
+
+            if !inference.ty.is_mat() {
+                return Err(TypeError::not_a_matrix(inference.ty, inference.span));
+            }
+
+            let ty = match intrinsic {
+                hir::Intrinsic::Transpose | hir::Intrinsic::Inverse => inference.ty,
+                hir::Intrinsic::Determinant => Type::Real.into(),
+            };
+
+            Ok(InferenceResult {
+                set: None,
+                ty,
                 span: expr.span(),
             })
         }
 
-        hir::Expr::Call { fun, args } => {
-            let &fun_id = fun;
+        hir::Expr::Call { id, args } => {
+            let inferences = args
+                .iter()
+                .map(|arg| infer_expr(arg, inferrer, symbols))
+                .collect::<Vec<_>>();
+
+            let overloads = symbols
+                .functions
+                .iter()
+                .filter(|(_, fun)| fun.id.0 == id.0)
+                .map(|(fun_id, _)| fun_id)
+                .collect::<Vec<_>>();
+
+            let has_error = inferences.iter().any(|arg| arg.ty == TypeVar::Error);
+
+            let fun_id = match overloads.as_slice() {
+                // an undefined function is already reported by the semcheck
+                [] => {
+                    inferrer.broken = true;
+
+                    return Ok(InferenceResult::error(expr.span()));
+                }
+                &[fun_id] => fun_id,
+                overloads => match resolve_overload(*id, overloads, &inferences, symbols) {
+                    Ok(fun_id) => fun_id,
+                    // an argument with a type error fits several overloads
+                    Err(_) if has_error => return Ok(InferenceResult::error(expr.span())),
+                    Err(err) => return Err(err),
+                },
+            };
+
+            inferrer.calls.insert(id.span(), fun_id);
+
             let fun = &symbols.functions[fun_id];
 
-            for (expr, &req_ty) in args.iter().zip(&fun.args_types) {
+            for (inference, req_ty) in inferences.into_iter().zip(&fun.args_types) {
                 let InferenceResult {
-                    set, ty: arg_ty, ..
-                } = infer_expr(&expr, inferrer, symbols)?;
+                    set,
+                    ty: arg_ty,
+                    span,
+                } = inference;
+
+                let required = TypeVar::Type(req_ty.clone());
+
+                // a real argument is converted to complex, but not the other way round
+                match TypeVar::max(required.clone(), arg_ty.clone()) {
+                    Ok(ty) if ty != required && ty != TypeVar::Error => {
+                        return Err(TypeError::mismatched_types(required, arg_ty, span));
+                    }
 
-                if let Err((got, required)) = TypeVar::max(TypeVar::Type(req_ty), arg_ty) {
-                    return Err(TypeError::mismatched_types(required, got, expr.span()));
+                    Err((required, got)) => {
+                        return Err(TypeError::mismatched_types(required, got, span));
+                    }
+
+                    Ok(_) => {}
                 }
 
-                if let Some(set) = set {
-                    inferrer.set_set_ty(set, TypeVar::Type(req_ty)).unwrap();
+                // an ambiguous argument gets the type of the parameter,
+                // a real variable stays real when it's converted
+                if let (Some(set), false) = (set, matches!(arg_ty, TypeVar::Type(_))) {
+                    inferrer.set_set_ty(set, required).unwrap();
                 }
             }
 
-            let ret_ty = fun.ret_ty.map(TypeVar::Type).unwrap_or(TypeVar::None);
+            let ret_ty = fun.ret_ty.clone().map_or(TypeVar::None, TypeVar::Type);
 
             Ok(InferenceResult {
                 set: None,
@@ -213,16 +400,28 @@ pub fn infer_expr<'source>(
             })
         }
 
-        &hir::Expr::Atom(hir::Atom::Var(var)) => {
-            if !inferrer.vars.contains_key(&var) {
-                let ty = symbols.variables[var].ty;
-                let ty = ty.map_or(TypeVar::Unknown, TypeVar::Type);
+        hir::Expr::Print { args, .. } => {
+            for arg in args {
+                let inference = infer_expr(arg, inferrer, symbols);
 
-                inferrer.set_var_ty(var, ty).unwrap();
+                if let TypeVar::None
+                | TypeVar::Type(Type::Array(..) | Type::Struct(_) | Type::Tuple(_)) =
+                    inference.ty
+                {
+                    return Err(TypeError::not_printable(inference.ty, inference.span));
+                }
             }
 
-            let set = inferrer.vars[&var];
-            let ret_ty = inferrer.sets[&set];
+            Ok(InferenceResult {
+                set: None,
+                ty: TypeVar::None,
+                span: expr.span(),
+            })
+        }
+
+        &hir::Expr::Atom(hir::Atom::Var(var)) => {
+            let set = infer_var(var, inferrer, symbols);
+            let ret_ty = inferrer.sets[&set].clone();
 
             Ok(InferenceResult {
                 set: Some(set),
@@ -239,9 +438,487 @@ pub fn infer_expr<'source>(
     }
 }
 
-#[derive(Clone, Copy)]
+/// infers the statements of the block, the result is the inference of its value,
+/// a diverging block fits any type
+fn infer_block<'source>(
+    block: &hir::BlockExpr<'source>,
+    span: Span,
+    inferrer: &mut TypeInferrer<'source>,
+    symbols: &hir::Symbols<'source>,
+) -> TypeResult<'source, InferenceResult> {
+    for stmt in &block.statements {
+        if let Err(err) = stmt.infer(inferrer, symbols) {
+            inferrer.error(err);
+        }
+    }
+
+    match &block.value {
+        Some(value) => Ok(infer_expr(value, inferrer, symbols)),
+
+        None => Ok(InferenceResult {
+            set: None,
+            ty: TypeVar::Unknown,
+            span,
+        }),
+    }
+}
+
+/// the type of a vector that has the swizzle components and the type of the swizzle result
+pub fn swizzle_tys(swizzle: &Swizzle) -> (TypeVar, Type) {
+    let max_component = swizzle.as_slice().iter().max().unwrap();
+
+    let vec_ty = match max_component {
+        Component::X | Component::Y => TypeVar::Vec,
+        Component::Z => TypeVar::Vec34,
+        Component::W => Type::Vec4.into(),
+    };
+
+    let ty = match swizzle.as_slice().len() {
+        1 => Type::Real,
+        2 => Type::Vec2,
+        3 => Type::Vec3,
+        4 => Type::Vec4,
+
+        _ => unreachable!(),
+    };
+
+    (vec_ty, ty)
+}
+
+pub fn infer_binary<'source>(
+    inferrer: &mut TypeInferrer<'source>,
+    op: hir::BinOp,
+    lhs_inference: InferenceResult,
+    rhs_inference: InferenceResult,
+    span: Span,
+) -> TypeResult<'source, InferenceResult> {
+    // the operand with a type error is already reported
+    if lhs_inference.ty == TypeVar::Error || rhs_inference.ty == TypeVar::Error {
+        return Ok(InferenceResult::error(span));
+    }
+
+    let (ret_ty, set) = match (&lhs_inference.ty, op, &rhs_inference.ty) {
+        (_, op, _) if op.is_int() => {
+            inferrer.assume_inference(&lhs_inference, Type::Int.into())?;
+            inferrer.assume_inference(&rhs_inference, Type::Int.into())?;
+
+            let set = inferrer
+                .try_unite(lhs_inference.set, rhs_inference.set)
+                .unwrap();
+
+            (Type::Int.into(), set)
+        }
+
+        (lhs_ty, _, rhs_ty) if lhs_ty.is_mat() || rhs_ty.is_mat() => {
+            let ty = mat_binary(inferrer, op, &lhs_inference, &rhs_inference)?;
+
+            (ty, None)
+        }
+
+        (lhs_ty, _, rhs_ty) if lhs_ty.is_quat() || rhs_ty.is_quat() => {
+            let ty = quat_binary(inferrer, op, &lhs_inference, &rhs_inference)?;
+
+            (ty, None)
+        }
+
+        (_, hir::BinOp::Add | hir::BinOp::Sub, _) => {
+            let lhs_ty = inferrer.assume_inference(&lhs_inference, TypeVar::Linear)?;
+            let rhs_ty = inferrer.assume_inference(&rhs_inference, TypeVar::Linear)?;
+
+            let operation_ty =
+                TypeVar::max(lhs_ty.clone(), rhs_ty.clone()).map_err(|(required, got)| {
+                    TypeError::mismatched_types(required, got, rhs_inference.span)
+                })?;
+
+            let set = inferrer
+                .try_unite(lhs_inference.set, rhs_inference.set)
+                .unwrap();
+
+            (operation_ty, set)
+        }
+
+        (lhs_ty, hir::BinOp::Mul | hir::BinOp::Div, rhs_ty)
+            if lhs_ty.is_number() && rhs_ty.is_number() =>
+        {
+            let operation_ty =
+                TypeVar::max(lhs_ty.clone(), rhs_ty.clone()).map_err(|(required, got)| {
+                    TypeError::mismatched_types(required, got, rhs_inference.span)
+                })?;
+
+            let set = inferrer
+                .try_unite(lhs_inference.set, rhs_inference.set)
+                .unwrap();
+
+            (operation_ty, set)
+        }
+
+        (_, hir::BinOp::Mul, rhs_ty) if rhs_ty.is_vec() => {
+            inferrer.assume_inference(&lhs_inference, Type::Real.into())?;
+
+            (rhs_ty.clone(), rhs_inference.set)
+        }
+
+        (lhs_ty, hir::BinOp::Mul | hir::BinOp::Div, _) if lhs_ty.is_vec() => {
+            inferrer.assume_inference(&rhs_inference, Type::Real.into())?;
+
+            (lhs_ty.clone(), lhs_inference.set)
+        }
+
+        (_, hir::BinOp::Mul, _) => {
+            inferrer.assume_inference(&lhs_inference, TypeVar::Linear)?;
+            inferrer.assume_inference(&rhs_inference, TypeVar::Linear)?;
+
+            if let Some((a, b)) = lhs_inference.set.zip(rhs_inference.set) {
+                inferrer.connect(Relation::Mul(
+                    a.spanned(lhs_inference.span),
+                    b.spanned(rhs_inference.span),
+                ));
+            }
+
+            (TypeVar::Linear, None)
+        }
+
+        (_, hir::BinOp::Div, _) => {
+            inferrer.assume_inference(&lhs_inference, TypeVar::Linear)?;
+            inferrer.assume_inference(&rhs_inference, TypeVar::Number)?;
+
+            if let Some((a, b)) = lhs_inference.set.zip(rhs_inference.set) {
+                inferrer.connect(Relation::Div(
+                    a.spanned(lhs_inference.span),
+                    b.spanned(rhs_inference.span),
+                ));
+            }
+
+            (TypeVar::Linear, None)
+        }
+
+        (_, op, _) if op.is_ord() => {
+            inferrer.assume_inference(&lhs_inference, TypeVar::Scalar)?;
+            inferrer.assume_inference(&rhs_inference, TypeVar::Scalar)?;
+
+            inferrer
+                .try_unite(lhs_inference.set, rhs_inference.set)
+                .map_err(|(got, required)| {
+                    TypeError::mismatched_types(required, got, rhs_inference.span)
+                })?;
+
+            (Type::Bool.into(), None)
+        }
+
+        (_, op, _) if op.is_eq() => {
+            inferrer.assume_inference(&lhs_inference, TypeVar::Linear)?;
+            inferrer.assume_inference(&rhs_inference, TypeVar::Linear)?;
+
+            inferrer
+                .try_unite(lhs_inference.set, rhs_inference.set)
+                .map_err(|(got, required)| {
+                    TypeError::mismatched_types(required, got, rhs_inference.span)
+                })?;
+
+            (Type::Bool.into(), None)
+        }
+
+        (_, op, _) if op.is_logic() => {
+            inferrer.assume_inference(&lhs_inference, Type::Bool.into())?;
+            inferrer.assume_inference(&rhs_inference, Type::Bool.into())?;
+
+            let set = inferrer
+                .try_unite(lhs_inference.set, rhs_inference.set)
+                .unwrap();
+
+            (Type::Bool.into(), set)
+        }
+
+        (lhs_ty, op, rhs_ty) => {
+            return Err(TypeError::invalid_operands(
+                op,
+                lhs_ty.clone(),
+                rhs_ty.clone(),
+                span,
+            ))
+        }
+    };
+
+    Ok(InferenceResult {
+        set,
+        ty: ret_ty,
+        span,
+    })
+}
+
+/// the result type of a binary operation with a matrix operand
+fn mat_binary<'source>(
+    inferrer: &mut TypeInferrer<'source>,
+    op: hir::BinOp,
+    lhs: &InferenceResult,
+    rhs: &InferenceResult,
+) -> TypeResult<'source, TypeVar> {
+    match (&lhs.ty, op, &rhs.ty) {
+        (lhs_ty, hir::BinOp::Add | hir::BinOp::Sub, _) if lhs_ty.is_mat() => {
+            inferrer.assume_inference(rhs, lhs_ty.clone())
+        }
+
+        (_, hir::BinOp::Add | hir::BinOp::Sub, rhs_ty) => {
+            inferrer.assume_inference(lhs, rhs_ty.clone())
+        }
+
+        (lhs_ty, hir::BinOp::Mul, rhs_ty) if lhs_ty.is_mat() && rhs_ty.is_mat() => {
+            inferrer.assume_inference(rhs, lhs_ty.clone())
+        }
+
+        (TypeVar::Type(mat), hir::BinOp::Mul, rhs_ty) if rhs_ty.is_vec() => {
+            inferrer.assume_inference(rhs, mat.column().unwrap().into())
+        }
+
+        (lhs_ty, hir::BinOp::Mul | hir::BinOp::Div, _) if lhs_ty.is_mat() => {
+            inferrer.assume_inference(rhs, Type::Real.into())?;
+
+            Ok(lhs_ty.clone())
+        }
+
+        (_, hir::BinOp::Mul, rhs_ty) => {
+            inferrer.assume_inference(lhs, Type::Real.into())?;
+
+            Ok(rhs_ty.clone())
+        }
+
+        (lhs_ty, _, rhs_ty) => {
+            let (got, span) = if lhs_ty.is_mat() {
+                (lhs_ty.clone(), lhs.span)
+            } else {
+                (rhs_ty.clone(), rhs.span)
+            };
+
+            Err(TypeError::mismatched_types(TypeVar::Linear, got, span))
+        }
+    }
+}
+
+/// the result type of a binary operation with a quaternion operand
+fn quat_binary<'source>(
+    inferrer: &mut TypeInferrer<'source>,
+    op: hir::BinOp,
+    lhs: &InferenceResult,
+    rhs: &InferenceResult,
+) -> TypeResult<'source, TypeVar> {
+    match (&lhs.ty, op, &rhs.ty) {
+        (_, hir::BinOp::Add | hir::BinOp::Sub, _) => {
+            inferrer.assume_inference(lhs, Type::Quat.into())?;
+            inferrer.assume_inference(rhs, Type::Quat.into())
+        }
+
+        (lhs_ty, hir::BinOp::Mul | hir::BinOp::Div, rhs_ty)
+            if lhs_ty.is_quat() && rhs_ty.is_quat() =>
+        {
+            Ok(Type::Quat.into())
+        }
+
+        (lhs_ty, hir::BinOp::Mul | hir::BinOp::Div, _) if lhs_ty.is_quat() => {
+            inferrer.assume_inference(rhs, Type::Real.into())?;
+
+            Ok(Type::Quat.into())
+        }
+
+        (_, hir::BinOp::Mul, _) => {
+            inferrer.assume_inference(lhs, Type::Real.into())?;
+
+            Ok(Type::Quat.into())
+        }
+
+        (lhs_ty, _, rhs_ty) => {
+            let (got, span) = if lhs_ty.is_quat() {
+                (lhs_ty.clone(), lhs.span)
+            } else {
+                (rhs_ty.clone(), rhs.span)
+            };
+
+            Err(TypeError::mismatched_types(TypeVar::Linear, got, span))
+        }
+    }
+}
+
+/// the type of `lhs[index]`: an array element, a matrix column or a vector component
+pub fn index_ty<'source>(ty: &TypeVar, span: Span) -> TypeResult<'source, Type> {
+    match ty {
+        TypeVar::Type(Type::Vec2 | Type::Vec3 | Type::Vec4) => Ok(Type::Real),
+        TypeVar::Type(mat @ (Type::Mat2 | Type::Mat3 | Type::Mat4)) => Ok(mat.column().unwrap()),
+
+        ty => array_ty(ty, span).map(|(elem, _)| elem),
+    }
+}
+
+/// a literal index is checked here, the others are checked at runtime
+pub fn check_index<'source>(
+    index: &Spanned<hir::Expr<'source>>,
+    ty: &TypeVar,
+) -> TypeResult<'source, ()> {
+    let (Some(value), TypeVar::Type(ty)) = (literal_int(&index.0), ty) else {
+        return Ok(());
+    };
+
+    let Some(len) = ty.index_bound() else {
+        return Ok(());
+    };
+
+    if (0..len as i64).contains(&value) {
+        return Ok(());
+    }
+
+    Err(TypeError {
+        kind: TypeErrorKind::IndexOutOfBounds { index: value, len },
+        span: index.span(),
+    })
+}
+
+/// the value of an integer literal, possibly negated
+fn literal_int(expr: &hir::Expr) -> Option<i64> {
+    match expr {
+        hir::Expr::Unary {
+            op: hir::UnOp::Neg,
+            rhs,
+        } => literal_int(&rhs.0).map(|value| -value),
+
+        hir::Expr::Atom(hir::Atom::Literal(literal)) if literal.ty == LiteralType::Int => {
+            parse_int::parse(literal.value).ok()
+        }
+
+        _ => None,
+    }
+}
+
+/// returns the type and the count of the elements
+pub fn array_ty<'source>(ty: &TypeVar, span: Span) -> TypeResult<'source, (Type, usize)> {
+    match ty {
+        TypeVar::Type(Type::Array(elem, len)) => Ok((elem.as_ref().clone(), *len)),
+
+        ty => Err(TypeError::not_an_array(ty.clone(), span)),
+    }
+}
+
+/// returns the struct and the position of the field in it
+/// the struct or tuple type and the index of the field in it,
+/// the fields of a tuple are the indices of its elements
+pub fn aggregate_field<'source>(
+    ty: &TypeVar,
+    field: Id<'source>,
+    span: Span,
+) -> TypeResult<'source, (Type, usize)> {
+    let index = match ty {
+        TypeVar::Type(Type::Struct(struct_ty)) => struct_ty.field(field.0),
+
+        TypeVar::Type(Type::Tuple(elems)) => field
+            .0
+            .parse()
+            .ok()
+            .filter(|&index: &usize| index < elems.len()),
+
+        _ => return Err(TypeError::not_a_struct(ty.clone(), span)),
+    };
+
+    let TypeVar::Type(ty) = ty.clone() else {
+        unreachable!()
+    };
+
+    match index {
+        Some(index) => Ok((ty, index)),
+        None => Err(TypeError::non_existent_field(ty, field)),
+    }
+}
+
+pub fn infer_var(var: VarId, inferrer: &mut TypeInferrer, symbols: &hir::Symbols) -> SetId {
+    if !inferrer.vars.contains_key(&var) {
+        let data = &symbols.variables[var];
+
+        // a poisoned variable is already reported by the semcheck
+        let ty = match &data.ty {
+            _ if data.poisoned => TypeVar::Error,
+            ty => ty.clone().map_or(TypeVar::Unknown, TypeVar::Type),
+        };
+
+        inferrer.set_var_ty(var, ty).unwrap();
+    }
+
+    inferrer.vars[&var]
+}
+
+fn array_inference<'source>(
+    elem: TypeVar,
+    len: usize,
+    span: Span,
+) -> TypeResult<'source, InferenceResult> {
+    if elem == TypeVar::Error {
+        return Ok(InferenceResult::error(span));
+    }
+
+    let TypeVar::Type(elem) = elem else {
+        return Err(TypeError {
+            kind: TypeErrorKind::AmbiguousArrayElement(elem),
+            span,
+        });
+    };
+
+    Ok(InferenceResult {
+        set: None,
+        ty: Type::array(elem, len).into(),
+        span,
+    })
+}
+
+/// an overload is chosen if it's the only one taking exactly the arguments types,
+/// or else the only one which can take them
+fn resolve_overload<'source>(
+    id: Id<'source>,
+    overloads: &[FunId],
+    args: &[InferenceResult],
+    symbols: &hir::Symbols<'source>,
+) -> TypeResult<'source, FunId> {
+    let takes = |fun_id: FunId, exactly: bool| {
+        let args_types = &symbols.functions[fun_id].args_types;
+
+        args_types.len() == args.len()
+            && args_types.iter().zip(args).all(|(ty, arg)| {
+                let ty = TypeVar::Type(ty.clone());
+
+                if exactly {
+                    ty == arg.ty
+                } else {
+                    TypeVar::max(ty, arg.ty.clone()).is_ok()
+                }
+            })
+    };
+
+    if let Some(&fun_id) = overloads.iter().find(|&&fun_id| takes(fun_id, true)) {
+        return Ok(fun_id);
+    }
+
+    let suitable = overloads
+        .iter()
+        .copied()
+        .filter(|&fun_id| takes(fun_id, false))
+        .collect::<Vec<_>>();
+
+    let args = args.iter().map(|arg| arg.ty.clone()).collect();
+
+    match suitable.as_slice() {
+        &[fun_id] => Ok(fun_id),
+        [] => Err(TypeError::no_matching_overload(id, args)),
+        _ => Err(TypeError::ambiguous_call(id, args)),
+    }
+}
+
+#[derive(Clone)]
 pub struct InferenceResult {
     pub set: Option<SetId>,
     pub ty: TypeVar,
     pub span: Span,
 }
+
+impl InferenceResult {
+    pub fn error(span: Span) -> Self {
+        Self {
+            set: None,
+            ty: TypeVar::Error,
+            span,
+        }
+    }
+}
@@ -1,15 +1,19 @@
+use smplc_ast::{Span, Spanned};
 use smplc_hir as hir;
 use smplc_hir::Type;
 
-use crate::error::{TypeError, TypeResult};
+use crate::error::{TypeError, TypeErrorKind, TypeResult};
 
-use super::expr::{infer_expr, InferenceResult};
+use super::expr::{
+    aggregate_field, check_index, index_ty, infer_binary, infer_expr, infer_var, swizzle_tys,
+    InferenceResult,
+};
 use super::{TypeInfer, TypeInferrer, TypeVar};
 
 impl<'source> TypeInfer<'source> for hir::Statement<'source> {
     fn infer(
         &self,
-        inferrer: &mut TypeInferrer,
+        inferrer: &mut TypeInferrer<'source>,
         symbols: &hir::Symbols<'source>,
     ) -> TypeResult<'source, ()> {
         match self {
@@ -17,6 +21,12 @@ impl<'source> TypeInfer<'source> for hir::Statement<'source> {
             hir::Statement::If(if_statement) => if_statement.infer(inferrer, symbols),
             hir::Statement::Return(return_statement) => return_statement.infer(inferrer, symbols),
             hir::Statement::While(while_statement) => while_statement.infer(inferrer, symbols),
+            hir::Statement::DoWhile(do_while_statement) => {
+                do_while_statement.infer(inferrer, symbols)
+            }
+            hir::Statement::Loop(loop_statement) => loop_statement.body.infer(inferrer, symbols),
+            hir::Statement::For(for_statement) => for_statement.infer(inferrer, symbols),
+            hir::Statement::Match(match_statement) => match_statement.infer(inferrer, symbols),
 
             _ => Ok(()),
         }
@@ -26,7 +36,7 @@ impl<'source> TypeInfer<'source> for hir::Statement<'source> {
 impl<'source> TypeInfer<'source> for hir::ExprStatement<'source> {
     fn infer(
         &self,
-        inferrer: &mut TypeInferrer,
+        inferrer: &mut TypeInferrer<'source>,
         symbols: &hir::Symbols<'source>,
     ) -> TypeResult<'source, ()> {
         match self {
@@ -35,18 +45,18 @@ impl<'source> TypeInfer<'source> for hir::ExprStatement<'source> {
                     set: value_set,
                     ty: value_ty,
                     ..
-                } = infer_expr(&rhs, inferrer, symbols)?;
+                } = infer_expr(rhs, inferrer, symbols);
 
-                let var_set = if let Some(ty) = symbols.variables[var].ty {
-                    TypeVar::max(TypeVar::Type(ty), value_ty).map_err(|(required, got)| {
-                        TypeError::mismatched_types(required, got, rhs.span())
-                    })?;
+                let var_set = if let Some(ty) = &symbols.variables[var].ty {
+                    TypeVar::max(TypeVar::Type(ty.clone()), value_ty).map_err(
+                        |(required, got)| TypeError::mismatched_types(required, got, rhs.span()),
+                    )?;
 
                     if let Some(set) = value_set {
-                        inferrer.set_set_ty(set, TypeVar::Type(ty)).unwrap();
+                        inferrer.set_set_ty(set, TypeVar::Type(ty.clone())).unwrap();
                     }
 
-                    inferrer.set_var_ty(var, TypeVar::Type(ty)).unwrap()
+                    inferrer.set_var_ty(var, TypeVar::Type(ty.clone())).unwrap()
                 } else {
                     inferrer.set_var_ty(var, value_ty).unwrap()
                 };
@@ -58,8 +68,122 @@ impl<'source> TypeInfer<'source> for hir::ExprStatement<'source> {
                 Ok(())
             }
 
+            hir::ExprStatement::Store { place, op, rhs } => {
+                let set = infer_var(place.var.0, inferrer, symbols);
+
+                let mut ty = inferrer.sets[&set].clone();
+                let mut span = place.var.span();
+
+                for projection in &place.projections {
+                    // the type error of the variable is already reported
+                    if ty == TypeVar::Error {
+                        break;
+                    }
+
+                    match projection {
+                        hir::Projection::Index(index) => {
+                            let elem_ty = index_ty(&ty, span)?;
+
+                            let index_inference = infer_expr(index, inferrer, symbols);
+                            inferrer.assume_inference(&index_inference, Type::Int.into())?;
+
+                            check_index(index, &ty)?;
+
+                            ty = elem_ty.into();
+                            span = Span::unite(span, index.span());
+                        }
+
+                        &hir::Projection::Field(field) => {
+                            let (aggregate_ty, index) = aggregate_field(&ty, field, span)?;
+
+                            ty = aggregate_ty.field(index).0.into();
+                            span = Span::unite(span, field.span());
+                        }
+                    }
+                }
+
+                let mut current = InferenceResult {
+                    // a variable without projections is updated itself
+                    set: place.projections.is_empty().then_some(set),
+                    ty,
+                    span,
+                };
+
+                if let Some(swizzle) = &place.swizzle {
+                    let (vec_ty, ty) = swizzle_tys(swizzle);
+
+                    inferrer.assume_inference(&current, vec_ty)?;
+
+                    current = InferenceResult {
+                        set: None,
+                        ty: ty.into(),
+                        span,
+                    };
+                }
+
+                let rhs_inference = infer_expr(rhs, inferrer, symbols);
+
+                let InferenceResult {
+                    set: value_set,
+                    ty: value_ty,
+                    ..
+                } = match *op {
+                    Some(op) => {
+                        let span = Span::unite(span, rhs.span());
+
+                        infer_binary(inferrer, op, current.clone(), rhs_inference, span)?
+                    }
+
+                    None => rhs_inference,
+                };
+
+                TypeVar::max(current.ty.clone(), value_ty).map_err(|(required, got)| {
+                    TypeError::mismatched_types(required, got, rhs.span())
+                })?;
+
+                if let Some(set) = value_set {
+                    inferrer.set_set_ty(set, current.ty).unwrap();
+                }
+
+                Ok(())
+            }
+
+            hir::ExprStatement::Destructure { vars, rhs } => {
+                let inference = infer_expr(rhs, inferrer, symbols);
+
+                let elems = match &inference.ty {
+                    TypeVar::Type(Type::Tuple(elems)) if elems.len() == vars.len() => elems,
+
+                    TypeVar::Error => {
+                        for &var in vars {
+                            inferrer.set_var_ty(var, TypeVar::Error).unwrap();
+                        }
+
+                        return Ok(());
+                    }
+
+                    ty => {
+                        for &var in vars {
+                            inferrer.set_var_ty(var, TypeVar::Error).unwrap();
+                        }
+
+                        return Err(TypeError::invalid_destructure(
+                            ty.clone(),
+                            vars.len(),
+                            rhs.span(),
+                        ));
+                    }
+                };
+
+                for (&var, elem) in vars.iter().zip(elems) {
+                    inferrer.set_var_ty(var, elem.clone().into()).unwrap();
+                }
+
+                Ok(())
+            }
+
             hir::ExprStatement::Expr(expr) => {
-                infer_expr(expr, inferrer, symbols)?;
+                infer_expr(expr, inferrer, symbols);
 
                 Ok(())
             }
@@ -70,18 +194,10 @@ impl<'source> TypeInfer<'source> for hir::ExprStatement<'source> {
 impl<'source> TypeInfer<'source> for hir::IfStatement<'source> {
     fn infer(
         &self,
-        inferrer: &mut TypeInferrer,
+        inferrer: &mut TypeInferrer<'source>,
         symbols: &hir::Symbols<'source>,
     ) -> TypeResult<'source, ()> {
-        let InferenceResult { set, ty, .. } = infer_expr(&self.cond, inferrer, symbols)?;
-
-        TypeVar::max(ty, TypeVar::Type(Type::Bool)).map_err(|(got, required)| {
-            TypeError::mismatched_types(required, got, self.cond.span())
-        })?;
-
-        if let Some(set) = set {
-            inferrer.set_set_ty(set, TypeVar::Type(Type::Bool)).unwrap();
-        }
+        infer_cond(&self.cond, inferrer, symbols);
 
         self.body.infer(inferrer, symbols)?;
 
@@ -96,17 +212,19 @@ impl<'source> TypeInfer<'source> for hir::IfStatement<'source> {
 impl<'source> TypeInfer<'source> for hir::ReturnStatement<'source> {
     fn infer(
         &self,
-        inferrer: &mut TypeInferrer,
+        inferrer: &mut TypeInferrer<'source>,
         symbols: &hir::Symbols<'source>,
     ) -> TypeResult<'source, ()> {
-        let ret_ty = symbols.functions[inferrer.current_fn.unwrap()].ret_ty;
+        let ret_ty = symbols.functions[inferrer.current_fn.unwrap()]
+            .ret_ty
+            .clone();
         let ret_ty = ret_ty.map_or(TypeVar::None, TypeVar::Type);
 
         match &self.value {
             Some(value) => {
-                let InferenceResult { set, ty, .. } = infer_expr(&value, inferrer, symbols)?;
+                let InferenceResult { set, ty, .. } = infer_expr(value, inferrer, symbols);
 
-                TypeVar::max(ty, ret_ty).map_err(|(got, required)| {
+                TypeVar::max(ty, ret_ty.clone()).map_err(|(got, required)| {
                     TypeError::mismatched_types(required, got, value.span())
                 })?;
 
@@ -115,11 +233,11 @@ impl<'source> TypeInfer<'source> for hir::ReturnStatement<'source> {
                 }
             }
 
-            None if ret_ty != TypeVar::None => {
-                todo!("make Type Error")
+            None => {
+                if let TypeVar::Type(ty) = ret_ty {
+                    return Err(TypeError::missing_return_value(ty, self.span));
+                }
             }
-
-            _ => {}
         }
 
         Ok(())
@@ -129,35 +247,158 @@ impl<'source> TypeInfer<'source> for hir::ReturnStatement<'source> {
 impl<'source> TypeInfer<'source> for hir::WhileStatement<'source> {
     fn infer(
         &self,
-        inferrer: &mut TypeInferrer,
+        inferrer: &mut TypeInferrer<'source>,
         symbols: &hir::Symbols<'source>,
     ) -> TypeResult<'source, ()> {
-        let InferenceResult { set, ty, .. } = infer_expr(&self.cond, inferrer, symbols)?;
+        infer_cond(&self.cond, inferrer, symbols);
 
-        TypeVar::max(ty, TypeVar::Type(Type::Bool)).map_err(|(got, required)| {
-            TypeError::mismatched_types(required, got, self.cond.span())
-        })?;
+        self.body.infer(inferrer, symbols)?;
 
-        if let Some(set) = set {
-            inferrer.set_set_ty(set, TypeVar::Type(Type::Bool)).unwrap();
-        }
+        Ok(())
+    }
+}
 
+impl<'source> TypeInfer<'source> for hir::DoWhileStatement<'source> {
+    fn infer(
+        &self,
+        inferrer: &mut TypeInferrer<'source>,
+        symbols: &hir::Symbols<'source>,
+    ) -> TypeResult<'source, ()> {
         self.body.infer(inferrer, symbols)?;
 
+        infer_cond(&self.cond, inferrer, symbols);
+
         Ok(())
     }
 }
 
+impl<'source> TypeInfer<'source> for hir::MatchStatement<'source> {
+    fn infer(
+        &self,
+        inferrer: &mut TypeInferrer<'source>,
+        symbols: &hir::Symbols<'source>,
+    ) -> TypeResult<'source, ()> {
+        let inference = infer_expr(&self.value, inferrer, symbols);
+
+        // the type of the patterns, there is none if they're all `_`
+        if let Some(pattern_ty) = &self.ty {
+            if let Err(err) = inferrer.assume_inference(&inference, pattern_ty.clone().into()) {
+                inferrer.error(err);
+            }
+        }
+
+        for arm in &self.arms {
+            arm.body.infer(inferrer, symbols)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'source> TypeInfer<'source> for hir::ForStatement<'source> {
+    fn infer(
+        &self,
+        inferrer: &mut TypeInferrer<'source>,
+        symbols: &hir::Symbols<'source>,
+    ) -> TypeResult<'source, ()> {
+        let mut ty = TypeVar::Scalar;
+        let mut set = None;
+
+        for bound in [&self.start, &self.end].into_iter().chain(&self.step) {
+            let inference = infer_expr(bound, inferrer, symbols);
+
+            ty = inferrer.assume_inference(&inference, ty)?;
+
+            set = inferrer
+                .try_unite(set, inference.set)
+                .map_err(|(got, required)| {
+                    TypeError::mismatched_types(required, got, bound.span())
+                })?;
+        }
+
+        if self.step.is_none() {
+            let span = Span::unite(self.start.span(), self.end.span());
+
+            if ty == TypeVar::Type(Type::Real) {
+                return Err(TypeError {
+                    kind: TypeErrorKind::RealRangeWithoutStep,
+                    span,
+                });
+            }
+
+            ty = TypeVar::Type(Type::Int);
+
+            if let Some(set) = set {
+                inferrer.set_set_ty(set, ty.clone()).unwrap();
+            }
+        }
+
+        // the loop would never end
+        if let Some(step) = self.step.as_ref().filter(|step| is_zero(&step.0)) {
+            inferrer.error(TypeError {
+                kind: TypeErrorKind::ZeroStep,
+                span: step.span(),
+            });
+        }
+
+        let var_set = inferrer.set_var_ty(self.var, ty).unwrap();
+
+        if let Some(set) = set {
+            inferrer.unite(var_set, set).unwrap();
+        }
+
+        self.body.infer(inferrer, symbols)
+    }
+}
+
 impl<'source> TypeInfer<'source> for hir::Block<'source> {
     fn infer(
         &self,
-        inferrer: &mut TypeInferrer,
+        inferrer: &mut TypeInferrer<'source>,
         symbols: &hir::Symbols<'source>,
     ) -> TypeResult<'source, ()> {
+        // an error is reported and the next statement is inferred
         for stmt in &self.statements {
-            stmt.infer(inferrer, symbols)?;
+            if let Err(err) = stmt.infer(inferrer, symbols) {
+                inferrer.error(err);
+            }
         }
 
         Ok(())
     }
 }
+
+/// the condition must be `bool`, the error is reported so the body is still inferred
+fn infer_cond<'source>(
+    cond: &Spanned<hir::Expr<'source>>,
+    inferrer: &mut TypeInferrer<'source>,
+    symbols: &hir::Symbols<'source>,
+) {
+    let inference = infer_expr(cond, inferrer, symbols);
+
+    if let Err(err) = inferrer.assume_inference(&inference, Type::Bool.into()) {
+        inferrer.error(err);
+    }
+}
+
+/// checks if the expression is a literal zero, possibly negated
+fn is_zero(expr: &hir::Expr) -> bool {
+    match expr {
+        hir::Expr::Unary {
+            op: hir::UnOp::Neg,
+            rhs,
+        } => is_zero(&rhs.0),
+
+        hir::Expr::Atom(hir::Atom::Literal(literal)) => {
+            let digits = match literal.value.get(..2) {
+                Some("0b" | "0o" | "0x") => &literal.value[2..],
+                // the exponent doesn't matter
+                _ => literal.value.split(['e', 'E']).next().unwrap(),
+            };
+
+            digits.chars().all(|ch| matches!(ch, '0' | '_' | '.'))
+        }
+
+        _ => false,
+    }
+}
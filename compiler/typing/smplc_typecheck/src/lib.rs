@@ -1,5 +1,5 @@
 use smplc_hir::HIR;
-use smplc_thir::{Constant, Function, THIR};
+use smplc_thir::{Constant, Function, Static, THIR};
 
 use error::TypeError;
 use infer::{infer_expr, TypeInfer, TypeInferrer};
@@ -12,29 +12,56 @@ mod infer;
 mod type_var;
 mod typed;
 
+#[cfg(test)]
+mod tests;
+
 pub fn typecheck(hir: HIR) -> Result<THIR, Vec<TypeError>> {
     let mut inferrer = TypeInferrer::default();
 
     for function in &hir.functions {
         inferrer.current_fn = Some(function.id);
 
-        function
-            .body
-            .infer(&mut inferrer, &hir.symbols)
-            .map_err(|x| vec![x])?;
+        if let Err(err) = function.body.infer(&mut inferrer, &hir.symbols) {
+            inferrer.error(err);
+        }
     }
 
     for constant in &hir.constants {
-        let inference =
-            infer_expr(&constant.value, &mut inferrer, &hir.symbols).map_err(|x| vec![x])?;
-
-        TypeVar::max(TypeVar::Type(constant.ty), inference.ty)
-            .map_err(|(required, got)| {
-                TypeError::mismatched_types(required, got, constant.value.span())
-            })
-            .map_err(|err| vec![err])?;
+        // an unused constant gets its type here
+        inferrer
+            .set_var_ty(constant.id, TypeVar::Type(constant.ty.clone()))
+            .unwrap();
+
+        let inference = infer_expr(&constant.value, &mut inferrer, &hir.symbols);
+
+        if let Err((required, got)) = TypeVar::max(TypeVar::Type(constant.ty.clone()), inference.ty)
+        {
+            inferrer.error(TypeError::mismatched_types(
+                required,
+                got,
+                constant.value.span(),
+            ));
+        }
     }
 
+    for static_ in &hir.statics {
+        inferrer
+            .set_var_ty(static_.id, TypeVar::Type(static_.ty.clone()))
+            .unwrap();
+
+        let inference = infer_expr(&static_.value, &mut inferrer, &hir.symbols);
+
+        if let Err((required, got)) = TypeVar::max(TypeVar::Type(static_.ty.clone()), inference.ty)
+        {
+            inferrer.error(TypeError::mismatched_types(
+                required,
+                got,
+                static_.value.span(),
+            ));
+        }
+    }
+
+    let calls = std::mem::take(&mut inferrer.calls);
     let symbols = inferrer.infer(hir.symbols)?;
 
     let functions = hir
@@ -43,7 +70,7 @@ pub fn typecheck(hir: HIR) -> Result<THIR, Vec<TypeError>> {
         .map(|function| Function {
             id: function.id,
             args: function.args,
-            body: function.body.typed(&symbols),
+            body: function.body.typed(&symbols, &calls),
         })
         .collect();
 
@@ -53,7 +80,19 @@ pub fn typecheck(hir: HIR) -> Result<THIR, Vec<TypeError>> {
         .map(|constant| Constant {
             id: constant.id,
             ty: constant.ty,
-            value: constant.value.0.typed(&symbols),
+            span: constant.value.span(),
+            value: constant.value.0.typed(&symbols, &calls),
+        })
+        .collect();
+
+    let statics = hir
+        .statics
+        .into_iter()
+        .map(|static_| Static {
+            id: static_.id,
+            ty: static_.ty,
+            span: static_.value.span(),
+            value: static_.value.0.typed(&symbols, &calls),
         })
         .collect();
 
@@ -61,5 +100,6 @@ pub fn typecheck(hir: HIR) -> Result<THIR, Vec<TypeError>> {
         symbols,
         functions,
         constants,
+        statics,
     })
 }
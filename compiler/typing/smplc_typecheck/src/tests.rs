@@ -0,0 +1,250 @@
+use smplc_hir::Type;
+use smplc_lexer::Lexer;
+use smplc_parse::{parse, TokenStream};
+use smplc_semcheck::sem_check;
+
+use crate::error::TypeErrorKind;
+use crate::type_var::TypeVar;
+use crate::typecheck;
+
+/// all the type errors of the program in the order they are reported
+macro_rules! typetest {
+    ($code:literal => [$($error:expr),*]) => {
+        let errors = type_errors($code, 0).err().unwrap_or_default();
+        assert_eq!(errors, vec![$($error),*]);
+    };
+}
+
+/// the type errors of the program with `sem_errors` semantic errors,
+/// `Ok` if it's typed
+fn type_errors(code: &str, sem_errors: usize) -> Result<(), Vec<TypeErrorKind<'_>>> {
+    let token_stream = TokenStream::new(Lexer::new(code));
+    let ast = parse(token_stream).unwrap();
+
+    let (hir, errors, _) = sem_check(ast);
+    assert_eq!(errors.len(), sem_errors, "{errors:?}");
+
+    typecheck(hir)
+        .map(|_| ())
+        .map_err(|errors| errors.into_iter().map(|error| error.kind).collect())
+}
+
+#[test]
+pub fn for_step() {
+    typetest![
+        "fn main() { for i in 0..10 step 0 {} }" => [TypeErrorKind::ZeroStep]
+    ];
+
+    typetest![
+        "fn main() { for x in 1.0..0.0 step -0.0 {} }" => [TypeErrorKind::ZeroStep]
+    ];
+
+    typetest![
+        "fn main() { for i in 10..0 step -1 {} for x in 0.0..1.0 step 0.5 {} }" => []
+    ];
+
+    typetest![
+        "fn main() { for x in 0.0..1.0 {} }" => [TypeErrorKind::RealRangeWithoutStep]
+    ];
+}
+
+#[test]
+pub fn index_out_of_bounds() {
+    typetest![
+        "fn main() { let a: [int; 3] = [1, 2, 3]; a[5] = 1; printi(a[-1]); printi(a[2]); }" => [
+            TypeErrorKind::IndexOutOfBounds { index: 5, len: 3 },
+            TypeErrorKind::IndexOutOfBounds { index: -1, len: 3 }
+        ]
+    ];
+
+    typetest![
+        "fn main() { let m = mat2(vec2(1.0, 0.0), vec2(0.0, 1.0)); let c = m[2]; let v = vec2(1.0, 2.0); let x = v[0x2]; }" => [
+            TypeErrorKind::IndexOutOfBounds { index: 2, len: 2 },
+            TypeErrorKind::IndexOutOfBounds { index: 2, len: 2 }
+        ]
+    ];
+
+    // the components of a matrix are assigned by two indices
+    typetest![
+        "const M: mat2 = mat2(vec2(1.0, 0.0), vec2(0.0, 1.0)); fn main() { let c = M[2]; let m = M; m[1][0] = 2.0; m[0][2] += 1.0; }" => [
+            TypeErrorKind::IndexOutOfBounds { index: 2, len: 2 },
+            TypeErrorKind::IndexOutOfBounds { index: 2, len: 2 }
+        ]
+    ];
+}
+
+#[test]
+pub fn missing_return_value() {
+    typetest![
+        "fn f(x: int) -> int { if x > 0 { return; } return x; } fn main() { printi(f(1)); }" => [
+            TypeErrorKind::MissingReturnValue(Type::Int)
+        ]
+    ];
+
+    typetest![
+        "fn f() { return; } fn main() { f(); }" => []
+    ];
+}
+
+#[test]
+pub fn several_errors() {
+    typetest![
+        "
+fn f() -> vec2 { return; }
+
+fn main() {
+    let a = [1, 2];
+    a[3] = 1;
+    for i in 0..3 step 0 {}
+    let x: int = 1.5;
+    printvec2(f());
+}
+" => [
+            TypeErrorKind::MissingReturnValue(Type::Vec2),
+            TypeErrorKind::IndexOutOfBounds { index: 3, len: 2 },
+            TypeErrorKind::ZeroStep,
+            TypeErrorKind::MismatchedTypes {
+                required: Type::Int.into(),
+                got: Type::Real.into()
+            }
+        ]
+    ];
+
+    // the variables of unknown types aren't reported after an error
+    typetest![
+        "fn g() {} fn main() { let a = g(); let b = a; let c = 1 + true; }" => [
+            TypeErrorKind::MismatchedTypes {
+                required: TypeVar::Linear,
+                got: Type::Bool.into()
+            }
+        ]
+    ];
+}
+
+#[test]
+pub fn after_sem_errors() {
+    let code = "\
+fn main() {
+    printi(x + 1);
+    let y = x * 2.0;
+    let z = undefined(y);
+    let w: Foo = 1;
+    printi(z + w);
+    let a: int = 1.5;
+}
+";
+
+    // the undefined names aren't reported again
+    assert_eq!(
+        type_errors(code, 3),
+        Err(vec![TypeErrorKind::MismatchedTypes {
+            required: Type::Int.into(),
+            got: Type::Real.into()
+        }])
+    );
+
+    // the program isn't typed even if the broken call doesn't reach any variable
+    let code = "static A: real = re(undefined(1.0)); fn main() { printr(A); }";
+
+    assert_eq!(type_errors(code, 1), Err(vec![]));
+
+    // the declaration of an unused variable is dropped with its broken value
+    let code = "fn main() { let s = \"abc\"; }";
+
+    assert_eq!(type_errors(code, 1), Err(vec![]));
+
+    let code = "fn main() { let x = len(1, 2); }";
+
+    assert_eq!(type_errors(code, 1), Err(vec![]));
+}
+
+#[test]
+pub fn unused_declaration() {
+    typetest!["fn main() { let x: int; }" => []];
+}
+
+#[test]
+pub fn diverging_branches() {
+    // a diverging branch fits the type of the other one
+    typetest![
+        "fn f(c: bool) -> int { let x: real = if c { return 1; } else { 2.0 }; return x as int; } fn main() { printi(f(true)); }" => []
+    ];
+
+    typetest![
+        "fn main() { for i in 0..10 { let x = if i > 5 { break; } else { i }; printi(x); } }" => []
+    ];
+
+    typetest![
+        "fn f(c: bool) -> int { let x: bool = if c { 1 } else { return 0; }; return 1; } fn main() { printi(f(true)); }" => [
+            TypeErrorKind::MismatchedTypes { required: Type::Bool.into(), got: Type::Int.into() }
+        ]
+    ];
+
+    typetest![
+        "fn f(c: bool) -> int { printi(if c { return 1; } else { return 2; }); return 0; } fn main() { printi(f(true)); }" => [
+            TypeErrorKind::NoValue
+        ]
+    ];
+}
+
+#[test]
+pub fn read_misuse() {
+    typetest![
+        "fn main() { let x: real = readi(); printi(readr()); }" => [
+            TypeErrorKind::MismatchedTypes { required: Type::Real.into(), got: Type::Int.into() },
+            TypeErrorKind::MismatchedTypes { required: Type::Int.into(), got: Type::Real.into() }
+        ]
+    ];
+
+    typetest![
+        "fn main() { let v: vec3 = readvec3() + readvec2(); }" => [
+            TypeErrorKind::MismatchedTypes { required: Type::Vec3.into(), got: Type::Vec2.into() }
+        ]
+    ];
+}
+
+#[test]
+pub fn quat_misuse() {
+    typetest![
+        "fn main() { let q = quat(1.0, 0.0, 0.0, 0.0); let v = rotate(q, vec4(1.0, 0.0, 0.0, 0.0)); }" => [
+            TypeErrorKind::MismatchedTypes { required: Type::Vec3.into(), got: Type::Vec4.into() }
+        ]
+    ];
+
+    // a vec4 isn't converted to quat implicitly
+    typetest![
+        "fn main() { let v = vec4(1.0, 0.0, 0.0, 0.0); let q = conjugate(v); let p = normalize(v as quat); }" => [
+            TypeErrorKind::MismatchedTypes { required: Type::Quat.into(), got: Type::Vec4.into() }
+        ]
+    ];
+
+    typetest![
+        "fn main() { let q = quat(1.0, 0.0, 0.0, 0.0); let p = slerp(q, q, 1); let r = slerp(q, vec3(0.0, 0.0, 1.0), 0.5); }" => [
+            TypeErrorKind::MismatchedTypes { required: Type::Real.into(), got: Type::Int.into() },
+            TypeErrorKind::MismatchedTypes { required: Type::Quat.into(), got: Type::Vec3.into() }
+        ]
+    ];
+}
+
+#[test]
+pub fn complex_misuse() {
+    typetest![
+        "fn main() { let z = readc(); let w = cpow(z, 2.0); let r = re(vec2(1.0, 2.0)); }" => [
+            TypeErrorKind::MismatchedTypes { required: Type::Int.into(), got: Type::Real.into() },
+            TypeErrorKind::MismatchedTypes { required: Type::Complex.into(), got: Type::Vec2.into() }
+        ]
+    ];
+
+    // a real is converted to complex, but not the other way round
+    typetest![
+        "fn main() { let z = readc(); let w = polar(z, 1.0); let s = csqrt(readr()); }" => [
+            TypeErrorKind::MismatchedTypes { required: Type::Real.into(), got: Type::Complex.into() }
+        ]
+    ];
+
+    typetest![
+        "fn main() { let z = readc(); let r: int = abs(z); }" => [
+            TypeErrorKind::MismatchedTypes { required: Type::Int.into(), got: Type::Real.into() }
+        ]
+    ];
+}
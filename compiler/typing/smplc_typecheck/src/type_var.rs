@@ -2,7 +2,7 @@ use std::fmt;
 
 use smplc_hir::Type;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TypeVar {
     Type(Type),
     /// Real, Integer
@@ -17,10 +17,13 @@ pub enum TypeVar {
     Linear,
     Unknown,
     None,
+    /// the type of an expression with a type error, it fits any type,
+    /// so the errors caused by the first one aren't reported
+    Error,
 }
 
 impl TypeVar {
-    pub fn is_vec(self) -> bool {
+    pub fn is_vec(&self) -> bool {
         matches!(
             self,
             Self::Vec34
@@ -31,36 +34,49 @@ impl TypeVar {
         )
     }
 
-    pub fn is_scalar(self) -> bool {
+    pub fn is_mat(&self) -> bool {
+        matches!(
+            self,
+            Self::Type(Type::Mat2) | Self::Type(Type::Mat3) | Self::Type(Type::Mat4)
+        )
+    }
+
+    pub fn is_quat(&self) -> bool {
+        matches!(self, Self::Type(Type::Quat))
+    }
+
+    pub fn is_scalar(&self) -> bool {
         matches!(
             self,
             Self::Scalar | Self::Type(Type::Real) | Self::Type(Type::Int)
         )
     }
 
-    pub fn is_number(self) -> bool {
+    pub fn is_number(&self) -> bool {
         self.is_scalar() || matches!(self, Self::Number | Self::Type(Type::Complex))
     }
 
-    pub fn is_linear(self) -> bool {
+    pub fn is_linear(&self) -> bool {
         self.is_vec() || self.is_number()
     }
 
     pub fn max(a: Self, b: Self) -> Result<Self, (Self, Self)> {
-        match (a, b) {
-            (a, b) if a == b => Ok(a),
+        match (&a, &b) {
+            (a, b) if a == b => Ok(a.clone()),
+
+            (Self::Error, _) | (_, Self::Error) => Ok(Self::Error),
 
-            (Self::Unknown, res) | (res, Self::Unknown) => Ok(res),
+            (Self::Unknown, res) | (res, Self::Unknown) => Ok(res.clone()),
 
             (Self::Type(Type::Complex), Self::Type(Type::Real))
             | (Self::Type(Type::Real), Self::Type(Type::Complex)) => Ok(Type::Complex.into()),
 
-            (Self::Linear, ty) | (ty, Self::Linear) if ty.is_linear() => Ok(ty),
-            (Self::Number, ty) | (ty, Self::Number) if ty.is_number() => Ok(ty),
-            (Self::Scalar, ty) | (ty, Self::Scalar) if ty.is_scalar() => Ok(ty),
+            (Self::Linear, ty) | (ty, Self::Linear) if ty.is_linear() => Ok(ty.clone()),
+            (Self::Number, ty) | (ty, Self::Number) if ty.is_number() => Ok(ty.clone()),
+            (Self::Scalar, ty) | (ty, Self::Scalar) if ty.is_scalar() => Ok(ty.clone()),
             (Self::Vec34, ty @ Self::Type(Type::Vec3 | Type::Vec4))
-            | (ty @ Self::Type(Type::Vec3 | Type::Vec4), Self::Vec34) => Ok(ty),
-            (Self::Vec, ty) | (ty, Self::Vec) if ty.is_vec() => Ok(ty),
+            | (ty @ Self::Type(Type::Vec3 | Type::Vec4), Self::Vec34) => Ok(ty.clone()),
+            (Self::Vec, ty) | (ty, Self::Vec) if ty.is_vec() => Ok(ty.clone()),
 
             _ => Err((a, b)),
         }
@@ -78,6 +94,7 @@ impl fmt::Display for TypeVar {
             TypeVar::Linear => write!(f, "AmbiguousLinear"),
             TypeVar::Unknown => write!(f, "Unknown"),
             TypeVar::None => write!(f, "None"),
+            TypeVar::Error => write!(f, "Error"),
         }
     }
 }
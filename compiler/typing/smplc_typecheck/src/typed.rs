@@ -1,44 +1,85 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use smplc_ast::{Span, Swizzle};
 use smplc_hir as hir;
 use smplc_hir::Atom;
 use smplc_thir::*;
 
+/// the overloads chosen by the inference, by the spans of the called functions names
+pub type Calls = HashMap<Span, FunId>;
+
 pub trait Typed<'source> {
     type Typed;
 
-    fn typed(self, symbols: &Symbols<'source>) -> Self::Typed;
+    fn typed(self, symbols: &Symbols<'source>, calls: &Calls) -> Self::Typed;
 }
 
 impl<'source> Typed<'source> for hir::Statement<'source> {
     type Typed = Statement<'source>;
 
-    fn typed(self, symbols: &Symbols<'source>) -> Self::Typed {
+    fn typed(self, symbols: &Symbols<'source>, calls: &Calls) -> Self::Typed {
         match self {
-            hir::Statement::Expr(expr_statement) => Statement::Expr(expr_statement.typed(symbols)),
-
-            hir::Statement::If(if_statement) => {
-                return Statement::If(IfStatement {
-                    cond: if_statement.cond.0.typed(symbols),
-                    body: if_statement.body.typed(symbols),
-                    else_body: if_statement.else_body.map(|block| block.typed(symbols)),
-                });
-            }
-
-            hir::Statement::Return(return_statement) => {
-                return Statement::Return(ReturnStatement {
-                    fun: return_statement.fun,
-                    value: return_statement.value.map(|expr| expr.0.typed(symbols)),
-                });
-            }
-
-            hir::Statement::While(while_statement) => {
-                return Statement::While(WhileStatement {
-                    cond: while_statement.cond.0.typed(symbols),
-                    body: while_statement.body.typed(symbols),
-                });
+            hir::Statement::Expr(expr_statement) => {
+                Statement::Expr(expr_statement.typed(symbols, calls))
             }
 
-            hir::Statement::Break => Statement::Break,
-            hir::Statement::Continue => Statement::Continue,
+            hir::Statement::If(if_statement) => Statement::If(IfStatement {
+                cond: if_statement.cond.0.typed(symbols, calls),
+                body: if_statement.body.typed(symbols, calls),
+                else_body: if_statement
+                    .else_body
+                    .map(|block| block.typed(symbols, calls)),
+            }),
+
+            hir::Statement::Return(return_statement) => Statement::Return(ReturnStatement {
+                fun: return_statement.fun,
+                value: return_statement
+                    .value
+                    .map(|expr| expr.0.typed(symbols, calls)),
+            }),
+
+            hir::Statement::While(while_statement) => Statement::While(WhileStatement {
+                label: while_statement.label.map(|label| label.0),
+                cond: while_statement.cond.0.typed(symbols, calls),
+                body: while_statement.body.typed(symbols, calls),
+            }),
+
+            hir::Statement::DoWhile(do_while_statement) => Statement::DoWhile(DoWhileStatement {
+                label: do_while_statement.label.map(|label| label.0),
+                body: do_while_statement.body.typed(symbols, calls),
+                cond: do_while_statement.cond.0.typed(symbols, calls),
+            }),
+
+            hir::Statement::Loop(loop_statement) => Statement::Loop(LoopStatement {
+                label: loop_statement.label.map(|label| label.0),
+                body: loop_statement.body.typed(symbols, calls),
+            }),
+
+            hir::Statement::For(for_statement) => Statement::For(ForStatement {
+                label: for_statement.label.map(|label| label.0),
+                var: for_statement.var,
+                start: for_statement.start.0.typed(symbols, calls),
+                end: for_statement.end.0.typed(symbols, calls),
+                inclusive: for_statement.inclusive,
+                step: for_statement.step.map(|step| step.0.typed(symbols, calls)),
+                body: for_statement.body.typed(symbols, calls),
+            }),
+
+            hir::Statement::Match(match_statement) => Statement::Match(MatchStatement {
+                value: match_statement.value.0.typed(symbols, calls),
+                arms: match_statement
+                    .arms
+                    .into_iter()
+                    .map(|arm| MatchArm {
+                        patterns: arm.patterns,
+                        body: arm.body.typed(symbols, calls),
+                    })
+                    .collect(),
+            }),
+
+            hir::Statement::Break(label) => Statement::Break(label.map(|label| label.0)),
+            hir::Statement::Continue(label) => Statement::Continue(label.map(|label| label.0)),
         }
     }
 }
@@ -46,14 +87,77 @@ impl<'source> Typed<'source> for hir::Statement<'source> {
 impl<'source> Typed<'source> for hir::ExprStatement<'source> {
     type Typed = ExprStatement<'source>;
 
-    fn typed(self, symbols: &Symbols<'source>) -> Self::Typed {
+    fn typed(self, symbols: &Symbols<'source>, calls: &Calls) -> Self::Typed {
         match self {
             hir::ExprStatement::Assign { var, rhs } => ExprStatement::Assign {
                 var,
-                rhs: rhs.0.typed(symbols),
+                rhs: rhs.0.typed(symbols, calls),
             },
 
-            hir::ExprStatement::Expr(expr) => ExprStatement::Expr(expr.0.typed(symbols)),
+            hir::ExprStatement::Store { place, op, rhs } => {
+                let mut ty = symbols.variables[place.var.0].ty.clone();
+
+                let mut projections = Vec::new();
+                let mut lane = None;
+
+                for projection in place.projections {
+                    match projection {
+                        // a component of a vector is the last projection
+                        hir::Projection::Index(index)
+                            if matches!(ty, Type::Vec2 | Type::Vec3 | Type::Vec4) =>
+                        {
+                            ty = Type::Real;
+                            lane = Some(index.0.typed(symbols, calls));
+                        }
+
+                        hir::Projection::Index(index) => {
+                            ty = match ty.clone() {
+                                Type::Array(elem, _) => *elem,
+                                mat => mat.column().unwrap(),
+                            };
+
+                            projections.push(Projection::Index(index.0.typed(symbols, calls)));
+                        }
+
+                        hir::Projection::Field(field) => {
+                            let aggregate_ty = ty.clone();
+                            let index = field_index(&aggregate_ty, field.0);
+
+                            ty = aggregate_ty.field(index).0;
+
+                            projections.push(Projection::Field {
+                                ty: aggregate_ty,
+                                index,
+                            });
+                        }
+                    }
+                }
+
+                if let Some(swizzle) = place.swizzle {
+                    ty = swizzle_ty(swizzle);
+                }
+
+                let rhs = rhs.0.typed(symbols, calls);
+                let op = op.map(|op| bin_op_typed(op, ty, expr_ty(&rhs, symbols)));
+
+                ExprStatement::Store {
+                    place: Place {
+                        var: place.var.0,
+                        projections,
+                        lane,
+                        swizzle: place.swizzle,
+                    },
+                    op,
+                    rhs,
+                }
+            }
+
+            hir::ExprStatement::Destructure { vars, rhs } => ExprStatement::Destructure {
+                vars,
+                rhs: rhs.0.typed(symbols, calls),
+            },
+
+            hir::ExprStatement::Expr(expr) => ExprStatement::Expr(expr.0.typed(symbols, calls)),
         }
     }
 }
@@ -61,25 +165,44 @@ impl<'source> Typed<'source> for hir::ExprStatement<'source> {
 impl<'source> Typed<'source> for hir::Block<'source> {
     type Typed = Block<'source>;
 
-    fn typed(self, symbols: &Symbols<'source>) -> Self::Typed {
+    fn typed(self, symbols: &Symbols<'source>, calls: &Calls) -> Self::Typed {
         let statements = self
             .statements
             .into_iter()
-            .map(|stmt| stmt.typed(symbols))
+            .map(|stmt| stmt.typed(symbols, calls))
             .collect();
 
         Block { statements }
     }
 }
 
+impl<'source> Typed<'source> for hir::BlockExpr<'source> {
+    type Typed = BlockExpr<'source>;
+
+    fn typed(self, symbols: &Symbols<'source>, calls: &Calls) -> Self::Typed {
+        let statements = self
+            .statements
+            .into_iter()
+            .map(|stmt| stmt.typed(symbols, calls))
+            .collect();
+
+        BlockExpr {
+            statements,
+            value: self
+                .value
+                .map(|value| Box::new(value.0.typed(symbols, calls))),
+        }
+    }
+}
+
 impl<'source> Typed<'source> for hir::Expr<'source> {
     type Typed = Expr<'source>;
 
-    fn typed(self, symbols: &Symbols<'source>) -> Self::Typed {
+    fn typed(self, symbols: &Symbols<'source>, calls: &Calls) -> Self::Typed {
         match self {
             hir::Expr::Binary { lhs, op, rhs } => {
-                let lhs = lhs.0.typed(symbols);
-                let rhs = rhs.0.typed(symbols);
+                let lhs = lhs.0.typed(symbols, calls);
+                let rhs = rhs.0.typed(symbols, calls);
 
                 let lhs_ty = expr_ty(&lhs, symbols);
                 let rhs_ty = expr_ty(&rhs, symbols);
@@ -92,28 +215,194 @@ impl<'source> Typed<'source> for hir::Expr<'source> {
             }
 
             hir::Expr::Unary { op, rhs } => {
-                let rhs = Box::new(rhs.0.typed(symbols));
+                let rhs = Box::new(rhs.0.typed(symbols, calls));
 
                 let op = match op {
                     hir::UnOp::Not => UnOp::Not,
                     hir::UnOp::Neg => UnOp::Neg(expr_ty(&rhs, symbols).try_into().unwrap()),
+                    hir::UnOp::BitNot => UnOp::BitNot,
                 };
 
                 Expr::Unary { op, rhs }
             }
 
+            hir::Expr::As { expr, ty } => {
+                let expr = expr.0.typed(symbols, calls);
+
+                match conversion(expr_ty(&expr, symbols), ty) {
+                    Some(conversion) => Expr::Unary {
+                        op: UnOp::Convert(conversion),
+                        rhs: Box::new(expr),
+                    },
+
+                    None => expr,
+                }
+            }
+
             hir::Expr::Swizzle { lhs, swizzle } => {
-                let lhs = Box::new(lhs.0.typed(symbols));
+                let lhs = Box::new(lhs.0.typed(symbols, calls));
 
                 Expr::Swizzle { lhs, swizzle }
             }
 
-            hir::Expr::Call { fun, args } => {
-                let args = args.into_iter().map(|expr| expr.0.typed(symbols)).collect();
+            hir::Expr::Index { lhs, index } => {
+                let lhs = lhs.0.typed(symbols, calls);
+                let index = index.0.typed(symbols, calls);
+
+                let lhs_ty = expr_ty(&lhs, symbols);
+                let len = lhs_ty.index_bound().unwrap();
+
+                let ty = match lhs_ty {
+                    Type::Array(elem, _) => *elem,
+
+                    ty @ (Type::Vec2 | Type::Vec3 | Type::Vec4) => {
+                        return Expr::Binary {
+                            lhs: Box::new(lhs),
+                            op: BinOp::Lane(ty.try_into().unwrap()),
+                            rhs: Box::new(index),
+                        }
+                    }
+
+                    mat => mat.column().unwrap(),
+                };
+
+                Expr::Index {
+                    lhs: Box::new(lhs),
+                    index: Box::new(index),
+                    ty,
+                    len,
+                }
+            }
+
+            hir::Expr::Intrinsic { intrinsic, arg } => {
+                let rhs = Box::new(arg.0.typed(symbols, calls));
+                let ty = expr_ty(&rhs, symbols).try_into().unwrap();
+
+                let op = match intrinsic {
+                    hir::Intrinsic::Transpose => UnOp::Transpose(ty),
+                    hir::Intrinsic::Determinant => UnOp::Determinant(ty),
+                    hir::Intrinsic::Inverse => UnOp::Inverse(ty),
+                };
+
+                Expr::Unary { op, rhs }
+            }
+
+            hir::Expr::Field { lhs, field } => {
+                let lhs = lhs.0.typed(symbols, calls);
+
+                let ty = expr_ty(&lhs, symbols);
+                let index = field_index(&ty, field.0);
+
+                Expr::Field {
+                    lhs: Box::new(lhs),
+                    ty,
+                    index,
+                }
+            }
+
+            hir::Expr::If {
+                cond,
+                body,
+                else_body,
+            } => Expr::If {
+                cond: Box::new(cond.0.typed(symbols, calls)),
+                body: body.typed(symbols, calls),
+                else_body: else_body.typed(symbols, calls),
+            },
+
+            hir::Expr::Block(block) => Expr::Block(block.typed(symbols, calls)),
+
+            hir::Expr::Struct { ty, fields } => {
+                let fields = fields
+                    .into_iter()
+                    .map(|field| field.0.typed(symbols, calls))
+                    .collect();
+
+                Expr::Struct { ty, fields }
+            }
+
+            hir::Expr::Tuple(elems) => {
+                let elems = elems
+                    .into_iter()
+                    .map(|elem| {
+                        let elem = elem.0.typed(symbols, calls);
+                        let ty = expr_ty(&elem, symbols);
+
+                        (elem, ty)
+                    })
+                    .collect();
+
+                Expr::Tuple(elems)
+            }
+
+            hir::Expr::Array(elems) => {
+                let elems = elems
+                    .into_iter()
+                    .map(|elem| elem.0.typed(symbols, calls))
+                    .collect::<Vec<_>>();
+
+                let ty = expr_ty(&elems[0], symbols);
+
+                Expr::Array { elems, ty }
+            }
+
+            hir::Expr::ArrayRepeat { value, len } => {
+                let value = value.0.typed(symbols, calls);
+                let ty = expr_ty(&value, symbols);
+
+                Expr::ArrayRepeat {
+                    value: Box::new(value),
+                    len,
+                    ty,
+                }
+            }
+
+            hir::Expr::Len(arg) => {
+                let Type::Array(_, len) = expr_ty(&arg.0.typed(symbols, calls), symbols) else {
+                    unreachable!()
+                };
+
+                Expr::Len(len)
+            }
+
+            hir::Expr::Call { id, args, .. } => {
+                let fun = calls[&id.span()];
+
+                // a real argument of a complex parameter is converted
+                let args = args
+                    .into_iter()
+                    .zip(&symbols.functions[fun].args_types)
+                    .map(|(expr, arg_ty)| {
+                        let expr = expr.0.typed(symbols, calls);
+
+                        match conversion(expr_ty(&expr, symbols), arg_ty.clone()) {
+                            Some(conversion) => Expr::Unary {
+                                op: UnOp::Convert(conversion),
+                                rhs: Box::new(expr),
+                            },
+
+                            None => expr,
+                        }
+                    })
+                    .collect();
 
                 Expr::Call { fun, args }
             }
 
+            hir::Expr::Print { pieces, args } => {
+                let args = args
+                    .into_iter()
+                    .map(|arg| {
+                        let arg = arg.0.typed(symbols, calls);
+                        let ty = expr_ty(&arg, symbols);
+
+                        (arg, ty)
+                    })
+                    .collect();
+
+                Expr::Print { pieces, args }
+            }
+
             hir::Expr::Atom(atom) => Expr::Atom(atom),
         }
     }
@@ -123,34 +412,134 @@ fn expr_ty(expr: &Expr, symbols: &Symbols) -> Type {
     match expr {
         Expr::Binary { op, .. } => match op {
             &BinOp::Arithm(_, ty) => ty.into(),
+            BinOp::Int(_) => Type::Int,
             &BinOp::Vec(_, ty) => ty.into(),
+            &BinOp::Mat(MatOp::MulVec, ty) => ty.column().into(),
+            &BinOp::Mat(_, ty) => ty.into(),
+            BinOp::Quat(_) => Type::Quat,
+            BinOp::Lane(_) => Type::Real,
             &BinOp::Eq(_, _) | BinOp::Ord(_, _) | BinOp::And | BinOp::Or => Type::Bool,
         },
 
         Expr::Unary { op, .. } => match op {
             &UnOp::Neg(ty) => ty.into(),
             UnOp::Not => Type::Bool,
+            UnOp::BitNot => Type::Int,
+            UnOp::Convert(Conversion::IntToReal) => Type::Real,
+            UnOp::Convert(Conversion::RealToInt) => Type::Int,
+            UnOp::Convert(Conversion::IntToComplex | Conversion::RealToComplex) => Type::Complex,
+            UnOp::Convert(Conversion::QuatToVec4) => Type::Vec4,
+            UnOp::Convert(Conversion::Vec4ToQuat) => Type::Quat,
+            &UnOp::Convert(Conversion::Resize(_, ty)) => ty.into(),
+            UnOp::Determinant(_) => Type::Real,
+            &UnOp::Transpose(ty) | &UnOp::Inverse(ty) => ty.into(),
         },
 
-        Expr::Swizzle { swizzle, .. } => match swizzle.as_slice().len() {
-            1 => Type::Real,
-            2 => Type::Vec2,
-            3 => Type::Vec3,
-            4 => Type::Vec4,
-            _ => unreachable!(),
-        },
+        &Expr::Swizzle { swizzle, .. } => swizzle_ty(swizzle),
 
-        &Expr::Call { fun: id, .. } => symbols.functions[id].ret_ty.unwrap(),
+        // the typecheck ensures that one of the branches has a value
+        Expr::If {
+            body, else_body, ..
+        } => {
+            let value = body.value.as_ref().or(else_body.value.as_ref());
+
+            expr_ty(value.unwrap(), symbols)
+        }
+        Expr::Block(body) => expr_ty(body.value.as_ref().unwrap(), symbols),
+
+        Expr::Index { ty, .. } => ty.clone(),
+        Expr::Field { ty, index, .. } => ty.field(*index).0,
+        Expr::Struct { ty, .. } => Type::Struct(Rc::clone(ty)),
+        Expr::Tuple(elems) => Type::Tuple(elems.iter().map(|(_, ty)| ty.clone()).collect()),
+        Expr::Array { elems, ty } => Type::array(ty.clone(), elems.len()),
+        Expr::ArrayRepeat { len, ty, .. } => Type::array(ty.clone(), *len),
+        Expr::Len(_) => Type::Int,
+
+        &Expr::Call { fun: id, .. } => symbols.functions[id].ret_ty.clone().unwrap(),
+
+        Expr::Print { .. } => unreachable!(),
 
         Expr::Atom(Atom::Literal(lit)) => lit.ty.into(),
 
-        &Expr::Atom(Atom::Var(id)) => symbols.variables[id].ty,
+        &Expr::Atom(Atom::Var(id)) => symbols.variables[id].ty.clone(),
+    }
+}
+
+/// the fields of a tuple are the indices of its elements
+fn field_index(ty: &Type, field: &str) -> usize {
+    match ty {
+        Type::Struct(struct_ty) => struct_ty.field(field).unwrap(),
+        _ => field.parse().unwrap(),
+    }
+}
+
+/// `None` if the value is already of the type
+fn conversion(from: Type, to: Type) -> Option<Conversion> {
+    let conversion = match (from, to) {
+        (from, to) if from == to => return None,
+
+        (Type::Int, Type::Real) => Conversion::IntToReal,
+        (Type::Real, Type::Int) => Conversion::RealToInt,
+        (Type::Int, Type::Complex) => Conversion::IntToComplex,
+        (Type::Real, Type::Complex) => Conversion::RealToComplex,
+        (Type::Quat, Type::Vec4) => Conversion::QuatToVec4,
+        (Type::Vec4, Type::Quat) => Conversion::Vec4ToQuat,
+
+        (from, to) => Conversion::Resize(from.try_into().unwrap(), to.try_into().unwrap()),
+    };
+
+    Some(conversion)
+}
+
+fn swizzle_ty(swizzle: Swizzle) -> Type {
+    match swizzle.as_slice().len() {
+        1 => Type::Real,
+        2 => Type::Vec2,
+        3 => Type::Vec3,
+        4 => Type::Vec4,
+        _ => unreachable!(),
     }
 }
 
 fn bin_op_typed(op: hir::BinOp, lhs: Type, rhs: Type) -> BinOp {
+    if let Ok(op) = IntOp::try_from(op) {
+        return BinOp::Int(op);
+    }
+
+    if let Ok(ty) = MatType::try_from(lhs.clone()).or(MatType::try_from(rhs.clone())) {
+        let op = match op {
+            hir::BinOp::Add => MatOp::Add,
+            hir::BinOp::Sub => MatOp::Sub,
+            hir::BinOp::Mul if lhs == Type::Real => MatOp::LeftMul,
+            hir::BinOp::Mul if rhs == Type::Real => MatOp::RightMul,
+            hir::BinOp::Mul if lhs == rhs => MatOp::MulMat,
+            hir::BinOp::Mul => MatOp::MulVec,
+            hir::BinOp::Div => MatOp::Div,
+
+            _ => unreachable!(),
+        };
+
+        return BinOp::Mat(op, ty);
+    }
+
+    if lhs == Type::Quat || rhs == Type::Quat {
+        let op = match op {
+            hir::BinOp::Add => QuatOp::Add,
+            hir::BinOp::Sub => QuatOp::Sub,
+            hir::BinOp::Mul if lhs == Type::Real => QuatOp::LeftMul,
+            hir::BinOp::Mul if rhs == Type::Real => QuatOp::RightMul,
+            hir::BinOp::Mul => QuatOp::MulQuat,
+            hir::BinOp::Div if rhs == Type::Real => QuatOp::Div,
+            hir::BinOp::Div => QuatOp::DivQuat,
+
+            _ => unreachable!(),
+        };
+
+        return BinOp::Quat(op);
+    }
+
     if let Ok(op) = ArithmOp::try_from(op) {
-        if let Ok(ty) = VecType::try_from(lhs).or(VecType::try_from(rhs)) {
+        if let Ok(ty) = VecType::try_from(lhs.clone()).or(VecType::try_from(rhs.clone())) {
             let op = match op {
                 ArithmOp::Add => VecOp::Add,
                 ArithmOp::Sub => VecOp::Sub,